@@ -1,3 +1,49 @@
+use std::path::PathBuf;
+
+/// Event IDs written by `event_log.rs`; each gets a "%1" message so Event Viewer shows the text as is
+const EVENT_IDS: std::ops::RangeInclusive<u32> = 1001..=1004;
+
 fn main() {
+    if std::env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("windows") {
+        embed_event_messages();
+    }
     tauri_build::build()
 }
+
+/// Link a MESSAGETABLE resource into the exe, which the installer registers as the
+/// EventMessageFile of the "AVD Health Monitor" event source
+fn embed_event_messages() {
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo"));
+    let table = out_dir.join("event_messages.bin");
+    std::fs::write(&table, message_table()).expect("failed to write message table");
+
+    let rc = out_dir.join("event_messages.rc");
+    let path = table.display().to_string().replace('\\', "\\\\");
+    std::fs::write(&rc, format!("1 MESSAGETABLE \"{}\"\n", path)).expect("failed to write event_messages.rc");
+    embed_resource::compile(&rc, embed_resource::NONE)
+        .manifest_optional()
+        .expect("failed to compile event_messages.rc");
+}
+
+/// MESSAGE_RESOURCE_DATA with one block of Unicode "%1" entries, as mc.exe would generate
+fn message_table() -> Vec<u8> {
+    let text: Vec<u8> = "%1\r\n\0".encode_utf16().flat_map(u16::to_le_bytes).collect();
+    // MESSAGE_RESOURCE_ENTRY: length, flags (1 = Unicode) and the text padded to 4 bytes
+    let length = (4 + text.len() + 3) & !3;
+    let mut entry = Vec::with_capacity(length);
+    entry.extend((length as u16).to_le_bytes());
+    entry.extend(1u16.to_le_bytes());
+    entry.extend(&text);
+    entry.resize(length, 0);
+
+    // Block count, then LowId, HighId and the offset of the entries behind the single block
+    let mut data = Vec::new();
+    data.extend(1u32.to_le_bytes());
+    data.extend(EVENT_IDS.start().to_le_bytes());
+    data.extend(EVENT_IDS.end().to_le_bytes());
+    data.extend(16u32.to_le_bytes());
+    for _ in EVENT_IDS {
+        data.extend(&entry);
+    }
+    data
+}
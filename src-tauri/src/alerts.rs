@@ -0,0 +1,814 @@
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::event_log;
+use crate::fslogix::{FSLogixPath, ShareAccess};
+use crate::history::IncidentLog;
+use crate::logger;
+use crate::scheduler::{EndpointResult, FSLogixResult};
+use crate::settings::{AppConfig, Endpoint, FSLogixPathState};
+use crate::webhooks;
+
+/// Alert state of a single endpoint or FSLogix path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AlertState {
+    Ok,
+    /// Failing, but fewer consecutive checks than the alert threshold
+    Degraded,
+    /// Failed at least the threshold number of checks in a row
+    Alerting,
+    /// First healthy check after alerting
+    Recovered,
+}
+
+/// What is wrong with a check
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Problem {
+    /// Latency above the "good" threshold; `critical` when it is above "warning" as well
+    HighLatency { latency: f64, critical: bool },
+    Unreachable { error: String },
+}
+
+/// One evaluated result: an endpoint or FSLogix path and its problem, if any
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub id: String,
+    pub name: String,
+    /// Measured latency, None when the probe failed
+    pub latency: Option<f64>,
+    pub problem: Option<Problem>,
+}
+
+/// Consecutive-check threshold and repeat cooldown of one alert group
+#[derive(Debug, Clone, Copy)]
+pub struct AlertPolicy {
+    pub threshold: u32,
+    pub cooldown_ms: i64,
+}
+
+impl AlertPolicy {
+    fn new(threshold: u32, cooldown_minutes: u32) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            cooldown_ms: i64::from(cooldown_minutes) * 60_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertEventKind {
+    /// Entered the alerting state, or is still alerting after the cooldown
+    Alert,
+    Recovered,
+}
+
+/// A state change that may be worth a notification
+#[derive(Debug, Clone)]
+pub struct AlertEvent {
+    pub kind: AlertEventKind,
+    pub id: String,
+    pub name: String,
+    pub problem: Option<Problem>,
+    pub consecutive_failures: u32,
+    /// Milliseconds since the first failed check; for recoveries, the whole outage
+    pub outage_ms: i64,
+}
+
+/// Number of incidents kept per tracker, oldest are dropped first
+const MAX_INCIDENTS: usize = 100;
+
+/// Which group of checks an incident belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IncidentSource {
+    Endpoints,
+    Fslogix,
+}
+
+/// An endpoint or FSLogix path that alerted during an incident
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IncidentTarget {
+    pub id: String,
+    pub name: String,
+}
+
+/// A period during which at least one endpoint or path of a group was alerting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Incident {
+    pub id: String,
+    pub source: IncidentSource,
+    /// Unix milliseconds of the first failed check of the first affected target
+    pub started: i64,
+    /// Unix milliseconds of the last recovery, None while the incident is open
+    pub ended: Option<i64>,
+    pub affected: Vec<IncidentTarget>,
+    /// Highest latency measured on an affected target while the incident was open
+    pub worst_latency: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+struct AlertEntry {
+    state: AlertState,
+    consecutive_failures: u32,
+    /// Unix milliseconds of the first failed check of the current outage
+    failing_since: Option<i64>,
+    /// Unix milliseconds of the last alert event
+    last_alerted: Option<i64>,
+}
+
+/// Per-ID alert state machine: OK -> degraded -> alerting -> recovered -> OK
+/// Alerting IDs are grouped into incidents that close once every one of them has recovered.
+#[derive(Debug)]
+pub struct AlertTracker {
+    source: IncidentSource,
+    entries: HashMap<String, AlertEntry>,
+    incidents: VecDeque<Incident>,
+    /// Incidents opened or closed since the last `take_changed_incidents`
+    changed: Vec<Incident>,
+}
+
+impl AlertTracker {
+    pub fn new(source: IncidentSource) -> Self {
+        Self {
+            source,
+            entries: HashMap::new(),
+            incidents: VecDeque::new(),
+            changed: Vec::new(),
+        }
+    }
+
+    /// Put back incidents logged by an earlier run, oldest first
+    /// An incident left open is closed by the first round in which nothing is alerting.
+    pub fn restore(&mut self, incidents: impl IntoIterator<Item = Incident>) {
+        self.incidents.extend(incidents.into_iter().filter(|i| i.source == self.source));
+        while self.incidents.len() > MAX_INCIDENTS {
+            self.incidents.pop_front();
+        }
+    }
+
+    /// Snapshots of the incidents opened or closed since the last call, for the incident log
+    pub fn take_changed_incidents(&mut self) -> Vec<Incident> {
+        std::mem::take(&mut self.changed)
+    }
+
+    /// Feed one round of checks and return the resulting alert and recovery events
+    /// IDs missing from `checks` (muted, disabled or removed) are forgotten.
+    pub fn evaluate(&mut self, checks: &[Check], policy: AlertPolicy, now: i64) -> Vec<AlertEvent> {
+        self.entries.retain(|id, _| checks.iter().any(|c| &c.id == id));
+
+        let mut events = Vec::new();
+        for check in checks {
+            let entry = self.entries.entry(check.id.clone()).or_insert(AlertEntry {
+                state: AlertState::Ok,
+                consecutive_failures: 0,
+                failing_since: None,
+                last_alerted: None,
+            });
+            let outage_ms = entry.failing_since.map_or(0, |since| now - since);
+
+            let event_kind = match &check.problem {
+                None => {
+                    let was_alerting = entry.state == AlertState::Alerting;
+                    entry.state = if was_alerting { AlertState::Recovered } else { AlertState::Ok };
+                    entry.consecutive_failures = 0;
+                    entry.failing_since = None;
+                    entry.last_alerted = None;
+                    was_alerting.then_some(AlertEventKind::Recovered)
+                }
+                Some(_) => {
+                    entry.failing_since.get_or_insert(now);
+                    entry.consecutive_failures += 1;
+                    if entry.consecutive_failures < policy.threshold {
+                        entry.state = AlertState::Degraded;
+                        None
+                    } else {
+                        let cooled_down = entry.last_alerted.is_none_or(|last| now - last >= policy.cooldown_ms);
+                        entry.state = AlertState::Alerting;
+                        cooled_down.then(|| {
+                            entry.last_alerted = Some(now);
+                            AlertEventKind::Alert
+                        })
+                    }
+                }
+            };
+
+            if let Some(kind) = event_kind {
+                events.push(AlertEvent {
+                    kind,
+                    id: check.id.clone(),
+                    name: check.name.clone(),
+                    problem: check.problem.clone(),
+                    consecutive_failures: entry.consecutive_failures,
+                    outage_ms,
+                });
+            }
+        }
+
+        self.update_incident(checks, &events, now);
+        events
+    }
+
+    /// Open an incident on the first alert, add later alerting IDs to it and
+    /// close it once nothing is alerting anymore
+    fn update_incident(&mut self, checks: &[Check], events: &[AlertEvent], now: i64) {
+        let mut opened = false;
+        for event in events.iter().filter(|e| e.kind == AlertEventKind::Alert) {
+            if self.incidents.back().is_none_or(|i| i.ended.is_some()) {
+                let started = now - event.outage_ms;
+                let source = match self.source {
+                    IncidentSource::Endpoints => "endpoints",
+                    IncidentSource::Fslogix => "fslogix",
+                };
+                self.incidents.push_back(Incident {
+                    id: format!("{}-{}", source, started),
+                    source: self.source,
+                    started,
+                    ended: None,
+                    affected: Vec::new(),
+                    worst_latency: None,
+                });
+                if self.incidents.len() > MAX_INCIDENTS {
+                    self.incidents.pop_front();
+                }
+                opened = true;
+            }
+
+            let Some(incident) = self.incidents.back_mut() else { continue };
+            if !incident.affected.iter().any(|t| t.id == event.id) {
+                incident.affected.push(IncidentTarget {
+                    id: event.id.clone(),
+                    name: event.name.clone(),
+                });
+            }
+        }
+
+        let alerting = self.entries.values().any(|e| e.state == AlertState::Alerting);
+        let Some(incident) = self.incidents.back_mut().filter(|i| i.ended.is_none()) else { return };
+        for check in checks.iter().filter(|c| incident.affected.iter().any(|t| t.id == c.id)) {
+            if let Some(latency) = check.latency {
+                incident.worst_latency = Some(incident.worst_latency.map_or(latency, |worst| worst.max(latency)));
+            }
+        }
+        if !alerting {
+            incident.ended = Some(now);
+        }
+        if opened || !alerting {
+            self.changed.push(incident.clone());
+        }
+    }
+
+    /// Incidents of this tracker, oldest first
+    pub fn incidents(&self) -> impl Iterator<Item = &Incident> {
+        self.incidents.iter()
+    }
+
+    pub fn state(&self, id: &str) -> AlertState {
+        self.entries.get(id).map_or(AlertState::Ok, |e| e.state)
+    }
+
+    /// Current state of every tracked ID
+    pub fn states(&self) -> HashMap<String, AlertState> {
+        self.entries.keys().map(|id| (id.clone(), self.state(id))).collect()
+    }
+}
+
+/// Alert state of all monitored endpoints and FSLogix paths
+pub struct AlertEngine {
+    pub endpoints: AlertTracker,
+    pub fslogix: AlertTracker,
+    /// Where opened and closed incidents are persisted, None until `open_incident_log`
+    log: Option<IncidentLog>,
+}
+
+impl Default for AlertEngine {
+    fn default() -> Self {
+        Self {
+            endpoints: AlertTracker::new(IncidentSource::Endpoints),
+            fslogix: AlertTracker::new(IncidentSource::Fslogix),
+            log: None,
+        }
+    }
+}
+
+/// Alert states of all tracked endpoints and FSLogix paths, keyed by ID
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertStates {
+    pub endpoints: HashMap<String, AlertState>,
+    pub fslogix: HashMap<String, AlertState>,
+}
+
+impl AlertEngine {
+    pub fn states(&self) -> AlertStates {
+        AlertStates {
+            endpoints: self.endpoints.states(),
+            fslogix: self.fslogix.states(),
+        }
+    }
+
+    /// Load the incidents of earlier runs and persist new ones to the log from now on
+    pub fn open_incident_log(&mut self, log: IncidentLog) {
+        match log.load() {
+            Ok(incidents) => {
+                self.endpoints.restore(incidents.iter().cloned());
+                self.fslogix.restore(incidents);
+            }
+            Err(e) => logger::error("Alerts", &format!("Failed to load incidents: {}", e)),
+        }
+        self.log = Some(log);
+    }
+
+    /// Append the incidents opened or closed by the last evaluation to the log
+    fn save_incident_changes(&mut self) {
+        let mut changed = self.endpoints.take_changed_incidents();
+        changed.extend(self.fslogix.take_changed_incidents());
+        if let Some(log) = &self.log {
+            if let Err(e) = log.append(&changed) {
+                logger::error("Alerts", &format!("Failed to write incidents: {}", e));
+            }
+        }
+    }
+
+    /// Enforce the history retention on the incident log
+    pub fn prune_incident_log(&self, retention_days: u32, now: chrono::DateTime<chrono::Utc>) {
+        if let Some(log) = &self.log {
+            if let Err(e) = log.prune(retention_days, now) {
+                logger::error("Alerts", &format!("Failed to prune incidents: {}", e));
+            }
+        }
+    }
+
+    /// Incidents of both groups, newest first
+    pub fn incidents(&self) -> Vec<Incident> {
+        let mut incidents: Vec<Incident> = self.endpoints.incidents().chain(self.fslogix.incidents()).cloned().collect();
+        incidents.sort_by_key(|i| std::cmp::Reverse(i.started));
+        incidents
+    }
+}
+
+// Global alert engine, fed by the scheduler after every test cycle
+pub static ALERTS: Lazy<Mutex<AlertEngine>> = Lazy::new(|| Mutex::new(AlertEngine::default()));
+
+/// Turn endpoint results into checks, skipping muted endpoints
+/// High latency only counts for latency-critical endpoints; failures count for all.
+pub fn endpoint_checks(endpoints: &[Endpoint], results: &[EndpointResult], config: &AppConfig) -> Vec<Check> {
+    let good = f64::from(config.thresholds.good);
+    let warning = f64::from(config.thresholds.warning);
+
+    results
+        .iter()
+        .filter_map(|result| {
+            let endpoint = endpoints.iter().find(|e| e.id == result.endpoint_id)?;
+            if endpoint.muted == Some(true) {
+                return None;
+            }
+
+            let problem = if !result.success {
+                Some(Problem::Unreachable {
+                    error: result.error.as_ref().map(ToString::to_string).unwrap_or_default(),
+                })
+            } else if endpoint.latency_critical != Some(false) && result.latency > good {
+                Some(Problem::HighLatency {
+                    latency: result.latency,
+                    critical: result.latency > warning,
+                })
+            } else {
+                None
+            };
+
+            Some(Check {
+                id: endpoint.id.clone(),
+                name: endpoint.name.clone(),
+                latency: result.success.then_some(result.latency),
+                problem,
+            })
+        })
+        .collect()
+}
+
+/// Turn FSLogix results into checks, skipping muted paths
+/// A reachable host whose share can't be listed counts as a failure.
+pub fn fslogix_checks(paths: &[FSLogixPath], results: &[FSLogixResult], states: &[FSLogixPathState]) -> Vec<Check> {
+    results
+        .iter()
+        .filter_map(|result| {
+            let path = paths.iter().find(|p| p.id == result.path_id)?;
+            if states.iter().any(|s| s.id == path.id && s.muted) {
+                return None;
+            }
+
+            let share_error = result.share.as_ref().filter(|s| s.access != ShareAccess::Ok);
+            let problem = if !result.reachable {
+                Some(Problem::Unreachable {
+                    error: result.error.as_ref().map(ToString::to_string).unwrap_or_default(),
+                })
+            } else {
+                share_error.map(|share| Problem::Unreachable {
+                    error: share.detail.clone().unwrap_or_else(|| format!("{:?}", share.access)),
+                })
+            };
+
+            let kind = if path.path_type == "profile" { "Profile" } else { "ODFC" };
+            Some(Check {
+                id: path.id.clone(),
+                name: format!("{}: {}", kind, path.hostname),
+                latency: result.latency,
+                problem,
+            })
+        })
+        .collect()
+}
+
+/// Title and body of the notification for endpoint alert events, None when nothing is alerting
+pub fn endpoint_notification(events: &[AlertEvent]) -> Option<(String, String)> {
+    let alerts: Vec<&AlertEvent> = events.iter().filter(|e| e.kind == AlertEventKind::Alert).collect();
+    if alerts.is_empty() {
+        return None;
+    }
+
+    let unreachable = alerts.iter().any(|e| matches!(e.problem, Some(Problem::Unreachable { .. })));
+    let critical = alerts
+        .iter()
+        .any(|e| matches!(e.problem, Some(Problem::HighLatency { critical: true, .. })));
+    let title = if unreachable {
+        "Endpoints Unreachable"
+    } else if critical {
+        "Critical Latency Detected"
+    } else {
+        "High Latency Warning"
+    };
+
+    let lines: Vec<String> = alerts
+        .iter()
+        .map(|e| match &e.problem {
+            Some(Problem::HighLatency { latency, .. }) => format!("{}: {:.0}ms", e.name, latency),
+            Some(Problem::Unreachable { error }) if !error.is_empty() => format!("{}: unreachable ({})", e.name, error),
+            _ => format!("{}: unreachable", e.name),
+        })
+        .collect();
+    Some((title.to_string(), lines.join("\n")))
+}
+
+/// Title and body of the notification for FSLogix alert events, None when nothing is alerting
+pub fn fslogix_notification(events: &[AlertEvent]) -> Option<(String, String)> {
+    let lines: Vec<String> = events
+        .iter()
+        .filter(|e| e.kind == AlertEventKind::Alert)
+        .map(|e| format!("{} ({} failures)", e.name, e.consecutive_failures))
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+    Some((
+        "FSLogix Storage Unreachable".to_string(),
+        format!("The following storage paths are unreachable:\n{}", lines.join("\n")),
+    ))
+}
+
+/// Title and body of the notification pairing recovery events with their earlier alert, None without recoveries
+pub fn recovery_notification(title: &str, events: &[AlertEvent]) -> Option<(String, String)> {
+    let lines: Vec<String> = events
+        .iter()
+        .filter(|e| e.kind == AlertEventKind::Recovered)
+        .map(|e| format!("{}: recovered after {}", e.name, format_duration(e.outage_ms)))
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+    Some((title.to_string(), lines.join("\n")))
+}
+
+/// Short human-readable duration, e.g. "45s", "4m 30s" or "2h 5m"
+pub fn format_duration(ms: i64) -> String {
+    let seconds = ms.max(0) / 1000;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+/// Evaluate a round of endpoint results and notify about new or repeated alerts and recoveries
+pub fn process_endpoint_results(app: &tauri::AppHandle, endpoints: &[Endpoint], results: &[EndpointResult], config: &AppConfig) {
+    let checks = endpoint_checks(endpoints, results, config);
+    let policy = AlertPolicy::new(config.alert_threshold, config.alert_cooldown);
+    let now = chrono::Utc::now().timestamp_millis();
+    let events = {
+        let mut engine = ALERTS.lock();
+        let events = engine.endpoints.evaluate(&checks, policy, now);
+        engine.save_incident_changes();
+        events
+    };
+
+    log_events("endpoint", &events);
+    if config.notifications_enabled {
+        if let Some((title, body)) = endpoint_notification(&events) {
+            notify(app, &title, &body);
+        }
+        if let Some((title, body)) = recovery_notification("Endpoints Recovered", &events) {
+            notify(app, &title, &body);
+        }
+    }
+    if config.event_log_enabled {
+        event_log::record(IncidentSource::Endpoints, &events);
+    }
+    webhooks::dispatch(IncidentSource::Endpoints, &events);
+}
+
+/// Evaluate a round of FSLogix results and notify about new or repeated alerts and recoveries
+pub fn process_fslogix_results(
+    app: &tauri::AppHandle,
+    paths: &[FSLogixPath],
+    results: &[FSLogixResult],
+    states: &[FSLogixPathState],
+    config: &AppConfig,
+) {
+    let checks = fslogix_checks(paths, results, states);
+    let policy = AlertPolicy::new(config.fslogix_alert_threshold, config.fslogix_alert_cooldown);
+    let now = chrono::Utc::now().timestamp_millis();
+    let events = {
+        let mut engine = ALERTS.lock();
+        let events = engine.fslogix.evaluate(&checks, policy, now);
+        engine.save_incident_changes();
+        events
+    };
+
+    log_events("FSLogix path", &events);
+    if config.notifications_enabled {
+        if let Some((title, body)) = fslogix_notification(&events) {
+            notify(app, &title, &body);
+        }
+        if let Some((title, body)) = recovery_notification("FSLogix Storage Recovered", &events) {
+            notify(app, &title, &body);
+        }
+    }
+    if config.event_log_enabled {
+        event_log::record(IncidentSource::Fslogix, &events);
+    }
+    webhooks::dispatch(IncidentSource::Fslogix, &events);
+}
+
+fn log_events(kind: &str, events: &[AlertEvent]) {
+    for event in events {
+        let message = match event.kind {
+            AlertEventKind::Alert => format!(
+                "Alerting on {} {} after {} consecutive failed checks",
+                kind, event.id, event.consecutive_failures
+            ),
+            AlertEventKind::Recovered => format!(
+                "{} {} recovered after {}",
+                kind,
+                event.id,
+                format_duration(event.outage_ms)
+            ),
+        };
+        logger::info("Alerts", &message);
+    }
+}
+
+/// Show a desktop notification
+pub fn notify(app: &tauri::AppHandle, title: &str, body: &str) {
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        logger::error("Alerts", &format!("Failed to show notification: {}", e));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: AlertPolicy = AlertPolicy {
+        threshold: 3,
+        cooldown_ms: 300_000,
+    };
+
+    fn check(id: &str, failing: bool) -> Check {
+        Check {
+            id: id.to_string(),
+            name: id.to_uppercase(),
+            latency: (!failing).then_some(20.0),
+            problem: failing.then(|| Problem::Unreachable {
+                error: "Connection refused".to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_state_machine_transitions() {
+        let mut tracker = AlertTracker::new(IncidentSource::Endpoints);
+
+        assert!(tracker.evaluate(&[check("a", false)], POLICY, 0).is_empty());
+        assert_eq!(tracker.state("a"), AlertState::Ok);
+
+        assert!(tracker.evaluate(&[check("a", true)], POLICY, 1_000).is_empty());
+        assert!(tracker.evaluate(&[check("a", true)], POLICY, 2_000).is_empty());
+        assert_eq!(tracker.state("a"), AlertState::Degraded);
+
+        let events = tracker.evaluate(&[check("a", true)], POLICY, 3_000);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, AlertEventKind::Alert);
+        assert_eq!(events[0].consecutive_failures, 3);
+        assert_eq!(tracker.state("a"), AlertState::Alerting);
+
+        let events = tracker.evaluate(&[check("a", false)], POLICY, 4_000);
+        assert_eq!(events[0].kind, AlertEventKind::Recovered);
+        assert_eq!(tracker.state("a"), AlertState::Recovered);
+
+        assert!(tracker.evaluate(&[check("a", false)], POLICY, 5_000).is_empty());
+        assert_eq!(tracker.state("a"), AlertState::Ok);
+    }
+
+    #[test]
+    fn test_degraded_recovers_without_events() {
+        let mut tracker = AlertTracker::new(IncidentSource::Endpoints);
+        tracker.evaluate(&[check("a", true)], POLICY, 0);
+        assert!(tracker.evaluate(&[check("a", false)], POLICY, 1_000).is_empty());
+        assert_eq!(tracker.state("a"), AlertState::Ok);
+
+        // The count starts over after a healthy check
+        tracker.evaluate(&[check("a", true)], POLICY, 2_000);
+        tracker.evaluate(&[check("a", true)], POLICY, 3_000);
+        assert_eq!(tracker.state("a"), AlertState::Degraded);
+    }
+
+    #[test]
+    fn test_cooldown_limits_repeated_alerts() {
+        let mut tracker = AlertTracker::new(IncidentSource::Endpoints);
+        let policy = AlertPolicy::new(1, 5);
+
+        assert_eq!(tracker.evaluate(&[check("a", true)], policy, 0).len(), 1);
+        assert!(tracker.evaluate(&[check("a", true)], policy, 60_000).is_empty());
+        assert_eq!(tracker.state("a"), AlertState::Alerting);
+
+        let events = tracker.evaluate(&[check("a", true)], policy, 300_000);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].consecutive_failures, 3);
+    }
+
+    #[test]
+    fn test_missing_checks_are_forgotten() {
+        let mut tracker = AlertTracker::new(IncidentSource::Endpoints);
+        let policy = AlertPolicy::new(1, 5);
+        tracker.evaluate(&[check("a", true), check("b", true)], policy, 0);
+
+        // "a" is muted: no longer checked, so its state is dropped
+        tracker.evaluate(&[check("b", true)], policy, 1_000);
+        assert_eq!(tracker.state("a"), AlertState::Ok);
+
+        // Unmuted while still failing: alerts again right away
+        let events = tracker.evaluate(&[check("a", true), check("b", true)], policy, 2_000);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].id, "a");
+    }
+
+    fn endpoint(id: &str, muted: bool, latency_critical: Option<bool>) -> Endpoint {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "name": id.to_uppercase(),
+            "url": format!("{}.example.com", id),
+            "muted": muted,
+            "latencyCritical": latency_critical,
+        }))
+        .unwrap()
+    }
+
+    fn result(id: &str, latency: f64, success: bool) -> EndpointResult {
+        EndpointResult {
+            endpoint_id: id.to_string(),
+            latency,
+            timestamp: 0,
+            success,
+            error: (!success).then_some(crate::errors::ProbeError::ConnectionRefused),
+            timings: None,
+            duration_ms: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_endpoint_checks_honor_mute_and_latency_critical() {
+        let endpoints = vec![
+            endpoint("fast", false, None),
+            endpoint("slow", false, None),
+            endpoint("muted", true, None),
+            endpoint("reachability", false, Some(false)),
+            endpoint("down", false, Some(false)),
+        ];
+        let results = vec![
+            result("fast", 20.0, true),
+            result("slow", 250.0, true),
+            result("muted", 900.0, true),
+            result("reachability", 900.0, true),
+            result("down", 0.0, false),
+        ];
+        let checks = endpoint_checks(&endpoints, &results, &AppConfig::default());
+
+        assert_eq!(checks.len(), 4);
+        assert!(checks[0].problem.is_none());
+        assert_eq!(checks[1].problem, Some(Problem::HighLatency { latency: 250.0, critical: true }));
+        assert!(checks[2].problem.is_none());
+        assert!(matches!(checks[3].problem, Some(Problem::Unreachable { .. })));
+    }
+
+    #[test]
+    fn test_notifications() {
+        let mut tracker = AlertTracker::new(IncidentSource::Endpoints);
+        let policy = AlertPolicy::new(1, 5);
+        let slow = Check {
+            id: "slow".to_string(),
+            name: "Gateway".to_string(),
+            latency: Some(120.4),
+            problem: Some(Problem::HighLatency { latency: 120.4, critical: false }),
+        };
+        let events = tracker.evaluate(&[slow], policy, 0);
+        let (title, body) = endpoint_notification(&events).unwrap();
+        assert_eq!(title, "High Latency Warning");
+        assert_eq!(body, "Gateway: 120ms");
+
+        let events = tracker.evaluate(&[check("profile", true)], policy, 0);
+        let (title, body) = fslogix_notification(&events).unwrap();
+        assert_eq!(title, "FSLogix Storage Unreachable");
+        assert!(body.ends_with("PROFILE (1 failures)"));
+
+        // Recoveries alone don't produce an alert notification, but a paired recovery one
+        let events = tracker.evaluate(&[check("profile", false)], policy, 95_000);
+        assert!(fslogix_notification(&events).is_none());
+        let (title, body) = recovery_notification("FSLogix Storage Recovered", &events).unwrap();
+        assert_eq!(title, "FSLogix Storage Recovered");
+        assert_eq!(body, "PROFILE: recovered after 1m 35s");
+    }
+
+    #[test]
+    fn test_recovery_reports_outage_duration() {
+        let mut tracker = AlertTracker::new(IncidentSource::Endpoints);
+        tracker.evaluate(&[check("a", true)], POLICY, 10_000);
+        tracker.evaluate(&[check("a", true)], POLICY, 20_000);
+        let events = tracker.evaluate(&[check("a", true)], POLICY, 30_000);
+        assert_eq!(events[0].outage_ms, 20_000);
+
+        let events = tracker.evaluate(&[check("a", false)], POLICY, 40_000);
+        assert_eq!(events[0].kind, AlertEventKind::Recovered);
+        assert_eq!(events[0].outage_ms, 30_000);
+    }
+
+    #[test]
+    fn test_incident_lifecycle() {
+        let mut tracker = AlertTracker::new(IncidentSource::Endpoints);
+        let policy = AlertPolicy::new(1, 5);
+        let slow = |latency: f64| Check {
+            id: "b".to_string(),
+            name: "B".to_string(),
+            latency: Some(latency),
+            problem: Some(Problem::HighLatency { latency, critical: true }),
+        };
+
+        tracker.evaluate(&[check("a", true), check("b", false)], policy, 1_000);
+        tracker.evaluate(&[check("a", true), slow(300.0)], policy, 2_000);
+        tracker.evaluate(&[check("a", false), slow(450.0)], policy, 3_000);
+
+        let incidents: Vec<&Incident> = tracker.incidents().collect();
+        assert_eq!(incidents.len(), 1);
+        assert_eq!(incidents[0].id, "endpoints-1000");
+        assert_eq!(incidents[0].started, 1_000);
+        assert_eq!(incidents[0].ended, None);
+        let affected: Vec<&str> = incidents[0].affected.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(affected, ["a", "b"]);
+        assert_eq!(incidents[0].worst_latency, Some(450.0));
+
+        // Closes once the last affected target recovers
+        tracker.evaluate(&[check("a", false), check("b", false)], policy, 4_000);
+        assert_eq!(tracker.incidents().next().unwrap().ended, Some(4_000));
+
+        // Opening and closing are logged, updates in between are not
+        let changed = tracker.take_changed_incidents();
+        assert_eq!(changed.len(), 2);
+        assert_eq!((changed[0].ended, changed[1].ended), (None, Some(4_000)));
+        assert!(tracker.take_changed_incidents().is_empty());
+
+        // The next outage opens a new incident
+        tracker.evaluate(&[check("a", true), check("b", false)], policy, 5_000);
+        assert_eq!(tracker.incidents().count(), 2);
+        assert_eq!(tracker.incidents().last().unwrap().started, 5_000);
+
+        // Nothing left to probe (all disabled) - states are dropped and the incident closes
+        assert!(tracker.evaluate(&[], policy, 6_000).is_empty());
+        assert!(tracker.states().is_empty());
+        assert_eq!(tracker.incidents().last().unwrap().ended, Some(6_000));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(45_000), "45s");
+        assert_eq!(format_duration(270_000), "4m 30s");
+        assert_eq!(format_duration(7_500_000), "2h 5m");
+    }
+}
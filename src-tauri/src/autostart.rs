@@ -3,6 +3,7 @@ use winreg::enums::*;
 #[cfg(target_os = "windows")]
 use winreg::RegKey;
 
+#[cfg(target_os = "windows")]
 const APP_NAME: &str = "AVDHealthMonitor";
 
 #[cfg(target_os = "windows")]
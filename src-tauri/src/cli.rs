@@ -0,0 +1,964 @@
+use serde::Serialize;
+
+use crate::dns::{self, DnsProbeResult};
+use crate::errors::ProbeError;
+use crate::fslogix::{self, ShareAccess, ShareCheck, StorageExposure, StorageResolution};
+use crate::latency::{self, ProbeOptions, ProbeResult};
+use crate::logger;
+use crate::settings::{apply_fslogix_path_states, load_endpoint_file, load_settings, resolve_endpoints, AppMode, Endpoint};
+use crate::traceroute::{self, TracerouteReport, DEFAULT_MAX_HOPS, MAX_HOPS_LIMIT};
+
+/// Exit code when every required endpoint and FSLogix path is reachable
+pub const EXIT_OK: i32 = 0;
+/// Exit code when at least one required endpoint or FSLogix path failed
+pub const EXIT_FAILED: i32 = 1;
+/// Exit code for invalid arguments or unreadable configuration
+pub const EXIT_USAGE: i32 = 2;
+
+const USAGE: &str = "\
+Usage: avd-health-monitor check [options]
+       avd-health-monitor traceroute <host> [options]
+       avd-health-monitor dns <host> [options]
+
+check: run all enabled endpoint and FSLogix tests once and print a report.
+
+  --mode <sessionhost|enduser>  Endpoint set to test (default: mode from settings)
+  --format <text|json>          Output format (default: text)
+  --no-fslogix                  Skip FSLogix storage path tests
+  --share-check                 Also open and list each FSLogix share (default: from settings)
+  -h, --help                    Show this help
+
+traceroute: trace the network path to a host with TCP SYN probes to its port.
+
+  --port <port>                 Destination port (default: 443)
+  --max-hops <1-64>             Maximum number of hops (default: 30)
+  --no-dns                      Don't resolve hop addresses to names
+  --format <text|json>          Output format (default: text)
+
+dns: resolve a host and show the CNAME chain, records, TTLs and the resolver that answered.
+
+  --server <ip[:port]>          Ask this DNS server instead of the system resolvers
+  --format <text|json>          Output format (default: text)
+
+Exit codes:
+  0  all required endpoints and FSLogix paths are reachable / destination reached / name resolved
+  1  at least one required endpoint or FSLogix path failed / destination not reached or unresolvable / no addresses
+  2  invalid arguments or configuration error";
+
+/// Report output format
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+/// Options of the `check` subcommand
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckOptions {
+    /// None uses the mode from the settings file
+    pub mode: Option<AppMode>,
+    pub format: OutputFormat,
+    pub fslogix: bool,
+    /// Open and list each FSLogix share, in addition to the setting
+    pub share_check: bool,
+}
+
+/// Options of the `traceroute` subcommand
+#[derive(Debug, Clone, PartialEq)]
+pub struct TracerouteOptions {
+    pub host: String,
+    pub port: u16,
+    pub max_hops: u8,
+    pub resolve_names: bool,
+    pub format: OutputFormat,
+}
+
+/// Options of the `dns` subcommand
+#[derive(Debug, Clone, PartialEq)]
+pub struct DnsOptions {
+    pub host: String,
+    /// None queries the system resolvers
+    pub server: Option<String>,
+    pub format: OutputFormat,
+}
+
+/// Parsed command line
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Check(CheckOptions),
+    Traceroute(TracerouteOptions),
+    Dns(DnsOptions),
+    Help,
+}
+
+/// Parse command line arguments (without the program name)
+/// Returns Ok(None) if no subcommand was given and the GUI should start.
+pub fn parse_args(args: &[String]) -> Result<Option<Command>, String> {
+    let Some(subcommand) = args.first() else {
+        return Ok(None);
+    };
+
+    match subcommand.as_str() {
+        "check" => parse_check(&args[1..]).map(Some),
+        "traceroute" => parse_traceroute(&args[1..]).map(Some),
+        "dns" => parse_dns(&args[1..]).map(Some),
+        "help" | "-h" | "--help" => Ok(Some(Command::Help)),
+        // Flags like those passed by the OS or Tauri on launch are left to the GUI
+        other if other.starts_with('-') => Ok(None),
+        other => Err(format!("Unknown command: {}", other)),
+    }
+}
+
+fn parse_check(args: &[String]) -> Result<Command, String> {
+    let mut options = CheckOptions {
+        mode: None,
+        format: OutputFormat::Text,
+        fslogix: true,
+        share_check: false,
+    };
+
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--mode" => {
+                let value = rest.next().ok_or("--mode requires a value")?;
+                options.mode = Some(parse_mode(value)?);
+            }
+            "--format" => {
+                let value = rest.next().ok_or("--format requires a value")?;
+                options.format = parse_format(value)?;
+            }
+            "--no-fslogix" => options.fslogix = false,
+            "--share-check" => options.share_check = true,
+            "-h" | "--help" => return Ok(Command::Help),
+            other => return Err(format!("Unknown option: {}", other)),
+        }
+    }
+
+    Ok(Command::Check(options))
+}
+
+fn parse_traceroute(args: &[String]) -> Result<Command, String> {
+    let mut host = None;
+    let mut options = TracerouteOptions {
+        host: String::new(),
+        port: 443,
+        max_hops: DEFAULT_MAX_HOPS,
+        resolve_names: true,
+        format: OutputFormat::Text,
+    };
+
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--port" => {
+                let value = rest.next().ok_or("--port requires a value")?;
+                options.port = value
+                    .parse()
+                    .ok()
+                    .filter(|port| *port > 0)
+                    .ok_or_else(|| format!("Invalid port '{}'", value))?;
+            }
+            "--max-hops" => {
+                let value = rest.next().ok_or("--max-hops requires a value")?;
+                options.max_hops = value
+                    .parse()
+                    .ok()
+                    .filter(|hops| (1..=MAX_HOPS_LIMIT).contains(hops))
+                    .ok_or_else(|| format!("Invalid hop count '{}', expected 1-{}", value, MAX_HOPS_LIMIT))?;
+            }
+            "--no-dns" => options.resolve_names = false,
+            "--format" => {
+                let value = rest.next().ok_or("--format requires a value")?;
+                options.format = parse_format(value)?;
+            }
+            "-h" | "--help" => return Ok(Command::Help),
+            other if other.starts_with('-') => return Err(format!("Unknown option: {}", other)),
+            other if host.is_none() => host = Some(other.to_string()),
+            other => return Err(format!("Unexpected argument: {}", other)),
+        }
+    }
+
+    options.host = host.ok_or("traceroute requires a host")?;
+    Ok(Command::Traceroute(options))
+}
+
+fn parse_dns(args: &[String]) -> Result<Command, String> {
+    let mut host = None;
+    let mut options = DnsOptions {
+        host: String::new(),
+        server: None,
+        format: OutputFormat::Text,
+    };
+
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--server" => {
+                let value = rest.next().ok_or("--server requires a value")?;
+                options.server = Some(value.clone());
+            }
+            "--format" => {
+                let value = rest.next().ok_or("--format requires a value")?;
+                options.format = parse_format(value)?;
+            }
+            "-h" | "--help" => return Ok(Command::Help),
+            other if other.starts_with('-') => return Err(format!("Unknown option: {}", other)),
+            other if host.is_none() => host = Some(other.to_string()),
+            other => return Err(format!("Unexpected argument: {}", other)),
+        }
+    }
+
+    options.host = host.ok_or("dns requires a host")?;
+    Ok(Command::Dns(options))
+}
+
+fn parse_mode(value: &str) -> Result<AppMode, String> {
+    match value.to_lowercase().as_str() {
+        "sessionhost" => Ok(AppMode::SessionHost),
+        "enduser" => Ok(AppMode::EndUser),
+        _ => Err(format!("Invalid mode '{}', expected sessionhost or enduser", value)),
+    }
+}
+
+fn parse_format(value: &str) -> Result<OutputFormat, String> {
+    match value.to_lowercase().as_str() {
+        "text" => Ok(OutputFormat::Text),
+        "json" => Ok(OutputFormat::Json),
+        _ => Err(format!("Invalid format '{}', expected text or json", value)),
+    }
+}
+
+/// Result of a single endpoint check
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointCheck {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub port: u16,
+    pub protocol: String,
+    pub category: Option<String>,
+    pub required: bool,
+    pub success: bool,
+    pub latency_ms: Option<f64>,
+    pub error: Option<ProbeError>,
+    /// Phase-level timing breakdown, None if the probe failed
+    pub timings: Option<ProbeResult>,
+}
+
+/// Result of a single FSLogix path check
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FSLogixCheck {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub path_type: String,
+    pub path: String,
+    pub hostname: String,
+    pub port: u16,
+    /// Muted in the app; a muted path doesn't fail the check
+    pub muted: bool,
+    pub reachable: bool,
+    pub latency_ms: Option<f64>,
+    pub error: Option<ProbeError>,
+    /// Private/public classification of the storage host
+    pub resolution: StorageResolution,
+    /// Share existence and listability, when share checks are enabled
+    pub share: Option<ShareCheck>,
+}
+
+/// Aggregated counts of a check run
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckSummary {
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub required_failed: usize,
+    pub fslogix_failed: usize,
+    pub healthy: bool,
+}
+
+/// Full report of a check run
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckReport {
+    pub mode: AppMode,
+    /// RFC 3339 timestamp of the run
+    pub timestamp: String,
+    pub endpoints: Vec<EndpointCheck>,
+    pub fslogix: Vec<FSLogixCheck>,
+    pub summary: CheckSummary,
+}
+
+impl CheckSummary {
+    fn from_results(endpoints: &[EndpointCheck], fslogix: &[FSLogixCheck]) -> Self {
+        let passed = endpoints.iter().filter(|e| e.success).count();
+        let required_failed = endpoints.iter().filter(|e| e.required && !e.success).count();
+        let fslogix_failed = fslogix
+            .iter()
+            .filter(|p| !p.muted)
+            .filter(|p| !p.reachable || p.share.as_ref().is_some_and(|s| s.access != ShareAccess::Ok))
+            .count();
+
+        Self {
+            total: endpoints.len(),
+            passed,
+            failed: endpoints.len() - passed,
+            required_failed,
+            fslogix_failed,
+            healthy: required_failed == 0 && fslogix_failed == 0,
+        }
+    }
+
+    fn exit_code(&self) -> i32 {
+        if self.healthy {
+            EXIT_OK
+        } else {
+            EXIT_FAILED
+        }
+    }
+}
+
+/// Run the CLI if a subcommand was given
+/// Returns None if the GUI should start instead, otherwise the process exit code.
+pub fn run(args: &[String]) -> Option<i32> {
+    let command = match parse_args(args) {
+        Ok(None) => return None,
+        Ok(Some(command)) => command,
+        Err(e) => {
+            attach_console();
+            eprintln!("{}\n\n{}", e, USAGE);
+            return Some(EXIT_USAGE);
+        }
+    };
+
+    attach_console();
+
+    match command {
+        Command::Help => {
+            println!("{}", USAGE);
+            Some(EXIT_OK)
+        }
+        Command::Check(options) => Some(run_check(&options)),
+        Command::Traceroute(options) => Some(run_traceroute(&options)),
+        Command::Dns(options) => Some(run_dns(&options)),
+    }
+}
+
+fn run_check(options: &CheckOptions) -> i32 {
+    let settings = match load_settings() {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!("Failed to load settings: {}", e);
+            return EXIT_USAGE;
+        }
+    };
+
+    if let Err(e) = logger::init(settings.config.retention_days) {
+        eprintln!("Failed to initialize logging: {}", e);
+    }
+
+    let mode = options.mode.clone().unwrap_or_else(|| settings.config.mode.clone());
+    let endpoint_file = match load_endpoint_file(None, &mode) {
+        Ok(file) => file,
+        Err(e) => {
+            eprintln!("Failed to load endpoints: {}", e);
+            return EXIT_USAGE;
+        }
+    };
+
+    let endpoints: Vec<Endpoint> = resolve_endpoints(&endpoint_file, &settings)
+        .into_iter()
+        .filter(|e| e.enabled)
+        .collect();
+    let fslogix_paths = if options.fslogix && mode == AppMode::SessionHost {
+        let mut paths = fslogix::get_fslogix_paths();
+        apply_fslogix_path_states(&mut paths);
+        paths
+    } else {
+        Vec::new()
+    };
+
+    let probe_options = ProbeOptions::from_config(&settings.config);
+    let share_check = options.share_check || settings.config.fslogix_share_check;
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("Failed to start async runtime: {}", e);
+            return EXIT_USAGE;
+        }
+    };
+
+    let (endpoint_checks, fslogix_checks) = runtime.block_on(async {
+        tokio::join!(
+            check_endpoints(endpoints, probe_options),
+            check_fslogix_paths(fslogix_paths, probe_options, share_check)
+        )
+    });
+
+    let summary = CheckSummary::from_results(&endpoint_checks, &fslogix_checks);
+    let exit_code = summary.exit_code();
+    let report = CheckReport {
+        mode,
+        timestamp: chrono::Local::now().to_rfc3339(),
+        endpoints: endpoint_checks,
+        fslogix: fslogix_checks,
+        summary,
+    };
+
+    logger::info(
+        "CLI",
+        &format!(
+            "Check finished: {}/{} endpoints passed, {} required failed, {} FSLogix paths failed",
+            report.summary.passed,
+            report.summary.total,
+            report.summary.required_failed,
+            report.summary.fslogix_failed
+        ),
+    );
+
+    match options.format {
+        OutputFormat::Json => match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Failed to serialize report: {}", e);
+                return EXIT_USAGE;
+            }
+        },
+        OutputFormat::Text => print!("{}", format_text(&report)),
+    }
+
+    exit_code
+}
+
+fn run_traceroute(options: &TracerouteOptions) -> i32 {
+    let config = load_settings().map(|s| s.config).unwrap_or_default();
+    if let Err(e) = logger::init(config.retention_days) {
+        eprintln!("Failed to initialize logging: {}", e);
+    }
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("Failed to start async runtime: {}", e);
+            return EXIT_USAGE;
+        }
+    };
+
+    let probe_options = ProbeOptions::from_config(&config);
+    let result = runtime.block_on(traceroute::traceroute(
+        &options.host,
+        options.port,
+        options.max_hops,
+        options.resolve_names,
+        &probe_options,
+    ));
+    let report = match result {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Traceroute to {} failed: {} ({})", options.host, e, e.code());
+            return EXIT_FAILED;
+        }
+    };
+
+    logger::info(
+        "CLI",
+        &format!(
+            "Traceroute to {}:{} finished: {} hops, destination {}",
+            report.host,
+            report.port,
+            report.hops.len(),
+            if report.reached { "reached" } else { "not reached" }
+        ),
+    );
+
+    match options.format {
+        OutputFormat::Json => match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Failed to serialize report: {}", e);
+                return EXIT_USAGE;
+            }
+        },
+        OutputFormat::Text => print!("{}", format_traceroute(&report)),
+    }
+
+    if report.reached {
+        EXIT_OK
+    } else {
+        EXIT_FAILED
+    }
+}
+
+fn run_dns(options: &DnsOptions) -> i32 {
+    let config = load_settings().map(|s| s.config).unwrap_or_default();
+    if let Err(e) = logger::init(config.retention_days) {
+        eprintln!("Failed to initialize logging: {}", e);
+    }
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("Failed to start async runtime: {}", e);
+            return EXIT_USAGE;
+        }
+    };
+
+    let probe_options = ProbeOptions::from_config(&config);
+    let result = runtime.block_on(dns::diagnose_dns(&options.host, options.server.as_deref(), &probe_options));
+    let result = match result {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("DNS lookup of {} failed: {} ({})", options.host, e, e.code());
+            return EXIT_FAILED;
+        }
+    };
+
+    logger::info(
+        "CLI",
+        &format!(
+            "DNS lookup of {} via {}: {}, {} addresses",
+            result.host,
+            result.server,
+            result.response_code,
+            result.addresses.len()
+        ),
+    );
+
+    match options.format {
+        OutputFormat::Json => match serde_json::to_string_pretty(&result) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Failed to serialize report: {}", e);
+                return EXIT_USAGE;
+            }
+        },
+        OutputFormat::Text => print!("{}", format_dns(&result)),
+    }
+
+    if result.addresses.is_empty() {
+        EXIT_FAILED
+    } else {
+        EXIT_OK
+    }
+}
+
+async fn check_endpoints(endpoints: Vec<Endpoint>, options: ProbeOptions) -> Vec<EndpointCheck> {
+    let handles: Vec<_> = endpoints
+        .into_iter()
+        .map(|endpoint| {
+            tokio::spawn(async move {
+                let port = endpoint.port.unwrap_or(443);
+                let protocol = endpoint.protocol.clone().unwrap_or_else(|| "tcp".to_string());
+                let options = options.for_endpoint(&endpoint);
+                let result = latency::probe_endpoint(&endpoint.url, port, &protocol, &options).await;
+
+                EndpointCheck {
+                    id: endpoint.id,
+                    name: endpoint.name,
+                    url: endpoint.url,
+                    port,
+                    protocol,
+                    category: endpoint.category,
+                    required: endpoint.required.unwrap_or(false),
+                    success: result.is_ok(),
+                    latency_ms: result.as_ref().ok().map(|probe| probe.latency_ms),
+                    error: result.as_ref().err().cloned(),
+                    timings: result.ok(),
+                }
+            })
+        })
+        .collect();
+
+    let mut checks = Vec::with_capacity(handles.len());
+    for handle in handles {
+        if let Ok(check) = handle.await {
+            checks.push(check);
+        }
+    }
+    checks
+}
+
+async fn check_fslogix_paths(
+    paths: Vec<fslogix::FSLogixPath>,
+    options: ProbeOptions,
+    share_check: bool,
+) -> Vec<FSLogixCheck> {
+    let handles: Vec<_> = paths
+        .into_iter()
+        .map(|path| {
+            tokio::spawn(async move {
+                let check = fslogix::check_path(&path, &options, share_check).await;
+                FSLogixCheck {
+                    id: path.id,
+                    path_type: path.path_type,
+                    path: path.path,
+                    hostname: path.hostname,
+                    port: path.port,
+                    muted: path.muted.unwrap_or(false),
+                    reachable: check.reachable,
+                    latency_ms: check.latency_ms,
+                    error: check.error,
+                    resolution: check.resolution,
+                    share: check.share,
+                }
+            })
+        })
+        .collect();
+
+    let mut checks = Vec::with_capacity(handles.len());
+    for handle in handles {
+        if let Ok(check) = handle.await {
+            checks.push(check);
+        }
+    }
+    checks
+}
+
+/// Render the report as human-readable text
+fn format_text(report: &CheckReport) -> String {
+    let mut out = String::new();
+    let mode = match report.mode {
+        AppMode::SessionHost => "Session Host",
+        AppMode::EndUser => "End User",
+    };
+    out.push_str(&format!("AVD Health Monitor - {} check ({})\n\n", mode, report.timestamp));
+
+    out.push_str("Endpoints\n");
+    for endpoint in &report.endpoints {
+        let status = if endpoint.success { "OK  " } else { "FAIL" };
+        let required = if endpoint.required { " (required)" } else { "" };
+        let detail = match (&endpoint.latency_ms, &endpoint.error) {
+            (Some(latency), _) => format!("{:.0} ms", latency),
+            (None, Some(error)) => format!("{} ({})", error, error.code()),
+            (None, None) => String::new(),
+        };
+        out.push_str(&format!(
+            "  [{}] {} - {}:{}{}  {}\n",
+            status, endpoint.name, endpoint.url, endpoint.port, required, detail
+        ));
+    }
+
+    if !report.fslogix.is_empty() {
+        out.push_str("\nFSLogix storage\n");
+        for path in &report.fslogix {
+            let status = if path.reachable { "OK  " } else { "FAIL" };
+            let detail = match (&path.latency_ms, &path.error) {
+                (Some(latency), _) => format!("{:.0} ms", latency),
+                (None, Some(error)) => format!("{} ({})", error, error.code()),
+                (None, None) => String::new(),
+            };
+            let exposure = match path.resolution.exposure {
+                StorageExposure::Private => "private",
+                StorageExposure::PublicAzure => "public Azure",
+                StorageExposure::Public => "public",
+                StorageExposure::Unresolvable => "unresolvable",
+            };
+            let muted = if path.muted { " (muted)" } else { "" };
+            out.push_str(&format!(
+                "  [{}] {} {} ({}){}  {}\n",
+                status, path.path_type, path.path, exposure, muted, detail
+            ));
+            if let Some(warning) = &path.resolution.warning {
+                out.push_str(&format!("         WARNING: {}\n", warning));
+            }
+            if let Some(share) = &path.share {
+                let access = match share.access {
+                    ShareAccess::Ok => "share OK",
+                    ShareAccess::HostUnreachable => "host unreachable",
+                    ShareAccess::ShareMissing => "share missing",
+                    ShareAccess::AccessDenied => "access denied",
+                    ShareAccess::Error => "share check failed",
+                    ShareAccess::Pending => "share check still pending",
+                };
+                match &share.detail {
+                    Some(detail) => out.push_str(&format!("         Share: {} - {}\n", access, detail)),
+                    None => out.push_str(&format!("         Share: {}\n", access)),
+                }
+            }
+        }
+    }
+
+    let summary = &report.summary;
+    out.push_str(&format!(
+        "\n{}/{} endpoints passed, {} required failed",
+        summary.passed, summary.total, summary.required_failed
+    ));
+    if !report.fslogix.is_empty() {
+        out.push_str(&format!(", {} FSLogix paths failed", summary.fslogix_failed));
+    }
+    out.push_str(&format!(" - {}\n", if summary.healthy { "HEALTHY" } else { "UNHEALTHY" }));
+    out
+}
+
+/// Render a traceroute as text in the familiar tracert layout
+fn format_traceroute(report: &TracerouteReport) -> String {
+    let method = match report.method {
+        traceroute::TracerouteMethod::Tcp => "TCP SYN",
+        traceroute::TracerouteMethod::Icmp => "ICMP echo",
+    };
+    let mut out = format!(
+        "Traceroute to {} ({}) port {}, {}, {} hops max ({})\n\n",
+        report.host, report.address, report.port, method, report.max_hops, report.timestamp
+    );
+
+    for hop in &report.hops {
+        let line = match (&hop.address, &hop.hostname) {
+            (None, _) => "*".to_string(),
+            (Some(address), Some(hostname)) => format!("{} [{}]", hostname, address),
+            (Some(address), None) => address.clone(),
+        };
+        let rtt = hop.rtt_ms.map(|rtt| format!("  {:.1} ms", rtt)).unwrap_or_default();
+        out.push_str(&format!("{:>3}  {}{}\n", hop.ttl, line, rtt));
+    }
+
+    if report.reached {
+        out.push_str(&format!("\nDestination reached in {} hops\n", report.hops.len()));
+    } else {
+        out.push_str("\nDestination not reached\n");
+    }
+    if let Some(note) = &report.note {
+        out.push_str(&format!("Note: {}\n", note));
+    }
+    out
+}
+
+/// Render a DNS diagnostic as text
+fn format_dns(result: &DnsProbeResult) -> String {
+    let source = if result.system_resolver { "system resolver" } else { "explicit server" };
+    let mut out = format!(
+        "DNS lookup of {} via {} ({}) - {} in {:.0} ms\n",
+        result.host, result.server, source, result.response_code, result.resolution_ms
+    );
+    if !result.failed_servers.is_empty() {
+        out.push_str(&format!("No answer from: {}\n", result.failed_servers.join(", ")));
+    }
+
+    if result.cname_chain.len() > 1 {
+        out.push_str(&format!("\nCNAME chain: {}\n", result.cname_chain.join(" -> ")));
+    }
+    if !result.records.is_empty() {
+        out.push_str("\nRecords\n");
+        for record in &result.records {
+            out.push_str(&format!(
+                "  {:<6}{}  {}  (TTL {}s)\n",
+                record.record_type, record.name, record.value, record.ttl
+            ));
+        }
+    }
+    out
+}
+
+/// Release builds use the Windows GUI subsystem, so attach to the console
+/// of the calling shell to make stdout/stderr visible
+#[cfg(target_os = "windows")]
+fn attach_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn attach_console() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn endpoint(id: &str, required: bool, success: bool) -> EndpointCheck {
+        EndpointCheck {
+            id: id.to_string(),
+            name: id.to_string(),
+            url: format!("{}.example.com", id),
+            port: 443,
+            protocol: "tcp".to_string(),
+            category: None,
+            required,
+            success,
+            latency_ms: success.then_some(20.0),
+            error: (!success).then_some(ProbeError::ConnectTimeout),
+            timings: None,
+        }
+    }
+
+    #[test]
+    fn test_no_args_starts_gui() {
+        assert_eq!(parse_args(&[]), Ok(None));
+        assert_eq!(parse_args(&args(&["--autostart"])), Ok(None));
+    }
+
+    #[test]
+    fn test_parse_check_options() {
+        let command = parse_args(&args(&["check", "--mode", "sessionhost", "--format", "json"])).unwrap();
+        assert_eq!(
+            command,
+            Some(Command::Check(CheckOptions {
+                mode: Some(AppMode::SessionHost),
+                format: OutputFormat::Json,
+                fslogix: true,
+                share_check: false,
+            }))
+        );
+
+        let command = parse_args(&args(&["check", "--no-fslogix", "--mode", "EndUser", "--share-check"])).unwrap();
+        assert_eq!(
+            command,
+            Some(Command::Check(CheckOptions {
+                mode: Some(AppMode::EndUser),
+                format: OutputFormat::Text,
+                fslogix: false,
+                share_check: true,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_args() {
+        assert!(parse_args(&args(&["status"])).is_err());
+        assert!(parse_args(&args(&["check", "--mode"])).is_err());
+        assert!(parse_args(&args(&["check", "--mode", "server"])).is_err());
+        assert!(parse_args(&args(&["check", "--format", "xml"])).is_err());
+        assert!(parse_args(&args(&["check", "--verbose"])).is_err());
+        assert_eq!(parse_args(&args(&["check", "--help"])), Ok(Some(Command::Help)));
+    }
+
+    #[test]
+    fn test_parse_traceroute_options() {
+        let command = parse_args(&args(&["traceroute", "rdweb.wvd.microsoft.com"])).unwrap();
+        assert_eq!(
+            command,
+            Some(Command::Traceroute(TracerouteOptions {
+                host: "rdweb.wvd.microsoft.com".to_string(),
+                port: 443,
+                max_hops: DEFAULT_MAX_HOPS,
+                resolve_names: true,
+                format: OutputFormat::Text,
+            }))
+        );
+
+        let command = parse_args(&args(&[
+            "traceroute", "--port", "445", "storage.file.core.windows.net", "--max-hops", "15", "--no-dns", "--format",
+            "json",
+        ]))
+        .unwrap();
+        assert_eq!(
+            command,
+            Some(Command::Traceroute(TracerouteOptions {
+                host: "storage.file.core.windows.net".to_string(),
+                port: 445,
+                max_hops: 15,
+                resolve_names: false,
+                format: OutputFormat::Json,
+            }))
+        );
+
+        assert!(parse_args(&args(&["traceroute"])).is_err());
+        assert!(parse_args(&args(&["traceroute", "a.example", "b.example"])).is_err());
+        assert!(parse_args(&args(&["traceroute", "a.example", "--port", "0"])).is_err());
+        assert!(parse_args(&args(&["traceroute", "a.example", "--max-hops", "65"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_dns_options() {
+        let command = parse_args(&args(&["dns", "storage.file.core.windows.net", "--server", "168.63.129.16"])).unwrap();
+        assert_eq!(
+            command,
+            Some(Command::Dns(DnsOptions {
+                host: "storage.file.core.windows.net".to_string(),
+                server: Some("168.63.129.16".to_string()),
+                format: OutputFormat::Text,
+            }))
+        );
+
+        assert!(parse_args(&args(&["dns"])).is_err());
+        assert!(parse_args(&args(&["dns", "a.example", "--server"])).is_err());
+    }
+
+    #[test]
+    fn test_summary_fails_only_on_required_endpoints() {
+        let endpoints = vec![endpoint("a", true, true), endpoint("b", false, false)];
+        let summary = CheckSummary::from_results(&endpoints, &[]);
+        assert_eq!(summary.passed, 1);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.required_failed, 0);
+        assert_eq!(summary.exit_code(), EXIT_OK);
+
+        let endpoints = vec![endpoint("a", true, false), endpoint("b", false, true)];
+        let summary = CheckSummary::from_results(&endpoints, &[]);
+        assert_eq!(summary.required_failed, 1);
+        assert_eq!(summary.exit_code(), EXIT_FAILED);
+    }
+
+    #[test]
+    fn test_summary_fails_on_unreachable_fslogix_path() {
+        let path = FSLogixCheck {
+            id: "fslogix-profile-0".to_string(),
+            path_type: "profile".to_string(),
+            path: r"\\storage.file.core.windows.net\profiles".to_string(),
+            hostname: "storage.file.core.windows.net".to_string(),
+            port: 445,
+            muted: false,
+            reachable: false,
+            latency_ms: None,
+            error: Some(ProbeError::ConnectionRefused),
+            resolution: StorageResolution {
+                exposure: StorageExposure::Unresolvable,
+                addresses: Vec::new(),
+                private_link: false,
+                warning: None,
+            },
+            share: None,
+        };
+        let summary = CheckSummary::from_results(&[endpoint("a", true, true)], std::slice::from_ref(&path));
+        assert_eq!(summary.fslogix_failed, 1);
+        assert_eq!(summary.exit_code(), EXIT_FAILED);
+
+        // A reachable host whose share can't be listed still counts as failed
+        let denied = FSLogixCheck {
+            reachable: true,
+            latency_ms: Some(12.0),
+            error: None,
+            share: Some(ShareCheck {
+                access: ShareAccess::AccessDenied,
+                detail: Some("Access is denied.".to_string()),
+                duration_ms: 30.0,
+            }),
+            ..path.clone()
+        };
+        let summary = CheckSummary::from_results(&[endpoint("a", true, true)], &[denied]);
+        assert_eq!(summary.fslogix_failed, 1);
+
+        // Muted paths don't fail the check, like in the app
+        let muted = FSLogixCheck { muted: true, ..path };
+        let summary = CheckSummary::from_results(&[endpoint("a", true, true)], &[muted]);
+        assert_eq!(summary.fslogix_failed, 0);
+        assert_eq!(summary.exit_code(), EXIT_OK);
+    }
+
+    #[test]
+    fn test_json_report_shape() {
+        let endpoints = vec![endpoint("a", true, false)];
+        let report = CheckReport {
+            mode: AppMode::SessionHost,
+            timestamp: "2024-05-01T12:00:00+00:00".to_string(),
+            summary: CheckSummary::from_results(&endpoints, &[]),
+            endpoints,
+            fslogix: Vec::new(),
+        };
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["mode"], "sessionhost");
+        assert_eq!(json["endpoints"][0]["latencyMs"], serde_json::Value::Null);
+        assert_eq!(json["summary"]["requiredFailed"], 1);
+        assert_eq!(json["summary"]["healthy"], false);
+    }
+}
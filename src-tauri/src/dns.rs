@@ -0,0 +1,366 @@
+use hickory_resolver::proto::op::{Message, MessageType, OpCode, Query, ResponseCode};
+use hickory_resolver::proto::rr::{Name, RData, Record, RecordType};
+use serde::Serialize;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::time::timeout;
+
+use crate::errors::ProbeError;
+use crate::latency::{as_ms, ProbeOptions};
+
+const DNS_PORT: u16 = 53;
+
+/// One answer record
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DnsRecord {
+    pub name: String,
+    /// A, AAAA or CNAME
+    pub record_type: String,
+    pub value: String,
+    pub ttl: u32,
+}
+
+/// Result of a DNS diagnostic query
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DnsProbeResult {
+    pub host: String,
+    /// Resolver that answered (ip:port)
+    pub server: String,
+    /// True if the answering server came from the system configuration rather than being chosen explicitly
+    pub system_resolver: bool,
+    /// Servers that were tried before one answered
+    pub failed_servers: Vec<String>,
+    /// NOERROR, NXDOMAIN, SERVFAIL, ...
+    pub response_code: String,
+    /// Names followed from the host to the canonical name, starting with the host
+    pub cname_chain: Vec<String>,
+    /// All A, AAAA and CNAME records of the answers
+    pub records: Vec<DnsRecord>,
+    /// A and AAAA addresses of the canonical name
+    pub addresses: Vec<String>,
+    /// Time for the A and AAAA queries (sent in parallel)
+    pub resolution_ms: f64,
+}
+
+/// Resolve a host with explicit A and AAAA queries and report how the answer was built
+/// Without `server` the system resolvers are tried in order until one answers, so the report shows
+/// which one did. With `server` (e.g. "168.63.129.16") only that resolver is asked, to compare
+/// corporate DNS with Azure DNS when private endpoints or split-horizon zones are involved.
+pub async fn diagnose_dns(host: &str, server: Option<&str>, options: &ProbeOptions) -> Result<DnsProbeResult, ProbeError> {
+    let (servers, system_resolver) = match server {
+        Some(server) => (vec![parse_server(server)?], false),
+        None => (system_servers()?, true),
+    };
+
+    let mut result = query_servers(host, &servers, options.timeout).await?;
+    result.system_resolver = system_resolver;
+    Ok(result)
+}
+
+/// Parse "ip" or "ip:port" ("[v6]:port" for IPv6), defaulting to port 53
+fn parse_server(server: &str) -> Result<SocketAddr, ProbeError> {
+    let server = server.trim();
+    if let Ok(addr) = server.parse::<SocketAddr>() {
+        return Ok(addr);
+    }
+    server
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+        .map(|ip| SocketAddr::new(ip, DNS_PORT))
+        .map_err(|_| ProbeError::InvalidEndpoint(format!("'{}' is not a DNS server address", server)))
+}
+
+/// Name servers from the system configuration (resolv.conf, or the network adapters on Windows)
+fn system_servers() -> Result<Vec<SocketAddr>, ProbeError> {
+    let (config, _) = hickory_resolver::system_conf::read_system_conf().map_err(|e| ProbeError::DnsFailure(e.to_string()))?;
+
+    // The configuration lists every server once for UDP and once for TCP
+    let mut servers: Vec<SocketAddr> = Vec::new();
+    for server in config.name_servers() {
+        if !servers.contains(&server.socket_addr) {
+            servers.push(server.socket_addr);
+        }
+    }
+
+    if servers.is_empty() {
+        return Err(ProbeError::DnsFailure("No DNS servers configured".to_string()));
+    }
+    Ok(servers)
+}
+
+async fn query_servers(host: &str, servers: &[SocketAddr], limit: Duration) -> Result<DnsProbeResult, ProbeError> {
+    let mut name = Name::from_ascii(host.trim_end_matches('.'))
+        .map_err(|e| ProbeError::InvalidEndpoint(format!("{}: {}", host, e)))?;
+    // Absolute name so search domains are not appended
+    name.set_fqdn(true);
+
+    let mut failed_servers = Vec::new();
+    let mut last_error = ProbeError::DnsTimeout;
+
+    for server in servers {
+        let start = Instant::now();
+        let (a, aaaa) = tokio::join!(
+            query(*server, &name, RecordType::A, limit),
+            query(*server, &name, RecordType::AAAA, limit)
+        );
+        let resolution = start.elapsed();
+
+        match (a, aaaa) {
+            (Ok(a), Ok(aaaa)) => {
+                let mut result = build_result(host, &a, &aaaa);
+                result.server = server.to_string();
+                result.failed_servers = failed_servers;
+                result.resolution_ms = as_ms(resolution);
+                return Ok(result);
+            }
+            (Err(e), _) | (_, Err(e)) => {
+                failed_servers.push(server.to_string());
+                last_error = e;
+            }
+        }
+    }
+
+    Err(last_error)
+}
+
+/// Combine the A and AAAA responses (both repeat the CNAME chain)
+fn build_result(host: &str, a: &Message, aaaa: &Message) -> DnsProbeResult {
+    let mut records: Vec<DnsRecord> = Vec::new();
+    for record in a.answers().iter().chain(aaaa.answers()) {
+        if let Some(record) = to_record(record) {
+            if !records.contains(&record) {
+                records.push(record);
+            }
+        }
+    }
+    // CNAMEs first, then addresses, the order the chain is followed in
+    records.sort_by_key(|record| record.record_type != "CNAME");
+
+    let mut cname_chain = vec![normalize(host)];
+    let mut canonical = normalize(host);
+    while let Some(target) = records.iter().find(|r| r.record_type == "CNAME" && r.name == canonical) {
+        // Guard against CNAME loops
+        if cname_chain.contains(&target.value) {
+            break;
+        }
+        canonical = target.value.clone();
+        cname_chain.push(canonical.clone());
+    }
+
+    let addresses = records
+        .iter()
+        .filter(|r| r.record_type != "CNAME" && r.name == canonical)
+        .map(|r| r.value.clone())
+        .collect();
+
+    // NXDOMAIN or SERVFAIL on A is the meaningful code; AAAA can fail on its own on broken resolvers
+    let code = if a.response_code() != ResponseCode::NoError {
+        a.response_code()
+    } else {
+        aaaa.response_code()
+    };
+
+    DnsProbeResult {
+        host: host.to_string(),
+        server: String::new(),
+        system_resolver: false,
+        failed_servers: Vec::new(),
+        response_code: format!("{:?}", code).to_uppercase(),
+        cname_chain,
+        records,
+        addresses,
+        resolution_ms: 0.0,
+    }
+}
+
+fn to_record(record: &Record) -> Option<DnsRecord> {
+    let (record_type, value) = match record.data()? {
+        RData::A(a) => ("A", a.to_string()),
+        RData::AAAA(aaaa) => ("AAAA", aaaa.to_string()),
+        RData::CNAME(cname) => ("CNAME", normalize(&cname.to_string())),
+        _ => return None,
+    };
+
+    Some(DnsRecord {
+        name: normalize(&record.name().to_string()),
+        record_type: record_type.to_string(),
+        value,
+        ttl: record.ttl(),
+    })
+}
+
+/// Lowercase without the trailing dot, so names from queries and answers compare equal
+fn normalize(name: &str) -> String {
+    name.trim_end_matches('.').to_lowercase()
+}
+
+/// Send one query over UDP, repeating it over TCP if the answer was truncated
+async fn query(server: SocketAddr, name: &Name, record_type: RecordType, limit: Duration) -> Result<Message, ProbeError> {
+    let id = next_id();
+    let mut request = Message::new();
+    request
+        .set_id(id)
+        .set_message_type(MessageType::Query)
+        .set_op_code(OpCode::Query)
+        .set_recursion_desired(true)
+        .add_query(Query::query(name.clone(), record_type));
+    let request = request.to_vec().map_err(|e| ProbeError::DnsFailure(e.to_string()))?;
+
+    let response = match timeout(limit, query_udp(server, &request, id)).await {
+        Ok(response) => response?,
+        Err(_) => return Err(ProbeError::DnsTimeout),
+    };
+    if !response.truncated() {
+        return Ok(response);
+    }
+
+    match timeout(limit, query_tcp(server, &request)).await {
+        Ok(response) => response,
+        Err(_) => Err(ProbeError::DnsTimeout),
+    }
+}
+
+async fn query_udp(server: SocketAddr, request: &[u8], id: u16) -> Result<Message, ProbeError> {
+    let bind_addr: SocketAddr = if server.is_ipv4() {
+        (Ipv4Addr::UNSPECIFIED, 0).into()
+    } else {
+        (Ipv6Addr::UNSPECIFIED, 0).into()
+    };
+    let socket = UdpSocket::bind(bind_addr).await.map_err(dns_io_error)?;
+    socket.connect(server).await.map_err(dns_io_error)?;
+    socket.send(request).await.map_err(dns_io_error)?;
+
+    let mut buf = [0u8; 4096];
+    loop {
+        let len = socket.recv(&mut buf).await.map_err(dns_io_error)?;
+        // Ignore late answers to earlier queries that reused the port
+        if let Ok(response) = Message::from_vec(&buf[..len]) {
+            if response.id() == id {
+                return Ok(response);
+            }
+        }
+    }
+}
+
+async fn query_tcp(server: SocketAddr, request: &[u8]) -> Result<Message, ProbeError> {
+    let mut stream = TcpStream::connect(server).await.map_err(dns_io_error)?;
+    let mut framed = (request.len() as u16).to_be_bytes().to_vec();
+    framed.extend_from_slice(request);
+    stream.write_all(&framed).await.map_err(dns_io_error)?;
+
+    let len = stream.read_u16().await.map_err(dns_io_error)?;
+    let mut response = vec![0u8; usize::from(len)];
+    stream.read_exact(&mut response).await.map_err(dns_io_error)?;
+    Message::from_vec(&response).map_err(|e| ProbeError::DnsFailure(e.to_string()))
+}
+
+fn dns_io_error(error: std::io::Error) -> ProbeError {
+    ProbeError::DnsFailure(error.to_string())
+}
+
+/// Query ID that differs between concurrent queries
+fn next_id() -> u16 {
+    static COUNTER: AtomicU16 = AtomicU16::new(0);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    (nanos as u16) ^ COUNTER.fetch_add(1, Ordering::Relaxed).rotate_left(8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hickory_resolver::proto::rr::rdata::{A, CNAME};
+
+    /// Stub resolver answering `files.example` with a CNAME to a privatelink name and an A record for it
+    async fn start_dns_server(nxdomain: bool) -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 1024];
+            while let Ok((len, from)) = socket.recv_from(&mut buf).await {
+                let request = Message::from_vec(&buf[..len]).unwrap();
+                let query = request.queries()[0].clone();
+                let mut response = Message::new();
+                response
+                    .set_id(request.id())
+                    .set_message_type(MessageType::Response)
+                    .set_recursion_available(true)
+                    .add_query(query.clone());
+
+                if nxdomain {
+                    response.set_response_code(ResponseCode::NXDomain);
+                } else {
+                    let canonical = Name::from_ascii("files.privatelink.example.").unwrap();
+                    response.add_answer(Record::from_rdata(
+                        query.name().clone(),
+                        3600,
+                        RData::CNAME(CNAME(canonical.clone())),
+                    ));
+                    if query.query_type() == RecordType::A {
+                        response.add_answer(Record::from_rdata(canonical, 60, RData::A(A::new(10, 1, 2, 3))));
+                    }
+                }
+                let _ = socket.send_to(&response.to_vec().unwrap(), from).await;
+            }
+        });
+        addr
+    }
+
+    #[test]
+    fn test_parse_server() {
+        assert_eq!(parse_server("168.63.129.16").unwrap(), "168.63.129.16:53".parse().unwrap());
+        assert_eq!(parse_server("10.0.0.4:5353").unwrap(), "10.0.0.4:5353".parse().unwrap());
+        assert_eq!(parse_server("[2001:db8::53]").unwrap(), "[2001:db8::53]:53".parse().unwrap());
+        assert_eq!(parse_server("dns.example").unwrap_err().code(), "INVALID_ENDPOINT");
+    }
+
+    #[tokio::test]
+    async fn test_reports_cname_chain_and_records() {
+        let server = start_dns_server(false).await;
+
+        let result = query_servers("Files.Example", &[server], Duration::from_secs(2)).await.unwrap();
+        assert_eq!(result.server, server.to_string());
+        assert_eq!(result.response_code, "NOERROR");
+        assert_eq!(result.cname_chain, vec!["files.example", "files.privatelink.example"]);
+        assert_eq!(result.addresses, vec!["10.1.2.3"]);
+        assert_eq!(result.records.len(), 2);
+        assert_eq!(result.records[0].record_type, "CNAME");
+        assert_eq!(result.records[0].ttl, 3600);
+        assert_eq!(result.records[1].ttl, 60);
+    }
+
+    #[tokio::test]
+    async fn test_reports_nxdomain() {
+        let server = start_dns_server(true).await;
+
+        let result = query_servers("missing.example", &[server], Duration::from_secs(2)).await.unwrap();
+        assert_eq!(result.response_code, "NXDOMAIN");
+        assert!(result.addresses.is_empty());
+        assert_eq!(result.cname_chain, vec!["missing.example"]);
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_next_server() {
+        // A bound socket that never answers
+        let silent = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let silent_addr = silent.local_addr().unwrap();
+        let server = start_dns_server(false).await;
+
+        let result = query_servers("files.example", &[silent_addr, server], Duration::from_millis(300))
+            .await
+            .unwrap();
+        assert_eq!(result.server, server.to_string());
+        assert_eq!(result.failed_servers, vec![silent_addr.to_string()]);
+
+        let error = query_servers("files.example", &[silent_addr], Duration::from_millis(300)).await.unwrap_err();
+        assert_eq!(error, ProbeError::DnsTimeout);
+    }
+}
@@ -0,0 +1,190 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::io::ErrorKind;
+
+/// Why a probe failed
+/// Serialized as `{ "code": "...", "message": "..." }` where the code is stable and matches
+/// the `ErrorCode` enum in the frontend, so alerts can tell a firewall block from a name
+/// that doesn't resolve without matching on messages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProbeError {
+    /// The name does not exist (NXDOMAIN) or has no addresses
+    DnsNxdomain(String),
+    /// The resolver did not answer in time
+    DnsTimeout,
+    /// Any other resolver failure
+    DnsFailure(String),
+    ConnectionRefused,
+    /// No answer to the TCP connect, usually a firewall silently dropping packets
+    ConnectTimeout,
+    ConnectionReset,
+    NetworkUnreachable(String),
+    TlsFailure(String),
+    /// The presented certificate chain is not trusted
+    CertificateInvalid(String),
+    HttpTimeout,
+    /// The server did not answer with a valid HTTP response
+    HttpError(String),
+    /// An intercepting proxy answered with 407 Proxy Authentication Required
+    ProxyAuthRequired,
+    InvalidEndpoint(String),
+    /// No answer to a STUN Binding request, UDP is likely blocked
+    StunTimeout,
+    /// The STUN server answered with an error or an unusable response
+    StunError(String),
+    /// The TCP connection was accepted but the server never answered the SMB NEGOTIATE
+    SmbTimeout,
+    /// The server only speaks SMB1, which Azure Files and current Windows don't accept
+    Smb1Only,
+    /// The server answered the NEGOTIATE with an error or an unusable response
+    SmbError(String),
+    Other(String),
+}
+
+impl ProbeError {
+    /// Stable error code sent to the frontend
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::DnsNxdomain(_) => "DNS_NXDOMAIN",
+            Self::DnsTimeout => "DNS_TIMEOUT",
+            Self::DnsFailure(_) => "DNS_RESOLUTION_FAILED",
+            Self::ConnectionRefused => "CONNECTION_REFUSED",
+            Self::ConnectTimeout => "CONNECT_TIMEOUT",
+            Self::ConnectionReset => "CONNECTION_RESET",
+            Self::NetworkUnreachable(_) => "NETWORK_UNREACHABLE",
+            Self::TlsFailure(_) => "TLS_FAILURE",
+            Self::CertificateInvalid(_) => "CERTIFICATE_INVALID",
+            Self::HttpTimeout => "HTTP_TIMEOUT",
+            Self::HttpError(_) => "HTTP_ERROR",
+            Self::ProxyAuthRequired => "PROXY_AUTH_REQUIRED",
+            Self::InvalidEndpoint(_) => "INVALID_ENDPOINT",
+            Self::StunTimeout => "STUN_TIMEOUT",
+            Self::StunError(_) => "STUN_ERROR",
+            Self::SmbTimeout => "SMB_TIMEOUT",
+            Self::Smb1Only => "SMB1_ONLY",
+            Self::SmbError(_) => "SMB_ERROR",
+            Self::Other(_) => "NETWORK_ERROR",
+        }
+    }
+
+    /// Whether retrying the probe could succeed
+    /// Names that don't exist, invalid endpoints, untrusted or unexpected certificates, proxy
+    /// authentication prompts and SMB1-only servers won't change between attempts.
+    pub fn is_retryable(&self) -> bool {
+        !matches!(
+            self,
+            Self::DnsNxdomain(_)
+                | Self::CertificateInvalid(_)
+                | Self::ProxyAuthRequired
+                | Self::InvalidEndpoint(_)
+                | Self::Smb1Only
+        )
+    }
+
+    /// Classify a failed name lookup
+    pub fn from_dns(host: &str, error: std::io::Error) -> Self {
+        // Windows Sockets: WSAHOST_NOT_FOUND, WSATRY_AGAIN, WSANO_DATA
+        match error.raw_os_error() {
+            Some(11001) | Some(11004) => return Self::DnsNxdomain(host.to_string()),
+            Some(11002) => return Self::DnsTimeout,
+            _ => {}
+        }
+
+        let message = error.to_string().to_lowercase();
+        if error.kind() == ErrorKind::NotFound
+            || message.contains("name or service not known")
+            || message.contains("nodename nor servname")
+            || message.contains("no address associated")
+            || message.contains("no such host")
+        {
+            Self::DnsNxdomain(host.to_string())
+        } else if error.kind() == ErrorKind::TimedOut
+            || message.contains("temporary failure in name resolution")
+            || message.contains("timed out")
+        {
+            Self::DnsTimeout
+        } else {
+            Self::DnsFailure(error.to_string())
+        }
+    }
+
+    /// Classify a failed TCP connect or socket I/O
+    pub fn from_io(error: std::io::Error) -> Self {
+        match error.kind() {
+            ErrorKind::ConnectionRefused => Self::ConnectionRefused,
+            ErrorKind::TimedOut => Self::ConnectTimeout,
+            ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted => Self::ConnectionReset,
+            ErrorKind::NetworkUnreachable | ErrorKind::HostUnreachable => Self::NetworkUnreachable(error.to_string()),
+            _ => Self::Other(error.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ProbeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DnsNxdomain(host) => write!(f, "Name does not resolve: {}", host),
+            Self::DnsTimeout => write!(f, "DNS lookup timeout"),
+            Self::DnsFailure(e) => write!(f, "DNS lookup failed: {}", e),
+            Self::ConnectionRefused => write!(f, "Connection refused"),
+            Self::ConnectTimeout => write!(f, "Connection timeout"),
+            Self::ConnectionReset => write!(f, "Connection reset"),
+            Self::NetworkUnreachable(e) => write!(f, "Network unreachable: {}", e),
+            Self::TlsFailure(e) => write!(f, "TLS handshake failed: {}", e),
+            Self::CertificateInvalid(e) => write!(f, "Certificate verification failed: {}", e),
+            Self::HttpTimeout => write!(f, "HTTP request timeout"),
+            Self::HttpError(e) => write!(f, "HTTP error: {}", e),
+            Self::ProxyAuthRequired => write!(f, "Proxy authentication required"),
+            Self::InvalidEndpoint(e) => write!(f, "Invalid endpoint: {}", e),
+            Self::StunTimeout => write!(f, "No STUN response (UDP blocked?)"),
+            Self::StunError(e) => write!(f, "STUN error: {}", e),
+            Self::SmbTimeout => write!(f, "No SMB response (port 445 filtered after connect?)"),
+            Self::Smb1Only => write!(f, "Server only supports SMB1"),
+            Self::SmbError(e) => write!(f, "SMB negotiation failed: {}", e),
+            Self::Other(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ProbeError {}
+
+impl Serialize for ProbeError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ProbeError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializes_code_and_message() {
+        let json = serde_json::to_value(ProbeError::ConnectTimeout).unwrap();
+        assert_eq!(json["code"], "CONNECT_TIMEOUT");
+        assert_eq!(json["message"], "Connection timeout");
+    }
+
+    #[test]
+    fn test_classify_dns_errors() {
+        let nxdomain = std::io::Error::other("failed to lookup address information: Name or service not known");
+        assert_eq!(ProbeError::from_dns("x.example", nxdomain), ProbeError::DnsNxdomain("x.example".to_string()));
+
+        let again = std::io::Error::other("failed to lookup address information: Temporary failure in name resolution");
+        assert_eq!(ProbeError::from_dns("x.example", again), ProbeError::DnsTimeout);
+
+        let windows = std::io::Error::from_raw_os_error(11001);
+        assert_eq!(ProbeError::from_dns("x.example", windows).code(), "DNS_NXDOMAIN");
+    }
+
+    #[test]
+    fn test_classify_connect_errors() {
+        assert_eq!(ProbeError::from_io(ErrorKind::ConnectionRefused.into()), ProbeError::ConnectionRefused);
+        assert_eq!(ProbeError::from_io(ErrorKind::TimedOut.into()), ProbeError::ConnectTimeout);
+        assert_eq!(ProbeError::from_io(ErrorKind::ConnectionReset.into()), ProbeError::ConnectionReset);
+    }
+}
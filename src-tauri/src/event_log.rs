@@ -0,0 +1,353 @@
+use std::io;
+
+use crate::alerts::{format_duration, AlertEvent, AlertEventKind, IncidentSource, Problem};
+use crate::logger;
+use crate::webhooks::host_name;
+
+/// Event source in the Windows Application log
+#[cfg_attr(not(windows), allow(dead_code))]
+pub const EVENT_SOURCE: &str = "AVD Health Monitor";
+/// Identifier of syslog messages
+#[cfg_attr(not(unix), allow(dead_code))]
+const SYSLOG_IDENT: &str = "avd-health-monitor";
+
+pub const EVENT_ENDPOINT_UNREACHABLE: u32 = 1001;
+pub const EVENT_ENDPOINT_HIGH_LATENCY: u32 = 1002;
+pub const EVENT_FSLOGIX_UNREACHABLE: u32 = 1003;
+pub const EVENT_RECOVERED: u32 = 1004;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Information,
+    Warning,
+    Error,
+}
+
+/// A health state change as written to the system log
+#[derive(Debug, Clone, PartialEq)]
+pub struct SystemEvent {
+    pub event_id: u32,
+    pub severity: Severity,
+    pub message: String,
+    /// Key/value pairs for SIEM parsing, always in the same order
+    pub fields: Vec<(&'static str, String)>,
+}
+
+/// Destination of system events: the Windows Event Log, syslog, or a test recorder
+pub trait EventWriter: Send + Sync {
+    fn write(&self, event: &SystemEvent) -> io::Result<()>;
+}
+
+/// Map an alert or recovery to its event ID, severity and fields
+pub fn system_event(source: IncidentSource, event: &AlertEvent, host: &str) -> SystemEvent {
+    let (event_id, severity, message) = match (event.kind, source, &event.problem) {
+        (AlertEventKind::Recovered, _, _) => (
+            EVENT_RECOVERED,
+            Severity::Information,
+            format!("Recovered: {} after {}", event.name, format_duration(event.outage_ms)),
+        ),
+        (_, IncidentSource::Fslogix, _) => (
+            EVENT_FSLOGIX_UNREACHABLE,
+            Severity::Error,
+            format!("FSLogix storage unreachable: {}", event.name),
+        ),
+        (_, IncidentSource::Endpoints, Some(Problem::HighLatency { latency, .. })) => (
+            EVENT_ENDPOINT_HIGH_LATENCY,
+            Severity::Warning,
+            format!("Endpoint high latency: {} ({:.0}ms)", event.name, latency),
+        ),
+        (_, IncidentSource::Endpoints, _) => (
+            EVENT_ENDPOINT_UNREACHABLE,
+            Severity::Error,
+            format!("Endpoint unreachable: {}", event.name),
+        ),
+    };
+
+    let mut fields = vec![
+        ("host", host.to_string()),
+        (
+            "source",
+            match source {
+                IncidentSource::Endpoints => "endpoints",
+                IncidentSource::Fslogix => "fslogix",
+            }
+            .to_string(),
+        ),
+        ("id", event.id.clone()),
+        ("name", event.name.clone()),
+        (
+            "state",
+            match event.kind {
+                AlertEventKind::Alert => "alerting",
+                AlertEventKind::Recovered => "recovered",
+            }
+            .to_string(),
+        ),
+    ];
+    match &event.problem {
+        Some(Problem::HighLatency { latency, .. }) => {
+            fields.push(("problem", "highLatency".to_string()));
+            fields.push(("latencyMs", format!("{:.0}", latency)));
+        }
+        Some(Problem::Unreachable { error }) => {
+            fields.push(("problem", "unreachable".to_string()));
+            fields.push(("error", error.clone()));
+        }
+        None => {}
+    }
+    fields.push(("consecutiveFailures", event.consecutive_failures.to_string()));
+    fields.push(("outageSeconds", (event.outage_ms / 1000).to_string()));
+
+    SystemEvent {
+        event_id,
+        severity,
+        message,
+        fields,
+    }
+}
+
+/// Event description for the Windows Event Log: the message followed by one `key: value` line per field
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn event_log_text(event: &SystemEvent) -> String {
+    let mut text = event.message.clone();
+    text.push_str("\r\n");
+    for (key, value) in &event.fields {
+        text.push_str(&format!("\r\n{}: {}", key, value));
+    }
+    text
+}
+
+/// RFC 3164 datagram for the local syslog socket (user facility), also picked up by journald
+#[cfg_attr(not(unix), allow(dead_code))]
+pub fn syslog_message(event: &SystemEvent, pid: u32) -> String {
+    const FACILITY_USER: u8 = 1;
+    let severity = match event.severity {
+        Severity::Error => 3,
+        Severity::Warning => 4,
+        Severity::Information => 6,
+    };
+
+    let mut message = format!(
+        "<{}>{}[{}]: {} eventId={}",
+        FACILITY_USER * 8 + severity,
+        SYSLOG_IDENT,
+        pid,
+        event.message,
+        event.event_id
+    );
+    for (key, value) in &event.fields {
+        if value.is_empty() || value.contains(|c: char| c.is_whitespace() || c == '"' || c == '=') {
+            message.push_str(&format!(" {}=\"{}\"", key, value.replace('\\', "\\\\").replace('"', "\\\"")));
+        } else {
+            message.push_str(&format!(" {}={}", key, value));
+        }
+    }
+    message
+}
+
+/// Writes to the Application log under the "AVD Health Monitor" source
+/// The installers register the source with the exe's message table (see build.rs); unregistered,
+/// Event Viewer still shows the text, prefixed with a missing description notice.
+#[cfg(windows)]
+pub struct WindowsEventLog;
+
+#[cfg(windows)]
+impl EventWriter for WindowsEventLog {
+    fn write(&self, event: &SystemEvent) -> io::Result<()> {
+        use windows_sys::Win32::System::EventLog::{
+            DeregisterEventSource, RegisterEventSourceW, ReportEventW, EVENTLOG_ERROR_TYPE, EVENTLOG_INFORMATION_TYPE,
+            EVENTLOG_WARNING_TYPE,
+        };
+
+        let wide = |s: &str| s.encode_utf16().chain(std::iter::once(0)).collect::<Vec<u16>>();
+        let source = wide(EVENT_SOURCE);
+        let text = wide(&event_log_text(event));
+        let strings = [text.as_ptr()];
+        let event_type = match event.severity {
+            Severity::Error => EVENTLOG_ERROR_TYPE,
+            Severity::Warning => EVENTLOG_WARNING_TYPE,
+            Severity::Information => EVENTLOG_INFORMATION_TYPE,
+        };
+
+        // SAFETY: all pointers refer to NUL-terminated buffers that outlive the calls
+        unsafe {
+            let handle = RegisterEventSourceW(std::ptr::null(), source.as_ptr());
+            if handle.is_null() {
+                return Err(io::Error::last_os_error());
+            }
+            let reported = ReportEventW(
+                handle,
+                event_type,
+                0,
+                event.event_id,
+                std::ptr::null_mut(),
+                strings.len() as u16,
+                0,
+                strings.as_ptr(),
+                std::ptr::null(),
+            );
+            let result = if reported == 0 { Err(io::Error::last_os_error()) } else { Ok(()) };
+            DeregisterEventSource(handle);
+            result
+        }
+    }
+}
+
+/// Sends to the local syslog socket, which journald also listens on
+#[cfg(unix)]
+pub struct Syslog;
+
+#[cfg(unix)]
+impl EventWriter for Syslog {
+    fn write(&self, event: &SystemEvent) -> io::Result<()> {
+        let socket = std::os::unix::net::UnixDatagram::unbound()?;
+        let message = syslog_message(event, std::process::id());
+
+        let mut last_error = io::Error::new(io::ErrorKind::NotFound, "No syslog socket found");
+        for path in ["/dev/log", "/var/run/syslog"] {
+            match socket.send_to(message.as_bytes(), path) {
+                Ok(_) => return Ok(()),
+                Err(e) => last_error = e,
+            }
+        }
+        Err(last_error)
+    }
+}
+
+/// System log writer of this platform
+pub fn default_writer() -> Box<dyn EventWriter> {
+    #[cfg(windows)]
+    let writer: Box<dyn EventWriter> = Box::new(WindowsEventLog);
+    #[cfg(unix)]
+    let writer: Box<dyn EventWriter> = Box::new(Syslog);
+    writer
+}
+
+/// Write one event per alert and recovery, returning how many were written
+pub fn write_events(writer: &dyn EventWriter, source: IncidentSource, events: &[AlertEvent], host: &str) -> usize {
+    events
+        .iter()
+        .map(|event| system_event(source, event, host))
+        .filter(|event| match writer.write(event) {
+            Ok(()) => true,
+            Err(e) => {
+                logger::error("EventLog", &format!("Failed to write event {}: {}", event.event_id, e));
+                false
+            }
+        })
+        .count()
+}
+
+/// Write a round of alert and recovery events to the system log of this platform
+pub fn record(source: IncidentSource, events: &[AlertEvent]) {
+    if !events.is_empty() {
+        write_events(default_writer().as_ref(), source, events, &host_name());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+
+    #[derive(Default)]
+    struct RecordingWriter {
+        events: Mutex<Vec<SystemEvent>>,
+    }
+
+    impl EventWriter for RecordingWriter {
+        fn write(&self, event: &SystemEvent) -> io::Result<()> {
+            self.events.lock().push(event.clone());
+            Ok(())
+        }
+    }
+
+    fn event(kind: AlertEventKind, problem: Option<Problem>) -> AlertEvent {
+        AlertEvent {
+            kind,
+            id: "gateway".to_string(),
+            name: "RD Gateway".to_string(),
+            problem,
+            consecutive_failures: 3,
+            outage_ms: 270_000,
+        }
+    }
+
+    fn unreachable() -> Option<Problem> {
+        Some(Problem::Unreachable {
+            error: "Connection refused".to_string(),
+        })
+    }
+
+    #[test]
+    fn test_event_ids() {
+        let cases = [
+            (IncidentSource::Endpoints, event(AlertEventKind::Alert, unreachable()), EVENT_ENDPOINT_UNREACHABLE, Severity::Error),
+            (
+                IncidentSource::Endpoints,
+                event(AlertEventKind::Alert, Some(Problem::HighLatency { latency: 250.0, critical: true })),
+                EVENT_ENDPOINT_HIGH_LATENCY,
+                Severity::Warning,
+            ),
+            (IncidentSource::Fslogix, event(AlertEventKind::Alert, unreachable()), EVENT_FSLOGIX_UNREACHABLE, Severity::Error),
+            (IncidentSource::Fslogix, event(AlertEventKind::Recovered, None), EVENT_RECOVERED, Severity::Information),
+        ];
+
+        for (source, alert, event_id, severity) in cases {
+            let system = system_event(source, &alert, "SH-01");
+            assert_eq!(system.event_id, event_id);
+            assert_eq!(system.severity, severity);
+        }
+    }
+
+    #[test]
+    fn test_event_fields() {
+        let system = system_event(IncidentSource::Endpoints, &event(AlertEventKind::Alert, unreachable()), "SH-01");
+        assert_eq!(system.message, "Endpoint unreachable: RD Gateway");
+        let keys: Vec<&str> = system.fields.iter().map(|(k, _)| *k).collect();
+        assert_eq!(
+            keys,
+            ["host", "source", "id", "name", "state", "problem", "error", "consecutiveFailures", "outageSeconds"]
+        );
+        assert_eq!(system.fields[6].1, "Connection refused");
+        assert_eq!(system.fields[8].1, "270");
+
+        let recovered = system_event(IncidentSource::Fslogix, &event(AlertEventKind::Recovered, None), "SH-01");
+        assert_eq!(recovered.message, "Recovered: RD Gateway after 4m 30s");
+        assert!(recovered.fields.contains(&("source", "fslogix".to_string())));
+        assert!(recovered.fields.contains(&("state", "recovered".to_string())));
+    }
+
+    #[test]
+    fn test_event_log_text() {
+        let system = system_event(IncidentSource::Endpoints, &event(AlertEventKind::Alert, unreachable()), "SH-01");
+        let text = event_log_text(&system);
+        assert!(text.starts_with("Endpoint unreachable: RD Gateway\r\n\r\nhost: SH-01\r\nsource: endpoints"));
+        assert!(text.ends_with("outageSeconds: 270"));
+    }
+
+    #[test]
+    fn test_syslog_message() {
+        let system = system_event(IncidentSource::Endpoints, &event(AlertEventKind::Alert, unreachable()), "SH-01");
+        let message = syslog_message(&system, 42);
+        // user.err
+        assert!(message.starts_with("<11>avd-health-monitor[42]: Endpoint unreachable: RD Gateway eventId=1001 host=SH-01"));
+        assert!(message.contains(r#" name="RD Gateway" "#));
+        assert!(message.contains(r#" error="Connection refused" "#));
+
+        let recovered = system_event(IncidentSource::Endpoints, &event(AlertEventKind::Recovered, None), "SH-01");
+        // user.info
+        assert!(syslog_message(&recovered, 42).starts_with("<14>"));
+    }
+
+    #[test]
+    fn test_write_events() {
+        let writer = RecordingWriter::default();
+        let events = [event(AlertEventKind::Alert, unreachable()), event(AlertEventKind::Recovered, None)];
+
+        assert_eq!(write_events(&writer, IncidentSource::Endpoints, &events, "SH-01"), 2);
+        let written = writer.events.lock();
+        assert_eq!(written[0].event_id, EVENT_ENDPOINT_UNREACHABLE);
+        assert_eq!(written[1].event_id, EVENT_RECOVERED);
+    }
+}
@@ -3,7 +3,21 @@ use winreg::enums::*;
 #[cfg(target_os = "windows")]
 use winreg::RegKey;
 
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::dns;
+use crate::errors::ProbeError;
+use crate::latency::{self, ProbeOptions};
+
+#[cfg(target_os = "windows")]
+use crate::logger;
 
 /// Represents an FSLogix storage path configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,11 +31,298 @@ pub struct FSLogixPath {
     pub path: String,
     /// Extracted hostname for network testing
     pub hostname: String,
-    /// Port for connectivity testing (445 for SMB)
+    /// Port for connectivity testing (445 for SMB, 443 for Azure blob)
     pub port: u16,
     /// If true, alerts are suppressed for this path
     #[serde(default)]
     pub muted: Option<bool>,
+    /// How the storage is reached
+    #[serde(default)]
+    pub provider: StorageProvider,
+    /// The path comes from CCDLocations (Cloud Cache) instead of VHDLocations
+    #[serde(default, rename = "cloudCache")]
+    pub cloud_cache: bool,
+    /// Index-based ID VHDLocations paths had before IDs were derived from the path, for migrating
+    /// saved state (Cloud Cache paths never had one)
+    #[serde(skip)]
+    pub legacy_id: Option<String>,
+}
+
+impl FSLogixPath {
+    /// Probe protocol for the storage behind this path
+    pub fn protocol(&self) -> &'static str {
+        match self.provider {
+            StorageProvider::Smb => "smb",
+            StorageProvider::AzureBlob => "https",
+        }
+    }
+}
+
+/// Storage service behind an FSLogix path
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageProvider {
+    /// SMB file share (VHDLocations or a Cloud Cache type=smb provider)
+    #[default]
+    Smb,
+    /// Azure page blobs of a Cloud Cache type=azure provider
+    AzureBlob,
+}
+
+/// A parsed CCDLocations provider entry
+#[derive(Debug, Clone, PartialEq)]
+pub enum CloudCacheProvider {
+    /// type=smb with the UNC path of a share
+    Smb { path: String, hostname: String },
+    /// type=azure with the blob endpoint of a storage account
+    Azure { account: String, hostname: String },
+}
+
+impl CloudCacheProvider {
+    fn into_path(self, path_type: &str) -> FSLogixPath {
+        let (path, hostname, port, provider) = match self {
+            CloudCacheProvider::Smb { path, hostname } => (path, hostname, 445, StorageProvider::Smb),
+            CloudCacheProvider::Azure { hostname, .. } => {
+                (format!("https://{}", hostname), hostname, 443, StorageProvider::AzureBlob)
+            }
+        };
+        FSLogixPath {
+            id: path_id(path_type, true, &path),
+            path_type: path_type.to_string(),
+            path,
+            hostname,
+            port,
+            muted: None,
+            provider,
+            cloud_cache: true,
+            legacy_id: None,
+        }
+    }
+}
+
+/// DNS suffixes of Azure Storage in the public and sovereign clouds
+const AZURE_STORAGE_SUFFIXES: [&str; 3] = [".core.windows.net", ".core.usgovcloudapi.net", ".core.chinacloudapi.cn"];
+
+/// Where a storage host resolves to from this machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageExposure {
+    /// All addresses are private (RFC 1918 or IPv6 ULA), e.g. an Azure Private Endpoint
+    Private,
+    /// A public address of an Azure Storage account - SMB goes over the internet
+    PublicAzure,
+    /// A public address of a non-Azure server
+    Public,
+    Unresolvable,
+}
+
+/// How a storage host resolves, with a warning for Private Endpoints that are bypassed
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageResolution {
+    pub exposure: StorageExposure,
+    pub addresses: Vec<String>,
+    /// The name is a CNAME to a privatelink zone, so the account has a Private Endpoint
+    pub private_link: bool,
+    pub warning: Option<String>,
+}
+
+/// Resolve a storage host and classify the result
+/// The addresses come from the system resolver (hosts file, NRPT and all), which is what SMB uses;
+/// the CNAME chain from an explicit DNS query shows whether the account has a Private Endpoint.
+pub async fn classify_storage_host(hostname: &str, options: &ProbeOptions) -> StorageResolution {
+    let (addrs, chain) = tokio::join!(
+        latency::resolve_addresses(hostname, 445, options.timeout),
+        dns::diagnose_dns(hostname, None, options)
+    );
+
+    let addrs: Vec<IpAddr> = addrs.map(|(addrs, _)| addrs.iter().map(|a| a.ip()).collect()).unwrap_or_default();
+    let chain = chain.map(|result| result.cname_chain).unwrap_or_default();
+    classify_resolution(hostname, &addrs, &chain)
+}
+
+fn classify_resolution(hostname: &str, addrs: &[IpAddr], cname_chain: &[String]) -> StorageResolution {
+    let host = hostname.to_lowercase();
+    let private_link = cname_chain.iter().any(|name| name.contains(".privatelink."));
+    let is_azure = AZURE_STORAGE_SUFFIXES.iter().any(|suffix| host.ends_with(suffix));
+
+    let exposure = if addrs.is_empty() {
+        StorageExposure::Unresolvable
+    } else if addrs.iter().all(is_private_address) {
+        StorageExposure::Private
+    } else if is_azure || private_link {
+        StorageExposure::PublicAzure
+    } else {
+        StorageExposure::Public
+    };
+
+    let warning = (private_link && exposure == StorageExposure::PublicAzure).then(|| {
+        format!(
+            "{} has a Private Endpoint but resolves to a public address ({}); SMB traffic leaves over the internet. \
+             Check the privatelink DNS zone or conditional forwarder used by this host.",
+            hostname,
+            addrs.iter().find(|a| !is_private_address(a)).map(ToString::to_string).unwrap_or_default()
+        )
+    });
+
+    StorageResolution {
+        exposure,
+        addresses: addrs.iter().map(ToString::to_string).collect(),
+        private_link,
+        warning,
+    }
+}
+
+/// RFC 1918 for IPv4, unique local addresses (fc00::/7) for IPv6
+fn is_private_address(addr: &IpAddr) -> bool {
+    match addr {
+        IpAddr::V4(v4) => v4.is_private(),
+        IpAddr::V6(v6) => (v6.segments()[0] & 0xfe00) == 0xfc00,
+    }
+}
+
+/// Outcome of opening the share behind an FSLogix path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShareAccess {
+    /// The share exists and its directory can be listed
+    Ok,
+    /// The storage host did not answer (name not found, no route, SMB blocked)
+    HostUnreachable,
+    /// The host answered but the share or folder does not exist
+    ShareMissing,
+    /// The share exists but this account may not list it
+    AccessDenied,
+    /// Any other failure
+    Error,
+    /// The check of an earlier round is still blocked on the host, so no new one was started
+    Pending,
+}
+
+/// Result of a share-level access check
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareCheck {
+    pub access: ShareAccess,
+    pub detail: Option<String>,
+    pub duration_ms: f64,
+}
+
+/// File system access used by the share check, so it can be faked in tests
+pub trait ShareAccessor: Send + Sync {
+    /// Open the directory at `path` and read its first entry
+    fn list_dir(&self, path: &str) -> std::io::Result<()>;
+}
+
+/// Lists shares through the operating system (SMB redirector on Windows)
+pub struct FsShareAccessor;
+
+impl ShareAccessor for FsShareAccessor {
+    fn list_dir(&self, path: &str) -> std::io::Result<()> {
+        std::fs::read_dir(path)?.next().transpose()?;
+        Ok(())
+    }
+}
+
+/// Blocking share listings that outlived their timeout, keyed by UNC path
+/// A blocking thread can't be cancelled, so a hung host gets no new thread until the old one returns.
+static PENDING_SHARE_CHECKS: Lazy<Mutex<HashMap<String, tokio::task::JoinHandle<std::io::Result<()>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Check that the share of an FSLogix path exists and can be listed, using the full UNC path
+/// The SMB redirector can block for a long time on an unreachable host, so the check is cut
+/// off after `limit` and reported as unreachable. While that listing is still blocked, later
+/// checks of the path report `Pending` instead of starting another one.
+pub async fn check_share(path: &FSLogixPath, accessor: Arc<dyn ShareAccessor>, limit: Duration) -> ShareCheck {
+    let start = Instant::now();
+    {
+        let mut pending = PENDING_SHARE_CHECKS.lock();
+        if pending.get(&path.path).is_some_and(|handle| !handle.is_finished()) {
+            return ShareCheck {
+                access: ShareAccess::Pending,
+                detail: Some(format!("An earlier check of {} has not returned yet", path.hostname)),
+                duration_ms: 0.0,
+            };
+        }
+        pending.remove(&path.path);
+    }
+
+    let unc = path.path.clone();
+    let mut handle = tokio::task::spawn_blocking(move || accessor.list_dir(&unc));
+    let outcome = tokio::time::timeout(limit, &mut handle).await;
+    let duration_ms = latency::as_ms(start.elapsed());
+
+    let (access, detail) = match outcome {
+        Ok(Ok(Ok(()))) => (ShareAccess::Ok, None),
+        Ok(Ok(Err(e))) => (classify_share_error(&e), Some(e.to_string())),
+        Ok(Err(e)) => (ShareAccess::Error, Some(e.to_string())),
+        Err(_) => {
+            PENDING_SHARE_CHECKS.lock().insert(path.path.clone(), handle);
+            (
+                ShareAccess::HostUnreachable,
+                Some(format!("No answer from {} within {} ms", path.hostname, limit.as_millis())),
+            )
+        }
+    };
+
+    ShareCheck { access, detail, duration_ms }
+}
+
+/// Outcome of one check of an FSLogix path
+#[derive(Debug, Clone)]
+pub struct PathCheck {
+    pub reachable: bool,
+    pub latency_ms: Option<f64>,
+    pub error: Option<ProbeError>,
+    pub resolution: StorageResolution,
+    /// None unless `share_check` is set and the path is an SMB share
+    pub share: Option<ShareCheck>,
+}
+
+/// Probe the storage host of `path`, classify its address and, with `share_check`, check the share
+/// itself, all concurrently. Shared by the scheduler and the command line check.
+pub async fn check_path(path: &FSLogixPath, options: &ProbeOptions, share_check: bool) -> PathCheck {
+    let (result, resolution, share) = tokio::join!(
+        latency::probe_endpoint(&path.hostname, path.port, path.protocol(), options),
+        classify_storage_host(&path.hostname, options),
+        async {
+            if share_check && path.provider == StorageProvider::Smb {
+                Some(check_share(path, Arc::new(FsShareAccessor), options.timeout).await)
+            } else {
+                None
+            }
+        }
+    );
+
+    PathCheck {
+        reachable: result.is_ok(),
+        latency_ms: result.as_ref().ok().map(|probe| probe.latency_ms),
+        error: result.err(),
+        resolution,
+        share,
+    }
+}
+
+/// Map an I/O error from opening a UNC path to a share access category
+/// Windows reports SMB failures as Win32 error codes, which are more precise than the ErrorKind.
+fn classify_share_error(error: &std::io::Error) -> ShareAccess {
+    match error.raw_os_error() {
+        // ERROR_BAD_NETPATH, ERROR_NETNAME_DELETED, ERROR_SEM_TIMEOUT, ERROR_NO_NET_OR_BAD_PATH,
+        // ERROR_NETWORK_UNREACHABLE, ERROR_HOST_UNREACHABLE
+        Some(53 | 64 | 121 | 1222 | 1231 | 1232) if cfg!(windows) => ShareAccess::HostUnreachable,
+        // ERROR_FILE_NOT_FOUND, ERROR_PATH_NOT_FOUND, ERROR_BAD_NET_NAME
+        Some(2 | 3 | 67) if cfg!(windows) => ShareAccess::ShareMissing,
+        // ERROR_ACCESS_DENIED, ERROR_NOT_AUTHENTICATED, ERROR_LOGON_FAILURE
+        Some(5 | 1244 | 1326) if cfg!(windows) => ShareAccess::AccessDenied,
+        _ => match error.kind() {
+            ErrorKind::PermissionDenied => ShareAccess::AccessDenied,
+            ErrorKind::NotFound => ShareAccess::ShareMissing,
+            ErrorKind::TimedOut | ErrorKind::NetworkUnreachable | ErrorKind::HostUnreachable => {
+                ShareAccess::HostUnreachable
+            }
+            _ => ShareAccess::Error,
+        },
+    }
 }
 
 /// Extract hostname from a UNC path
@@ -50,72 +351,414 @@ fn parse_vhd_locations(value: &str) -> Vec<String> {
         .collect()
 }
 
-#[cfg(target_os = "windows")]
-fn read_vhd_locations_from_key(key: &RegKey, value_name: &str) -> Vec<String> {
-    // Try to read as REG_SZ first
-    if let Ok(value) = key.get_value::<String, _>(value_name) {
-        return parse_vhd_locations(&value);
+/// Policy and local keys of Profile Containers, in order of precedence
+const PROFILES_KEYS: [&str; 2] = [r"SOFTWARE\Policies\FSLogix\Profiles", r"SOFTWARE\FSLogix\Profiles"];
+/// Policy and local keys of Office Containers (ODFC), in order of precedence
+const ODFC_KEYS: [&str; 2] = [r"SOFTWARE\Policies\FSLogix\ODFC", r"SOFTWARE\FSLogix\ODFC"];
+
+/// FSLogix defaults for values that are not set
+const DEFAULT_SIZE_IN_MBS: u32 = 30000;
+const DEFAULT_VOLUME_TYPE: &str = "VHD";
+
+/// A registry value, in the types FSLogix settings are stored as
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))] // only the Windows reader creates values
+pub enum RegistryValue {
+    Dword(u32),
+    String(String),
+    MultiString(Vec<String>),
+}
+
+impl RegistryValue {
+    /// Numeric value; some deployments store numbers as REG_SZ
+    fn as_u32(&self) -> Option<u32> {
+        match self {
+            RegistryValue::Dword(value) => Some(*value),
+            RegistryValue::String(value) => value.trim().parse().ok(),
+            RegistryValue::MultiString(_) => None,
+        }
     }
 
-    // Try to read as REG_MULTI_SZ
-    if let Ok(values) = key.get_value::<Vec<String>, _>(value_name) {
-        return values
-            .iter()
-            .flat_map(|v| parse_vhd_locations(v))
-            .collect();
+    fn as_string(&self) -> String {
+        match self {
+            RegistryValue::Dword(value) => value.to_string(),
+            RegistryValue::String(value) => value.trim().to_string(),
+            RegistryValue::MultiString(values) => values.join(";"),
+        }
     }
 
-    Vec::new()
+    /// The strings of a REG_SZ or REG_MULTI_SZ value
+    fn strings(&self) -> Vec<&str> {
+        match self {
+            RegistryValue::Dword(_) => Vec::new(),
+            RegistryValue::String(value) => vec![value.as_str()],
+            RegistryValue::MultiString(values) => values.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// Read access to HKLM, so the FSLogix configuration can be parsed without Windows
+pub trait RegistryReader {
+    /// Read a value under HKLM; None when the key or the value does not exist
+    fn read_value(&self, key: &str, name: &str) -> Option<RegistryValue>;
 }
 
+/// In-memory registry keyed by (key, value name)
+impl RegistryReader for HashMap<(String, String), RegistryValue> {
+    fn read_value(&self, key: &str, name: &str) -> Option<RegistryValue> {
+        self.get(&(key.to_string(), name.to_string())).cloned()
+    }
+}
+
+/// Reads the local machine registry
 #[cfg(target_os = "windows")]
-pub fn get_fslogix_paths() -> Vec<FSLogixPath> {
-    let mut paths = Vec::new();
-    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+pub struct WindowsRegistry;
 
-    // Check Profile Containers: HKLM\SOFTWARE\FSLogix\Profiles
-    if let Ok(profiles_key) = hklm.open_subkey(r"SOFTWARE\FSLogix\Profiles") {
-        let vhd_locations = read_vhd_locations_from_key(&profiles_key, "VHDLocations");
+#[cfg(target_os = "windows")]
+impl RegistryReader for WindowsRegistry {
+    fn read_value(&self, key: &str, name: &str) -> Option<RegistryValue> {
+        let key = RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey(key).ok()?;
+        if let Ok(values) = key.get_value::<Vec<String>, _>(name) {
+            return Some(RegistryValue::MultiString(values));
+        }
+        if let Ok(value) = key.get_value::<u32, _>(name) {
+            return Some(RegistryValue::Dword(value));
+        }
+        key.get_value::<String, _>(name).ok().map(RegistryValue::String)
+    }
+}
 
-        for (index, path) in vhd_locations.iter().enumerate() {
-            if let Some(hostname) = extract_hostname(path) {
-                paths.push(FSLogixPath {
-                    id: format!("fslogix-profile-{}", index),
-                    path_type: "profile".to_string(),
-                    path: path.clone(),
-                    hostname,
-                    port: 445, // SMB port
-                    muted: None,
-                });
+/// Effective settings of one container type, after merging the policy and local keys
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerConfig {
+    /// "profile" or "odfc"
+    #[serde(rename = "type")]
+    pub container_type: String,
+    pub enabled: bool,
+    /// UNC paths from VHDLocations
+    pub vhd_locations: Vec<String>,
+    /// Cloud Cache provider entries from CCDLocations, unparsed
+    pub ccd_locations: Vec<String>,
+    pub size_in_mbs: u32,
+    /// "VHD" or "VHDX"
+    pub volume_type: String,
+    pub flip_flop_profile_directory_name: bool,
+    pub is_dynamic: bool,
+    /// Names of the values that came from the Group Policy key
+    pub policy_values: Vec<String>,
+}
+
+/// FSLogix configuration of this machine with misconfiguration warnings
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FSLogixConfig {
+    pub profiles: ContainerConfig,
+    pub odfc: ContainerConfig,
+    pub warnings: Vec<String>,
+}
+
+/// Read the Profile and ODFC container settings through `reader`
+pub fn read_fslogix_config(reader: &dyn RegistryReader) -> FSLogixConfig {
+    let mut warnings = Vec::new();
+    let profiles = read_container_config(reader, "profile", &PROFILES_KEYS, &mut warnings);
+    let odfc = read_container_config(reader, "odfc", &ODFC_KEYS, &mut warnings);
+    FSLogixConfig { profiles, odfc, warnings }
+}
+
+fn read_container_config(
+    reader: &dyn RegistryReader,
+    container_type: &str,
+    keys: &[&str; 2],
+    warnings: &mut Vec<String>,
+) -> ContainerConfig {
+    let label = match container_type {
+        "profile" => "Profile Container",
+        _ => "Office Container",
+    };
+    let mut policy_values = Vec::new();
+
+    // The policy key wins over the local key, value by value
+    let mut read = |name: &str| {
+        let policy = reader.read_value(keys[0], name);
+        let local = reader.read_value(keys[1], name);
+        if let (Some(policy), Some(local)) = (&policy, &local) {
+            if policy.as_string() != local.as_string() {
+                // CCDLocations can carry account keys and SAS tokens
+                let shown = |value: &RegistryValue| match name {
+                    "CCDLocations" => redact_ccd_entry(&value.as_string()),
+                    _ => value.as_string(),
+                };
+                warnings.push(format!(
+                    "{}: {} is {} by policy but {} in {}; the policy value is used",
+                    label,
+                    name,
+                    shown(policy),
+                    shown(local),
+                    keys[1]
+                ));
             }
         }
+        if policy.is_some() {
+            policy_values.push(name.to_string());
+        }
+        policy.or(local)
+    };
+
+    let enabled = read("Enabled").and_then(|v| v.as_u32());
+    let vhd = read("VHDLocations");
+    let ccd = read("CCDLocations");
+    let size = read("SizeInMBs");
+    let volume_type = read("VolumeType");
+    let flip_flop = read("FlipFlopProfileDirectoryName").and_then(|v| v.as_u32());
+    let is_dynamic = read("IsDynamic").and_then(|v| v.as_u32());
+
+    let vhd_strings = vhd.as_ref().map(RegistryValue::strings).unwrap_or_default();
+    let vhd_locations: Vec<String> = vhd_strings.iter().flat_map(|value| parse_vhd_locations(value)).collect();
+    let invalid_vhd: Vec<&str> = vhd_strings
+        .iter()
+        .flat_map(|value| value.split(';'))
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty() && !entry.starts_with("\\\\"))
+        .collect();
+    let ccd_locations: Vec<String> = ccd
+        .as_ref()
+        .map(RegistryValue::strings)
+        .unwrap_or_default()
+        .iter()
+        .flat_map(|value| split_ccd_locations(value))
+        .map(|entry| redact_ccd_entry(&entry))
+        .collect();
+
+    let config = ContainerConfig {
+        container_type: container_type.to_string(),
+        enabled: enabled == Some(1),
+        vhd_locations,
+        ccd_locations,
+        size_in_mbs: size.as_ref().and_then(RegistryValue::as_u32).unwrap_or(DEFAULT_SIZE_IN_MBS),
+        volume_type: volume_type.map_or_else(|| DEFAULT_VOLUME_TYPE.to_string(), |v| v.as_string().to_uppercase()),
+        flip_flop_profile_directory_name: flip_flop == Some(1),
+        is_dynamic: is_dynamic != Some(0),
+        policy_values,
+    };
+
+    let has_locations = !config.vhd_locations.is_empty() || !config.ccd_locations.is_empty();
+    if !config.enabled && !has_locations {
+        return config;
     }
 
-    // Check Office Containers (ODFC): HKLM\SOFTWARE\Policies\FSLogix\ODFC
-    if let Ok(odfc_key) = hklm.open_subkey(r"SOFTWARE\Policies\FSLogix\ODFC") {
-        let vhd_locations = read_vhd_locations_from_key(&odfc_key, "VHDLocations");
+    if !config.enabled {
+        warnings.push(format!(
+            "{}: storage locations are configured but Enabled is not 1, so no containers are attached",
+            label
+        ));
+    } else if !has_locations {
+        warnings.push(format!("{}: Enabled is 1 but neither VHDLocations nor CCDLocations is set", label));
+    }
+    if !config.vhd_locations.is_empty() && !config.ccd_locations.is_empty() {
+        warnings.push(format!(
+            "{}: both CCDLocations and VHDLocations are set; Cloud Cache takes precedence and VHDLocations is ignored",
+            label
+        ));
+    }
+    for entry in invalid_vhd {
+        warnings.push(format!("{}: VHDLocations entry '{}' is not a UNC path and is not monitored", label, entry));
+    }
+    for entry in config.ccd_locations.iter().filter(|entry| parse_ccd_provider(entry).is_none()) {
+        warnings.push(format!(
+            "{}: CCDLocations entry '{}' is not a valid type=smb or type=azure provider and is not monitored",
+            label, entry
+        ));
+    }
+    if size.is_some() && config.size_in_mbs == 0 {
+        warnings.push(format!("{}: SizeInMBs is 0, which is not a valid container size", label));
+    }
+    match config.volume_type.as_str() {
+        "VHDX" => {}
+        "VHD" => warnings.push(format!(
+            "{}: VolumeType is VHD, which is limited to 2 TB and less resilient to interruptions; VHDX is recommended",
+            label
+        )),
+        other => warnings.push(format!("{}: VolumeType '{}' is not valid (VHD or VHDX)", label, other)),
+    }
+    if !config.is_dynamic {
+        warnings.push(format!(
+            "{}: IsDynamic is 0, so every container allocates its full {} MB up front",
+            label, config.size_in_mbs
+        ));
+    }
+
+    config
+}
+
+/// Split CCDLocations into provider entries
+/// Entries are separated by ';', but Azure connection strings wrapped in '|' contain ';' themselves.
+fn split_ccd_locations(value: &str) -> Vec<String> {
+    let mut entries = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in value.chars() {
+        match c {
+            '|' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            ';' if !quoted => entries.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    entries.push(current);
+    entries
+        .into_iter()
+        .map(|entry| entry.trim().to_string())
+        .filter(|entry| !entry.is_empty())
+        .collect()
+}
 
-        for (index, path) in vhd_locations.iter().enumerate() {
+/// Parse a CCDLocations entry such as `type=smb,connectionString=\\server\share`
+/// or `type=azure,connectionString="|DefaultEndpointsProtocol=https;AccountName=...;EndpointSuffix=...|"`
+fn parse_ccd_provider(entry: &str) -> Option<CloudCacheProvider> {
+    // ASCII lowercasing keeps byte offsets, so positions map back to `entry`
+    let start = entry.to_ascii_lowercase().find("connectionstring=")?;
+    let provider_type = entry[..start].split(',').find_map(|part| {
+        let (key, value) = part.split_once('=')?;
+        key.trim().eq_ignore_ascii_case("type").then(|| value.trim().to_ascii_lowercase())
+    })?;
+    let connection = entry[start + "connectionstring=".len()..]
+        .trim()
+        .trim_matches('"')
+        .trim_matches('|')
+        .trim();
+
+    match provider_type.as_str() {
+        "smb" => {
+            let path = connection.to_string();
+            let hostname = extract_hostname(&path).filter(|_| path.starts_with("\\\\"))?;
+            Some(CloudCacheProvider::Smb { path, hostname })
+        }
+        "azure" => parse_azure_connection_string(connection),
+        _ => None,
+    }
+}
+
+/// Blob endpoint of an Azure Storage connection string
+/// An explicit BlobEndpoint wins; otherwise it is built from AccountName and EndpointSuffix.
+fn parse_azure_connection_string(connection: &str) -> Option<CloudCacheProvider> {
+    let mut account = None;
+    let mut suffix = "core.windows.net".to_string();
+    let mut blob_endpoint = None;
+    for pair in connection.split(';') {
+        let Some((key, value)) = pair.split_once('=') else { continue };
+        let value = value.trim();
+        match key.trim().to_ascii_lowercase().as_str() {
+            "accountname" => account = Some(value.to_string()),
+            "endpointsuffix" => suffix = value.to_string(),
+            "blobendpoint" => blob_endpoint = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    let hostname = match blob_endpoint {
+        Some(endpoint) => {
+            let host = endpoint.split_once("://").map_or(endpoint.as_str(), |(_, rest)| rest);
+            host.split(['/', ':']).next().unwrap_or_default().to_string()
+        }
+        None => format!("{}.blob.{}", account.as_deref()?, suffix),
+    };
+    if hostname.is_empty() {
+        return None;
+    }
+    let account = account.unwrap_or_else(|| hostname.split('.').next().unwrap_or_default().to_string());
+    Some(CloudCacheProvider::Azure { account, hostname })
+}
+
+/// Mask account keys and SAS tokens in a CCDLocations entry
+fn redact_ccd_entry(entry: &str) -> String {
+    entry
+        .split(';')
+        .map(|segment| {
+            let lower = segment.to_ascii_lowercase();
+            let secret = ["accountkey=", "sharedaccesssignature="]
+                .iter()
+                .find_map(|key| lower.find(key).map(|pos| pos + key.len()));
+            match secret {
+                Some(pos) => {
+                    let value = &segment[pos..];
+                    let tail = &value[value.trim_end_matches(['|', '"']).len()..];
+                    format!("{}***{}", &segment[..pos], tail)
+                }
+                None => segment.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Stable path ID from the container type, the source and the normalized path
+/// Case, separators and trailing slashes don't change the ID, so reordering or retyping
+/// VHDLocations keeps muted state attached to the right share.
+fn path_id(path_type: &str, cloud_cache: bool, path: &str) -> String {
+    let normalized = path.trim().replace('/', "\\").trim_end_matches('\\').to_lowercase();
+    let source = if cloud_cache { "ccd" } else { "vhd" };
+    let key = format!("{}|{}|{}", path_type, source, normalized);
+    format!("fslogix-{}-{:016x}", path_type, fnv1a(key.as_bytes()))
+}
+
+/// 64-bit FNV-1a - stable across Rust releases, unlike `DefaultHasher`
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Storage paths to monitor from the VHDLocations and CCDLocations of both container types
+fn paths_from_config(config: &FSLogixConfig) -> Vec<FSLogixPath> {
+    let mut paths = Vec::new();
+    for container in [&config.profiles, &config.odfc] {
+        for (index, path) in container.vhd_locations.iter().enumerate() {
             if let Some(hostname) = extract_hostname(path) {
                 paths.push(FSLogixPath {
-                    id: format!("fslogix-odfc-{}", index),
-                    path_type: "odfc".to_string(),
+                    id: path_id(&container.container_type, false, path),
+                    path_type: container.container_type.clone(),
                     path: path.clone(),
                     hostname,
                     port: 445, // SMB port
                     muted: None,
+                    provider: StorageProvider::Smb,
+                    cloud_cache: false,
+                    legacy_id: Some(format!("fslogix-{}-{}", container.container_type, index)),
                 });
             }
         }
+
+        let providers = container.ccd_locations.iter().filter_map(|entry| parse_ccd_provider(entry));
+        paths.extend(providers.map(|provider| provider.into_path(&container.container_type)));
     }
+    paths
+}
+
+#[cfg(target_os = "windows")]
+pub fn get_fslogix_config() -> FSLogixConfig {
+    read_fslogix_config(&WindowsRegistry)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn get_fslogix_config() -> FSLogixConfig {
+    // FSLogix is Windows-only - report an empty registry
+    read_fslogix_config(&HashMap::new())
+}
 
+#[cfg(target_os = "windows")]
+pub fn get_fslogix_paths() -> Vec<FSLogixPath> {
+    let paths = paths_from_config(&get_fslogix_config());
+    logger::info("FSLogix", &format!("Found {} storage path(s) in registry", paths.len()));
     paths
 }
 
 #[cfg(not(target_os = "windows"))]
 pub fn get_fslogix_paths() -> Vec<FSLogixPath> {
-    // FSLogix is Windows-only
-    Vec::new()
+    // FSLogix is Windows-only - there are no paths to find
+    paths_from_config(&get_fslogix_config())
 }
 
 #[cfg(test)]
@@ -149,6 +792,50 @@ mod tests {
         assert_eq!(extract_hostname(r"\\"), None);
     }
 
+    #[test]
+    fn test_classify_private_endpoint() {
+        let chain = vec![
+            "acct.file.core.windows.net".to_string(),
+            "acct.privatelink.file.core.windows.net".to_string(),
+        ];
+        let addrs: Vec<IpAddr> = vec!["10.1.2.3".parse().unwrap()];
+        let result = classify_resolution("acct.file.core.windows.net", &addrs, &chain);
+        assert_eq!(result.exposure, StorageExposure::Private);
+        assert!(result.private_link);
+        assert!(result.warning.is_none());
+    }
+
+    #[test]
+    fn test_classify_bypassed_private_endpoint_warns() {
+        let chain = vec![
+            "acct.file.core.windows.net".to_string(),
+            "acct.privatelink.file.core.windows.net".to_string(),
+            "file.ams.data.core.windows.net".to_string(),
+        ];
+        let addrs: Vec<IpAddr> = vec!["20.60.1.2".parse().unwrap()];
+        let result = classify_resolution("acct.file.core.windows.net", &addrs, &chain);
+        assert_eq!(result.exposure, StorageExposure::PublicAzure);
+        assert!(result.warning.unwrap().contains("20.60.1.2"));
+    }
+
+    #[test]
+    fn test_classify_public_and_unresolvable() {
+        let addrs: Vec<IpAddr> = vec!["20.60.1.2".parse().unwrap()];
+        let result = classify_resolution("acct.file.core.windows.net", &addrs, &[]);
+        assert_eq!(result.exposure, StorageExposure::PublicAzure);
+        assert!(result.warning.is_none());
+
+        let result = classify_resolution("fileserver.contoso.com", &addrs, &[]);
+        assert_eq!(result.exposure, StorageExposure::Public);
+
+        let result = classify_resolution("missing.file.core.windows.net", &[], &[]);
+        assert_eq!(result.exposure, StorageExposure::Unresolvable);
+
+        let addrs: Vec<IpAddr> = vec!["fd00::5".parse().unwrap(), "192.168.1.10".parse().unwrap()];
+        let result = classify_resolution("fileserver", &addrs, &[]);
+        assert_eq!(result.exposure, StorageExposure::Private);
+    }
+
     #[test]
     fn test_parse_vhd_locations_single() {
         let value = r"\\storage.file.core.windows.net\share";
@@ -180,4 +867,318 @@ mod tests {
         let result = parse_vhd_locations(value);
         assert_eq!(result.len(), 2);
     }
+
+    fn registry(values: &[(&str, &str, RegistryValue)]) -> HashMap<(String, String), RegistryValue> {
+        values
+            .iter()
+            .map(|(key, name, value)| ((key.to_string(), name.to_string()), value.clone()))
+            .collect()
+    }
+
+    const POLICY_PROFILES: &str = r"SOFTWARE\Policies\FSLogix\Profiles";
+    const LOCAL_PROFILES: &str = r"SOFTWARE\FSLogix\Profiles";
+    const POLICY_ODFC: &str = r"SOFTWARE\Policies\FSLogix\ODFC";
+
+    #[test]
+    fn test_read_config_merges_policy_over_local() {
+        let reg = registry(&[
+            (LOCAL_PROFILES, "Enabled", RegistryValue::Dword(1)),
+            (LOCAL_PROFILES, "VHDLocations", RegistryValue::String(r"\\local\profiles".to_string())),
+            (POLICY_PROFILES, "VHDLocations", RegistryValue::MultiString(vec![r"\\policy\profiles".to_string()])),
+            (POLICY_PROFILES, "VolumeType", RegistryValue::String("vhdx".to_string())),
+            (POLICY_PROFILES, "SizeInMBs", RegistryValue::String("50000".to_string())),
+            (POLICY_ODFC, "Enabled", RegistryValue::Dword(1)),
+            (POLICY_ODFC, "VHDLocations", RegistryValue::String(r"\\odfc\share".to_string())),
+            (POLICY_ODFC, "VolumeType", RegistryValue::String("VHDX".to_string())),
+        ]);
+        let config = read_fslogix_config(&reg);
+
+        assert!(config.profiles.enabled);
+        assert_eq!(config.profiles.vhd_locations, vec![r"\\policy\profiles"]);
+        assert_eq!(config.profiles.volume_type, "VHDX");
+        assert_eq!(config.profiles.size_in_mbs, 50000);
+        assert!(config.profiles.is_dynamic);
+        assert_eq!(config.profiles.policy_values, vec!["VHDLocations", "SizeInMBs", "VolumeType"]);
+        assert!(config.odfc.enabled);
+        assert_eq!(config.warnings.len(), 1);
+        assert!(config.warnings[0].contains("VHDLocations"));
+
+        let paths = paths_from_config(&config);
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0].id, path_id("profile", false, r"\\policy\profiles"));
+        assert_eq!(paths[0].legacy_id.as_deref(), Some("fslogix-profile-0"));
+        assert_eq!(paths[0].hostname, "policy");
+        assert_eq!(paths[1].legacy_id.as_deref(), Some("fslogix-odfc-0"));
+    }
+
+    #[test]
+    fn test_read_config_defaults_without_registry() {
+        let config = read_fslogix_config(&HashMap::new());
+        assert!(!config.profiles.enabled);
+        assert_eq!(config.profiles.size_in_mbs, DEFAULT_SIZE_IN_MBS);
+        assert_eq!(config.profiles.volume_type, "VHD");
+        assert!(config.warnings.is_empty());
+        assert!(paths_from_config(&config).is_empty());
+    }
+
+    #[test]
+    fn test_read_config_warnings() {
+        let reg = registry(&[
+            (LOCAL_PROFILES, "Enabled", RegistryValue::Dword(1)),
+            (LOCAL_PROFILES, "VHDLocations", RegistryValue::String(r"\\server\profiles;C:\Profiles".to_string())),
+            (
+                LOCAL_PROFILES,
+                "CCDLocations",
+                RegistryValue::String(r"type=smb,connectionString=\\server\ccd".to_string()),
+            ),
+            (LOCAL_PROFILES, "IsDynamic", RegistryValue::Dword(0)),
+            (POLICY_ODFC, "VHDLocations", RegistryValue::String(r"\\odfc\share".to_string())),
+            (POLICY_ODFC, "VolumeType", RegistryValue::String("VHDX".to_string())),
+        ]);
+        let config = read_fslogix_config(&reg);
+        let has = |text: &str| config.warnings.iter().any(|w| w.contains(text));
+
+        assert!(has("Cloud Cache takes precedence"));
+        assert!(has(r"'C:\Profiles' is not a UNC path"));
+        assert!(has("VolumeType is VHD"));
+        assert!(has("allocates its full 30000 MB"));
+        assert!(has("Office Container: storage locations are configured but Enabled is not 1"));
+        assert_eq!(config.warnings.len(), 5);
+    }
+
+    #[test]
+    fn test_split_ccd_locations_keeps_azure_connection_strings() {
+        let value = "type=smb,connectionString=\\\\server\\ccd;\
+                     type=azure,connectionString=\"|DefaultEndpointsProtocol=https;AccountName=acct;AccountKey=key==;EndpointSuffix=core.windows.net|\"";
+        let entries = split_ccd_locations(value);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0], r"type=smb,connectionString=\\server\ccd");
+        assert!(entries[1].starts_with("type=azure,"));
+        assert!(entries[1].ends_with("core.windows.net|\""));
+    }
+
+    #[test]
+    fn test_path_ids_are_stable_across_reordering() {
+        let first = registry(&[(
+            LOCAL_PROFILES,
+            "VHDLocations",
+            RegistryValue::String(r"\\server1\profiles;\\server2\profiles".to_string()),
+        )]);
+        let reordered = registry(&[(
+            LOCAL_PROFILES,
+            "VHDLocations",
+            RegistryValue::String(r"\\SERVER2\Profiles\;\\server1\profiles".to_string()),
+        )]);
+        let first = paths_from_config(&read_fslogix_config(&first));
+        let reordered = paths_from_config(&read_fslogix_config(&reordered));
+
+        assert_eq!(first[0].id, reordered[1].id);
+        assert_eq!(first[1].id, reordered[0].id);
+        assert_ne!(first[0].id, first[1].id);
+        assert!(first[0].id.starts_with("fslogix-profile-"));
+
+        // The same share as an ODFC or Cloud Cache location is a different path
+        assert_ne!(path_id("profile", false, r"\\server1\profiles"), path_id("odfc", false, r"\\server1\profiles"));
+        assert_ne!(path_id("profile", false, r"\\server1\profiles"), path_id("profile", true, r"\\server1\profiles"));
+    }
+
+    #[test]
+    fn test_parse_ccd_smb_provider() {
+        let provider = parse_ccd_provider(r"type=smb,connectionString=\\fileserver\ccd").unwrap();
+        assert_eq!(
+            provider,
+            CloudCacheProvider::Smb {
+                path: r"\\fileserver\ccd".to_string(),
+                hostname: "fileserver".to_string(),
+            }
+        );
+        assert!(parse_ccd_provider(r"type=smb,connectionString=C:\ccd").is_none());
+        assert!(parse_ccd_provider(r"type=nfs,connectionString=\\server\share").is_none());
+        assert!(parse_ccd_provider(r"\\server\share").is_none());
+    }
+
+    #[test]
+    fn test_parse_ccd_azure_provider() {
+        let entry = "type=azure,connectionString=\"|DefaultEndpointsProtocol=https;AccountName=acct;\
+                     AccountKey=abc+def==;EndpointSuffix=core.usgovcloudapi.net|\"";
+        assert_eq!(
+            parse_ccd_provider(entry),
+            Some(CloudCacheProvider::Azure {
+                account: "acct".to_string(),
+                hostname: "acct.blob.core.usgovcloudapi.net".to_string(),
+            })
+        );
+
+        let entry = "Type=Azure,ConnectionString=\"|BlobEndpoint=https://acct.privatelink.blob.core.windows.net/;SharedAccessSignature=sv=2022&sig=x|\"";
+        assert_eq!(
+            parse_ccd_provider(entry),
+            Some(CloudCacheProvider::Azure {
+                account: "acct".to_string(),
+                hostname: "acct.privatelink.blob.core.windows.net".to_string(),
+            })
+        );
+
+        assert!(parse_ccd_provider("type=azure,connectionString=\"|AccountKey=abc|\"").is_none());
+    }
+
+    #[test]
+    fn test_redact_ccd_entry() {
+        let entry = "type=azure,connectionString=\"|AccountName=acct;AccountKey=abc+def==;EndpointSuffix=core.windows.net|\"";
+        assert_eq!(
+            redact_ccd_entry(entry),
+            "type=azure,connectionString=\"|AccountName=acct;AccountKey=***;EndpointSuffix=core.windows.net|\""
+        );
+        let entry = "type=azure,connectionString=\"|BlobEndpoint=https://acct.blob.core.windows.net;SharedAccessSignature=sv=1&sig=2|\"";
+        assert!(redact_ccd_entry(entry).ends_with("SharedAccessSignature=***|\""));
+    }
+
+    #[test]
+    fn test_read_config_warnings_redact_ccd_secrets() {
+        let reg = registry(&[
+            (
+                POLICY_PROFILES,
+                "CCDLocations",
+                RegistryValue::String(
+                    "type=azure,connectionString=\"|AccountName=acct;AccountKey=policy-key==;EndpointSuffix=core.windows.net|\""
+                        .to_string(),
+                ),
+            ),
+            (
+                LOCAL_PROFILES,
+                "CCDLocations",
+                RegistryValue::String(
+                    "type=azure,connectionString=\"|BlobEndpoint=https://acct.blob.core.windows.net;SharedAccessSignature=sv=1&sig=local-sig|\""
+                        .to_string(),
+                ),
+            ),
+        ]);
+        let config = read_fslogix_config(&reg);
+        let warning = config.warnings.iter().find(|w| w.contains("CCDLocations is")).unwrap();
+
+        assert!(warning.contains("AccountKey=***"));
+        assert!(warning.contains("SharedAccessSignature=***"));
+        assert!(config.warnings.iter().all(|w| !w.contains("policy-key") && !w.contains("local-sig")));
+    }
+
+    #[test]
+    fn test_cloud_cache_paths() {
+        let reg = registry(&[
+            (POLICY_PROFILES, "Enabled", RegistryValue::Dword(1)),
+            (POLICY_PROFILES, "VolumeType", RegistryValue::String("VHDX".to_string())),
+            (
+                POLICY_PROFILES,
+                "CCDLocations",
+                RegistryValue::String(
+                    "type=smb,connectionString=\\\\fileserver\\ccd;type=azure,connectionString=\"|AccountName=acct;\
+                     AccountKey=secret==|\";type=blob,connectionString=x"
+                        .to_string(),
+                ),
+            ),
+        ]);
+        let config = read_fslogix_config(&reg);
+        assert_eq!(config.profiles.ccd_locations.len(), 3);
+        assert!(config.profiles.ccd_locations.iter().all(|entry| !entry.contains("secret")));
+        assert_eq!(config.warnings.len(), 1);
+        assert!(config.warnings[0].contains("type=blob"));
+
+        let paths = paths_from_config(&config);
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0].id, path_id("profile", true, r"\\fileserver\ccd"));
+        assert_eq!(paths[0].hostname, "fileserver");
+        assert_eq!(paths[0].protocol(), "smb");
+        assert!(paths[0].cloud_cache);
+        assert_eq!(paths[1].hostname, "acct.blob.core.windows.net");
+        assert_eq!(paths[1].path, "https://acct.blob.core.windows.net");
+        assert_eq!(paths[1].port, 443);
+        assert_eq!(paths[1].protocol(), "https");
+    }
+
+    /// Share accessor that fails with a fixed error, or succeeds when there is none
+    struct FakeAccessor {
+        error: Option<fn() -> std::io::Error>,
+        delay: Duration,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl ShareAccessor for FakeAccessor {
+        fn list_dir(&self, _path: &str) -> std::io::Result<()> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            std::thread::sleep(self.delay);
+            self.error.map_or(Ok(()), |make| Err(make()))
+        }
+    }
+
+    fn share_path() -> FSLogixPath {
+        FSLogixPath {
+            id: "fslogix-profile-0".to_string(),
+            path_type: "profile".to_string(),
+            path: r"\\storage.file.core.windows.net\profiles".to_string(),
+            hostname: "storage.file.core.windows.net".to_string(),
+            port: 445,
+            muted: None,
+            provider: StorageProvider::Smb,
+            cloud_cache: false,
+            legacy_id: None,
+        }
+    }
+
+    async fn run_check(error: Option<fn() -> std::io::Error>, delay: Duration) -> ShareCheck {
+        let accessor = Arc::new(FakeAccessor { error, delay, calls: Default::default() });
+        check_share(&share_path(), accessor, Duration::from_millis(200)).await
+    }
+
+    #[tokio::test]
+    async fn test_check_share_categories() {
+        let check = run_check(None, Duration::ZERO).await;
+        assert_eq!(check.access, ShareAccess::Ok);
+        assert!(check.detail.is_none());
+
+        let check = run_check(Some(|| ErrorKind::PermissionDenied.into()), Duration::ZERO).await;
+        assert_eq!(check.access, ShareAccess::AccessDenied);
+        assert!(check.detail.is_some());
+
+        let check = run_check(Some(|| ErrorKind::NotFound.into()), Duration::ZERO).await;
+        assert_eq!(check.access, ShareAccess::ShareMissing);
+
+        let check = run_check(Some(|| ErrorKind::HostUnreachable.into()), Duration::ZERO).await;
+        assert_eq!(check.access, ShareAccess::HostUnreachable);
+
+        let check = run_check(Some(|| std::io::Error::other("boom")), Duration::ZERO).await;
+        assert_eq!(check.access, ShareAccess::Error);
+    }
+
+    #[tokio::test]
+    async fn test_check_share_timeout_is_unreachable() {
+        // Own UNC path, so the pending listing doesn't affect the other share tests
+        let path = FSLogixPath {
+            path: r"\\hung.file.core.windows.net\profiles".to_string(),
+            hostname: "hung.file.core.windows.net".to_string(),
+            ..share_path()
+        };
+        let accessor = Arc::new(FakeAccessor { error: None, delay: Duration::from_millis(1500), calls: Default::default() });
+        let limit = Duration::from_millis(200);
+
+        let check = check_share(&path, accessor.clone(), limit).await;
+        assert_eq!(check.access, ShareAccess::HostUnreachable);
+        assert!(check.detail.unwrap().contains("hung.file.core.windows.net"));
+
+        // The first listing is still blocked - no second thread is started
+        let check = check_share(&path, accessor.clone(), limit).await;
+        assert_eq!(check.access, ShareAccess::Pending);
+        assert_eq!(accessor.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // Once it returns, the next round checks again
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        check_share(&path, accessor.clone(), limit).await;
+        assert_eq!(accessor.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_classify_share_error_win32_codes() {
+        let classify = |code| classify_share_error(&std::io::Error::from_raw_os_error(code));
+        assert_eq!(classify(53), ShareAccess::HostUnreachable);
+        assert_eq!(classify(67), ShareAccess::ShareMissing);
+        assert_eq!(classify(1326), ShareAccess::AccessDenied);
+    }
 }
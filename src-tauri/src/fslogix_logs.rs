@@ -0,0 +1,347 @@
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Hours of profile logs summarized when no window is given
+pub const DEFAULT_LOG_HOURS: u32 = 24;
+
+/// An ERROR line logged during a profile session
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileLogError {
+    pub time: NaiveDateTime,
+    /// Win32 error code from the `[ERROR:xxxxxxxx]` tag
+    pub code: Option<u32>,
+    pub message: String,
+}
+
+/// Outcome of a profile session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProfileSessionStatus {
+    Succeeded,
+    /// The session logged at least one error
+    Failed,
+    /// No end marker - still running, or the service stopped mid-session
+    Incomplete,
+}
+
+/// One `Begin Session` / `End Session` block of an FSLogix profile log
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSession {
+    /// "Logon" or "Logoff"
+    pub operation: String,
+    /// DOMAIN\user
+    pub user: Option<String>,
+    pub sid: Option<String>,
+    pub started: NaiveDateTime,
+    pub ended: Option<NaiveDateTime>,
+    pub status: ProfileSessionStatus,
+    pub errors: Vec<ProfileLogError>,
+    pub warnings: usize,
+    /// Name of the log file the session was read from
+    pub log_file: String,
+}
+
+/// Recent profile sessions, newest first
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileLogSummary {
+    pub directory: String,
+    pub sessions: Vec<ProfileSession>,
+    pub failed_sessions: usize,
+    /// Log files that could not be read and were skipped
+    pub warnings: Vec<String>,
+}
+
+/// A parsed `[time][tid:...][LEVEL:code]  message` line
+struct LogLine<'a> {
+    time: NaiveTime,
+    /// Process and thread ID; concurrent sessions are logged by different threads
+    tid: &'a str,
+    level: &'a str,
+    code: Option<u32>,
+    message: &'a str,
+}
+
+/// %ProgramData%\FSLogix\Logs\Profile, None when ProgramData is not set (non-Windows)
+pub fn default_log_directory() -> Option<PathBuf> {
+    let program_data = std::env::var_os("ProgramData")?;
+    Some(PathBuf::from(program_data).join("FSLogix").join("Logs").join("Profile"))
+}
+
+/// Summarize the profile sessions started in the last `hours` from the logs in `directory`
+/// A missing directory means FSLogix is not installed and gives an empty summary. A log file
+/// that can't be read (e.g. locked or denied) is skipped with a warning.
+pub fn read_profile_sessions(directory: &Path, hours: u32, now: NaiveDateTime) -> std::io::Result<ProfileLogSummary> {
+    let since = now - Duration::hours(hours as i64);
+    let mut sessions = Vec::new();
+    let mut warnings = Vec::new();
+
+    if directory.is_dir() {
+        for entry in std::fs::read_dir(directory)? {
+            let path = match entry {
+                Ok(entry) => entry.path(),
+                Err(e) => {
+                    warnings.push(format!("Skipped an entry of {}: {}", directory.display(), e));
+                    continue;
+                }
+            };
+            let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            if !name.to_ascii_lowercase().ends_with(".log") {
+                continue;
+            }
+
+            // The file name carries the date; fall back to the modification date
+            let date = log_date_from_file_name(&name).or_else(|| {
+                let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+                Some(chrono::DateTime::<chrono::Local>::from(modified).date_naive())
+            });
+            let Some(date) = date else { continue };
+            // A file also holds sessions that ran past midnight into the next day
+            if date.succ_opt().unwrap_or(date) < since.date() {
+                continue;
+            }
+
+            let content = match std::fs::read(&path) {
+                Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+                Err(e) => {
+                    warnings.push(format!("Skipped {}: {}", name, e));
+                    continue;
+                }
+            };
+            sessions.extend(parse_profile_log(&content, date, &name).into_iter().filter(|s| s.started >= since));
+        }
+    }
+
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.started));
+    let failed_sessions = sessions.iter().filter(|s| s.status == ProfileSessionStatus::Failed).count();
+    Ok(ProfileLogSummary {
+        directory: directory.display().to_string(),
+        sessions,
+        failed_sessions,
+        warnings,
+    })
+}
+
+/// Date of a `Profile-YYYYMMDD.log` file
+fn log_date_from_file_name(name: &str) -> Option<NaiveDate> {
+    let stem = name.rsplit_once('.')?.0;
+    let digits = stem.rsplit('-').next()?;
+    NaiveDate::parse_from_str(digits, "%Y%m%d").ok()
+}
+
+/// Split a profile log into sessions, ordered by start time
+/// Lines only carry a time, so `date` is the day the file starts. Sessions of users logging on at
+/// the same time interleave, so lines are matched to the open session of their thread.
+pub fn parse_profile_log(content: &str, date: NaiveDate, log_file: &str) -> Vec<ProfileSession> {
+    let mut sessions = Vec::new();
+    let mut open: HashMap<&str, ProfileSession> = HashMap::new();
+    let mut date = date;
+    let mut last_time: Option<NaiveTime> = None;
+
+    for line in content.lines() {
+        let Some(line) = parse_line(line.trim_start_matches('\u{feff}')) else { continue };
+
+        // The clock going back by more than 12 hours means the log rolled past midnight
+        if last_time.is_some_and(|last| last - line.time > Duration::hours(12)) {
+            date = date.succ_opt().unwrap_or(date);
+        }
+        last_time = Some(line.time);
+        let time = date.and_time(line.time);
+
+        if let Some(operation) = line.message.strip_prefix("===== Begin Session:") {
+            let session = ProfileSession {
+                operation: operation.trim().to_string(),
+                user: None,
+                sid: None,
+                started: time,
+                ended: None,
+                status: ProfileSessionStatus::Incomplete,
+                errors: Vec::new(),
+                warnings: 0,
+                log_file: log_file.to_string(),
+            };
+            sessions.extend(open.insert(line.tid, session).map(unterminated));
+            continue;
+        }
+
+        let Some(session) = open.get_mut(line.tid) else { continue };
+        if line.message.starts_with("===== End Session:") {
+            session.ended = Some(time);
+            session.status = if session.errors.is_empty() {
+                ProfileSessionStatus::Succeeded
+            } else {
+                ProfileSessionStatus::Failed
+            };
+            sessions.extend(open.remove(line.tid));
+        } else if let Some(user) = line.message.strip_prefix("User:") {
+            let user = user.trim();
+            if user.starts_with("S-1-") {
+                session.sid.get_or_insert_with(|| user.to_string());
+            } else {
+                session.user.get_or_insert_with(|| user.to_string());
+            }
+        } else if line.level == "ERROR" {
+            session.errors.push(ProfileLogError {
+                time,
+                code: line.code,
+                message: line.message.to_string(),
+            });
+        } else if line.level == "WARN" {
+            session.warnings += 1;
+        }
+    }
+
+    sessions.extend(open.into_values().map(unterminated));
+    sessions.sort_by_key(|s| s.started);
+    sessions
+}
+
+/// A session without an end marker that already logged errors has failed regardless
+fn unterminated(mut session: ProfileSession) -> ProfileSession {
+    if !session.errors.is_empty() {
+        session.status = ProfileSessionStatus::Failed;
+    }
+    session
+}
+
+fn parse_line(line: &str) -> Option<LogLine<'_>> {
+    let (time, rest) = line.strip_prefix('[')?.split_once(']')?;
+    let time = NaiveTime::parse_from_str(time, "%H:%M:%S%.3f").ok()?;
+    let (tid, rest) = rest.strip_prefix('[')?.split_once(']')?;
+    let (tag, message) = rest.strip_prefix('[')?.split_once(']')?;
+    let (level, code) = match tag.split_once(':') {
+        Some((level, code)) => (level, u32::from_str_radix(code, 16).ok()),
+        None => (tag, None),
+    };
+
+    Some(LogLine {
+        time,
+        tid,
+        level,
+        code,
+        message: message.trim(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/fslogix/Profile-20260115.log");
+    const INTERLEAVED_FIXTURE: &str = include_str!("../tests/fixtures/fslogix/Profile-20260110.log");
+
+    fn at(day: u32, time: &str) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 1, day)
+            .unwrap()
+            .and_time(NaiveTime::parse_from_str(time, "%H:%M:%S%.3f").unwrap())
+    }
+
+    #[test]
+    fn test_parse_profile_log_sessions() {
+        let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+        let sessions = parse_profile_log(FIXTURE, date, "Profile-20260115.log");
+        assert_eq!(sessions.len(), 4);
+
+        let alice = &sessions[0];
+        assert_eq!(alice.operation, "Logon");
+        assert_eq!(alice.user.as_deref(), Some(r"CONTOSO\alice"));
+        assert_eq!(alice.sid.as_deref(), Some("S-1-5-21-1004336348-1177238915-682003330-1105"));
+        assert_eq!(alice.status, ProfileSessionStatus::Succeeded);
+        assert_eq!(alice.started, at(15, "07:53:44.857"));
+        assert_eq!(alice.ended, Some(at(15, "07:53:46.210")));
+
+        let bob = &sessions[1];
+        assert_eq!(bob.status, ProfileSessionStatus::Failed);
+        assert_eq!(bob.warnings, 1);
+        assert_eq!(bob.errors.len(), 2);
+        assert_eq!(bob.errors[0].code, Some(32));
+        assert_eq!(bob.errors[1].code, Some(31));
+        assert!(bob.errors[0].message.starts_with("Failed to attach VHD"));
+
+        let logoff = &sessions[2];
+        assert_eq!(logoff.operation, "Logoff");
+        assert_eq!(logoff.status, ProfileSessionStatus::Failed);
+        assert_eq!(logoff.errors[0].code, Some(5));
+
+        // Still attaching past midnight when the log ends
+        let carol = &sessions[3];
+        assert_eq!(carol.status, ProfileSessionStatus::Incomplete);
+        assert_eq!(carol.started, at(15, "23:59:59.900"));
+        assert_eq!(carol.ended, None);
+    }
+
+    #[test]
+    fn test_parse_interleaved_sessions() {
+        let date = NaiveDate::from_ymd_opt(2026, 1, 10).unwrap();
+        let sessions = parse_profile_log(INTERLEAVED_FIXTURE, date, "Profile-20260110.log");
+        assert_eq!(sessions.len(), 2);
+
+        let dave = &sessions[0];
+        assert_eq!(dave.user.as_deref(), Some(r"CONTOSO\dave"));
+        assert_eq!(dave.status, ProfileSessionStatus::Succeeded);
+        assert_eq!(dave.warnings, 0);
+        assert_eq!(dave.ended, Some(at(10, "08:01:11.200")));
+
+        // Erin's session began while Dave's was open and keeps its own warning and error
+        let erin = &sessions[1];
+        assert_eq!(erin.user.as_deref(), Some(r"CONTOSO\erin"));
+        assert_eq!(erin.sid.as_deref(), Some("S-1-5-21-1004336348-1177238915-682003330-1112"));
+        assert_eq!(erin.status, ProfileSessionStatus::Failed);
+        assert_eq!(erin.warnings, 1);
+        assert_eq!(erin.errors.len(), 1);
+        assert_eq!(erin.ended, Some(at(10, "08:01:20.700")));
+    }
+
+    #[test]
+    fn test_parse_line_ignores_garbage() {
+        let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+        let content = "not a log line\n[bad][tid:1][INFO] x\n[10:00:00.000][tid:1][ERROR:zz]   orphan error\n";
+        assert!(parse_profile_log(content, date, "Profile.log").is_empty());
+    }
+
+    #[test]
+    fn test_log_date_from_file_name() {
+        assert_eq!(log_date_from_file_name("Profile-20260115.log"), NaiveDate::from_ymd_opt(2026, 1, 15));
+        assert_eq!(log_date_from_file_name("Profile.log"), None);
+    }
+
+    #[test]
+    fn test_read_profile_sessions_from_directory() {
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/fslogix");
+
+        let summary = read_profile_sessions(&directory, 12, at(16, "01:00:00.000")).unwrap();
+        assert_eq!(summary.sessions.len(), 2);
+        // Newest first
+        assert_eq!(summary.sessions[0].user.as_deref(), Some(r"CONTOSO\carol"));
+        assert_eq!(summary.sessions[1].operation, "Logoff");
+        assert_eq!(summary.failed_sessions, 1);
+
+        let summary = read_profile_sessions(&directory, 24, at(20, "00:00:00.000")).unwrap();
+        assert!(summary.sessions.is_empty());
+
+        let summary = read_profile_sessions(&directory.join("missing"), 24, at(16, "00:00:00.000")).unwrap();
+        assert!(summary.sessions.is_empty());
+    }
+
+    #[test]
+    fn test_unreadable_log_is_skipped_with_warning() {
+        let directory = std::env::temp_dir().join(format!("avd-health-monitor-profile-logs-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/fslogix/Profile-20260115.log");
+        std::fs::copy(&fixture, directory.join("Profile-20260115.log")).unwrap();
+        // A directory with a log file name can't be read as a file
+        std::fs::create_dir(directory.join("Profile-20260116.log")).unwrap();
+
+        let summary = read_profile_sessions(&directory, 48, at(16, "12:00:00.000")).unwrap();
+        assert!(!summary.sessions.is_empty());
+        assert_eq!(summary.warnings.len(), 1);
+        assert!(summary.warnings[0].starts_with("Skipped Profile-20260116.log"));
+
+        let _ = std::fs::remove_dir_all(&directory);
+    }
+}
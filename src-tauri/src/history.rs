@@ -0,0 +1,404 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use crate::alerts::Incident;
+use crate::settings::get_settings_dir;
+
+const HISTORY_DIRNAME: &str = "history";
+const SEGMENT_EXTENSION: &str = "jsonl";
+/// Not a date, so the history segments ignore it
+const INCIDENTS_FILENAME: &str = "incidents.jsonl";
+
+/// A single probe result stored in the history
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryRecord {
+    /// Endpoint ID or FSLogix path ID
+    pub endpoint_id: String,
+    /// Unix timestamp in milliseconds
+    pub timestamp: i64,
+    /// Latency in milliseconds, None if the probe failed
+    pub latency: Option<f64>,
+    pub success: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Stable error code (see `ProbeError::code`), None for records written before codes existed
+    #[serde(default)]
+    pub error_code: Option<String>,
+}
+
+/// Append-only time-series store for probe results
+/// Records are written as JSON lines into one segment file per UTC day
+/// (e.g. history/2024-05-01.jsonl), so retention is enforced by deleting whole files.
+pub struct HistoryStore {
+    dir: PathBuf,
+}
+
+impl HistoryStore {
+    /// Open the store in the given directory, creating it if needed
+    pub fn new(dir: PathBuf) -> std::io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    /// Open the store next to the settings file
+    pub fn open_default() -> std::io::Result<Self> {
+        Self::new(get_settings_dir()?.join(HISTORY_DIRNAME))
+    }
+
+    fn segment_path(&self, day: NaiveDate) -> PathBuf {
+        self.dir
+            .join(format!("{}.{}", day.format("%Y-%m-%d"), SEGMENT_EXTENSION))
+    }
+
+    /// Append records to the segment files of their respective days
+    pub fn append(&self, records: &[HistoryRecord]) -> std::io::Result<()> {
+        let mut current: Option<(NaiveDate, fs::File)> = None;
+
+        for record in records {
+            let day = day_of(record.timestamp);
+
+            // Records usually arrive in batches for the same day, so keep the segment open
+            if current.as_ref().map(|(d, _)| *d) != Some(day) {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(self.segment_path(day))?;
+                current = Some((day, file));
+            }
+            let Some((_, file)) = current.as_mut() else {
+                continue;
+            };
+
+            let line = serde_json::to_string(record)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            writeln!(file, "{}", line)?;
+        }
+
+        Ok(())
+    }
+
+    /// Query records in the inclusive time range [from, to] (Unix milliseconds)
+    /// If `endpoint_id` is None, records for all endpoints are returned.
+    /// Results are ordered by timestamp.
+    pub fn query(&self, endpoint_id: Option<&str>, from: i64, to: i64) -> std::io::Result<Vec<HistoryRecord>> {
+        let mut results = Vec::new();
+        if from > to {
+            return Ok(results);
+        }
+
+        let first_day = day_of(from);
+        let last_day = day_of(to);
+
+        for (day, path) in self.segments()? {
+            if day < first_day || day > last_day {
+                continue;
+            }
+
+            let reader = BufReader::new(fs::File::open(&path)?);
+            for line in reader.lines() {
+                let line = line?;
+                // Skip lines that fail to parse (e.g. partially written after a crash)
+                let Ok(record) = serde_json::from_str::<HistoryRecord>(&line) else {
+                    continue;
+                };
+                if record.timestamp < from || record.timestamp > to {
+                    continue;
+                }
+                if endpoint_id.is_some_and(|id| id != record.endpoint_id) {
+                    continue;
+                }
+                results.push(record);
+            }
+        }
+
+        results.sort_by_key(|r| r.timestamp);
+        Ok(results)
+    }
+
+    /// Delete segment files older than `retention_days` relative to `now`
+    /// Returns the number of deleted segments.
+    pub fn prune(&self, retention_days: u32, now: DateTime<Utc>) -> std::io::Result<usize> {
+        let cutoff = now.date_naive() - chrono::Duration::days(i64::from(retention_days));
+        let mut deleted = 0;
+
+        for (day, path) in self.segments()? {
+            if day < cutoff {
+                fs::remove_file(&path)?;
+                deleted += 1;
+            }
+        }
+
+        Ok(deleted)
+    }
+
+    /// List all segment files with their day, sorted by day
+    fn segments(&self) -> std::io::Result<Vec<(NaiveDate, PathBuf)>> {
+        let mut segments = Vec::new();
+
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if let Some(day) = segment_day(&path) {
+                segments.push((day, path));
+            }
+        }
+
+        segments.sort_by_key(|(day, _)| *day);
+        Ok(segments)
+    }
+}
+
+/// Append-only log of alert incidents, kept next to the history segments
+/// An incident is written when it opens and again when it closes; the last line of an ID wins.
+pub struct IncidentLog {
+    path: PathBuf,
+}
+
+impl IncidentLog {
+    /// Open the log in the given directory, creating it if needed
+    pub fn new(dir: PathBuf) -> std::io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self { path: dir.join(INCIDENTS_FILENAME) })
+    }
+
+    /// Open the log in the history directory next to the settings file
+    pub fn open_default() -> std::io::Result<Self> {
+        Self::new(get_settings_dir()?.join(HISTORY_DIRNAME))
+    }
+
+    pub fn append(&self, incidents: &[Incident]) -> std::io::Result<()> {
+        if incidents.is_empty() {
+            return Ok(());
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        for incident in incidents {
+            let line = serde_json::to_string(incident)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            writeln!(file, "{}", line)?;
+        }
+        Ok(())
+    }
+
+    /// Latest state of every logged incident, ordered by start
+    pub fn load(&self) -> std::io::Result<Vec<Incident>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut incidents: Vec<Incident> = Vec::new();
+        let reader = BufReader::new(fs::File::open(&self.path)?);
+        for line in reader.lines() {
+            let line = line?;
+            // Skip lines that fail to parse (e.g. partially written after a crash)
+            let Ok(incident) = serde_json::from_str::<Incident>(&line) else {
+                continue;
+            };
+            match incidents.iter_mut().find(|i| i.id == incident.id) {
+                Some(existing) => *existing = incident,
+                None => incidents.push(incident),
+            }
+        }
+
+        incidents.sort_by_key(|i| i.started);
+        Ok(incidents)
+    }
+
+    /// Drop incidents that ended more than `retention_days` before `now` and compact the log
+    /// Returns the number of dropped incidents.
+    pub fn prune(&self, retention_days: u32, now: DateTime<Utc>) -> std::io::Result<usize> {
+        let cutoff = (now - chrono::Duration::days(i64::from(retention_days))).timestamp_millis();
+        let mut incidents = self.load()?;
+        let before = incidents.len();
+        incidents.retain(|i| i.ended.is_none_or(|ended| ended >= cutoff));
+
+        // Rewrite through a temporary file so a crash can't lose the whole log
+        let temp = self.path.with_extension("jsonl.tmp");
+        let _ = fs::remove_file(&temp);
+        Self { path: temp.clone() }.append(&incidents)?;
+        if incidents.is_empty() {
+            let _ = fs::remove_file(&self.path);
+        } else {
+            fs::rename(&temp, &self.path)?;
+        }
+        Ok(before - incidents.len())
+    }
+}
+
+/// UTC day of a Unix millisecond timestamp, saturating for out-of-range values
+fn day_of(timestamp_ms: i64) -> NaiveDate {
+    match DateTime::<Utc>::from_timestamp_millis(timestamp_ms) {
+        Some(dt) => dt.date_naive(),
+        None if timestamp_ms < 0 => NaiveDate::MIN,
+        None => NaiveDate::MAX,
+    }
+}
+
+/// Parse the day from a segment file name, ignoring unrelated files
+fn segment_day(path: &Path) -> Option<NaiveDate> {
+    if path.extension()?.to_str()? != SEGMENT_EXTENSION {
+        return None;
+    }
+    let stem = path.file_stem()?.to_str()?;
+    NaiveDate::parse_from_str(stem, "%Y-%m-%d").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn temp_store(name: &str) -> HistoryStore {
+        let dir = std::env::temp_dir().join(format!(
+            "avd-health-monitor-history-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        HistoryStore::new(dir).unwrap()
+    }
+
+    fn record(endpoint_id: &str, timestamp: i64, latency: Option<f64>) -> HistoryRecord {
+        HistoryRecord {
+            endpoint_id: endpoint_id.to_string(),
+            timestamp,
+            latency,
+            success: latency.is_some(),
+            error: latency.is_none().then(|| "Connection timeout".to_string()),
+            error_code: latency.is_none().then(|| "CONNECT_TIMEOUT".to_string()),
+        }
+    }
+
+    fn ms(year: i32, month: u32, day: u32, hour: u32) -> i64 {
+        Utc.with_ymd_and_hms(year, month, day, hour, 0, 0)
+            .unwrap()
+            .timestamp_millis()
+    }
+
+    #[test]
+    fn test_append_and_query_by_endpoint_and_range() {
+        let store = temp_store("query");
+        store
+            .append(&[
+                record("a", ms(2024, 5, 1, 10), Some(12.0)),
+                record("b", ms(2024, 5, 1, 11), Some(20.0)),
+                record("a", ms(2024, 5, 2, 9), None),
+                record("a", ms(2024, 5, 3, 9), Some(15.0)),
+            ])
+            .unwrap();
+
+        let all_a = store.query(Some("a"), ms(2024, 5, 1, 0), ms(2024, 5, 3, 23)).unwrap();
+        assert_eq!(all_a.len(), 3);
+        assert!(!all_a[1].success);
+        assert_eq!(all_a[1].error.as_deref(), Some("Connection timeout"));
+
+        let range = store.query(None, ms(2024, 5, 1, 11), ms(2024, 5, 2, 9)).unwrap();
+        assert_eq!(range.len(), 2);
+        assert_eq!(range[0].endpoint_id, "b");
+        assert_eq!(range[1].endpoint_id, "a");
+    }
+
+    #[test]
+    fn test_records_are_split_into_daily_segments() {
+        let store = temp_store("segments");
+        store
+            .append(&[
+                record("a", ms(2024, 5, 1, 10), Some(12.0)),
+                record("a", ms(2024, 5, 2, 10), Some(13.0)),
+            ])
+            .unwrap();
+
+        let segments = store.segments().unwrap();
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].0, NaiveDate::from_ymd_opt(2024, 5, 1).unwrap());
+    }
+
+    #[test]
+    fn test_prune_by_retention_days() {
+        let store = temp_store("prune");
+        store
+            .append(&[
+                record("a", ms(2024, 4, 1, 10), Some(12.0)),
+                record("a", ms(2024, 4, 25, 10), Some(13.0)),
+                record("a", ms(2024, 5, 1, 10), Some(14.0)),
+            ])
+            .unwrap();
+
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let deleted = store.prune(7, now).unwrap();
+        assert_eq!(deleted, 1);
+
+        let remaining = store.query(Some("a"), 0, i64::MAX).unwrap();
+        assert_eq!(remaining.len(), 2);
+    }
+
+    fn incident(started: i64, ended: Option<i64>) -> Incident {
+        serde_json::from_value(serde_json::json!({
+            "id": format!("endpoints-{}", started),
+            "source": "endpoints",
+            "started": started,
+            "ended": ended,
+            "affected": [{"id": "a", "name": "A"}],
+            "worstLatency": null,
+        }))
+        .unwrap()
+    }
+
+    fn temp_incident_log(name: &str) -> IncidentLog {
+        let dir = std::env::temp_dir().join(format!(
+            "avd-health-monitor-incidents-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        IncidentLog::new(dir).unwrap()
+    }
+
+    #[test]
+    fn test_incident_log_keeps_latest_snapshot() {
+        let log = temp_incident_log("load");
+        assert!(log.load().unwrap().is_empty());
+
+        let opened = incident(ms(2024, 5, 1, 10), None);
+        log.append(&[opened.clone(), incident(ms(2024, 4, 30, 8), Some(ms(2024, 4, 30, 9)))]).unwrap();
+        log.append(&[Incident { ended: Some(ms(2024, 5, 1, 11)), ..opened }]).unwrap();
+
+        let incidents = log.load().unwrap();
+        assert_eq!(incidents.len(), 2);
+        assert_eq!(incidents[0].started, ms(2024, 4, 30, 8));
+        assert_eq!(incidents[1].ended, Some(ms(2024, 5, 1, 11)));
+    }
+
+    #[test]
+    fn test_incident_log_prune_by_retention_days() {
+        let log = temp_incident_log("prune");
+        log.append(&[
+            incident(ms(2024, 4, 1, 10), Some(ms(2024, 4, 1, 11))),
+            incident(ms(2024, 4, 28, 10), Some(ms(2024, 4, 28, 11))),
+            // Still open - kept however old
+            incident(ms(2024, 4, 2, 10), None),
+        ])
+        .unwrap();
+
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        assert_eq!(log.prune(7, now).unwrap(), 1);
+        let incidents = log.load().unwrap();
+        assert_eq!(incidents.len(), 2);
+        assert_eq!(incidents[0].ended, None);
+    }
+
+    #[test]
+    fn test_query_skips_corrupt_lines() {
+        let store = temp_store("corrupt");
+        store.append(&[record("a", ms(2024, 5, 1, 10), Some(12.0))]).unwrap();
+
+        let path = store.segment_path(NaiveDate::from_ymd_opt(2024, 5, 1).unwrap());
+        let mut file = OpenOptions::new().append(true).open(path).unwrap();
+        write!(file, "{{\"endpointId\":\"a\",\"timest").unwrap();
+
+        let records = store.query(Some("a"), 0, i64::MAX).unwrap();
+        assert_eq!(records.len(), 1);
+    }
+}
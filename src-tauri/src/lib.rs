@@ -1,5 +1,6 @@
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::{
     image::Image,
@@ -9,48 +10,122 @@ use tauri::{
 };
 use tauri_plugin_notification::NotificationExt;
 
+mod alerts;
+mod event_log;
 mod latency;
+mod errors;
+mod dns;
+mod stun;
+mod smb;
+mod traceroute;
 mod tray_icon;
 mod logger;
 mod autostart;
 mod settings;
 mod fslogix;
+mod fslogix_logs;
+mod scheduler;
+mod history;
+mod metrics;
+mod webhooks;
+mod cli;
 
 use tray_icon::{generate_tray_icon, IconStatus, LatencyThresholds};
 use logger::Logger;
-use settings::{SettingsFile, SettingsResponse, AppMode, FSLogixPathState, get_settings_path, load_settings, load_settings_with_endpoints, load_settings_with_endpoints_for_mode, save_settings, initialize_settings, update_endpoint_state};
+use settings::{SettingsFile, SettingsResponse, AppMode, FSLogixPathState, get_settings_path, load_settings, load_settings_with_endpoints, load_settings_with_endpoints_for_mode, save_settings, save_settings_update, initialize_settings, update_endpoint_state, apply_fslogix_path_states};
 use fslogix::FSLogixPath;
+use scheduler::SCHEDULER;
+use history::{HistoryRecord, HistoryStore};
 
 // Global tray icon reference (using concrete Wry runtime type)
 static TRAY_ICON: Lazy<Arc<Mutex<Option<TrayIcon<tauri::Wry>>>>> = Lazy::new(|| Arc::new(Mutex::new(None)));
 
+// Global "Pause Monitoring" menu item reference so its label can follow the scheduler state
+static PAUSE_MENU_ITEM: Lazy<Arc<Mutex<Option<MenuItem<tauri::Wry>>>>> = Lazy::new(|| Arc::new(Mutex::new(None)));
+
 #[tauri::command]
-async fn test_latency(endpoint: String, port: Option<u16>, protocol: Option<String>) -> Result<f64, String> {
+async fn test_latency(
+    endpoint: String,
+    port: Option<u16>,
+    protocol: Option<String>,
+    timeout_ms: Option<u64>,
+    retries: Option<u32>,
+    retry_backoff_ms: Option<u64>,
+) -> Result<latency::ProbeResult, errors::ProbeError> {
     let port = port.unwrap_or(443);
     let protocol = protocol.unwrap_or_else(|| "tcp".to_string());
+    let options = probe_options().with_overrides(timeout_ms, retries, retry_backoff_ms);
 
-    match protocol.as_str() {
-        "http" => {
-            let url = format!("http://{}:{}", endpoint, port);
-            latency::test_http_latency(&url)
-                .await
-                .map_err(|e| e.to_string())
-        }
-        "https" => {
-            let url = format!("https://{}:{}", endpoint, port);
-            latency::test_http_latency(&url)
-                .await
-                .map_err(|e| e.to_string())
-        }
-        _ => {
-            // Default to TCP
-            latency::test_tcp_latency(&endpoint, port)
-                .await
-                .map_err(|e| e.to_string())
-        }
+    latency::probe_endpoint(&endpoint, port, &protocol, &options).await
+}
+
+#[tauri::command]
+async fn inspect_tls(endpoint: String, port: Option<u16>) -> Result<latency::TlsProbeResult, errors::ProbeError> {
+    latency::test_tls_handshake(&endpoint, port.unwrap_or(443), &probe_options()).await
+}
+
+#[tauri::command]
+async fn test_stun(endpoint: String, port: Option<u16>) -> Result<stun::StunProbeResult, errors::ProbeError> {
+    stun::test_stun(&endpoint, port.unwrap_or(stun::STUN_PORT), &probe_options()).await
+}
+
+#[tauri::command]
+async fn test_smb(endpoint: String, port: Option<u16>) -> Result<smb::SmbProbeResult, errors::ProbeError> {
+    smb::test_smb(&endpoint, port.unwrap_or(smb::SMB_PORT), &probe_options()).await
+}
+
+#[tauri::command]
+async fn diagnose_dns(endpoint: String, server: Option<String>) -> Result<dns::DnsProbeResult, errors::ProbeError> {
+    dns::diagnose_dns(&endpoint, server.as_deref(), &probe_options()).await
+}
+
+#[tauri::command]
+async fn traceroute(
+    endpoint: String,
+    port: Option<u16>,
+    max_hops: Option<u8>,
+) -> Result<traceroute::TracerouteReport, errors::ProbeError> {
+    traceroute::traceroute(
+        &endpoint,
+        port.unwrap_or(443),
+        max_hops.unwrap_or(traceroute::DEFAULT_MAX_HOPS),
+        true,
+        &probe_options(),
+    )
+    .await
+}
+
+/// Probe options from the saved settings (defaults if the settings file can't be read)
+fn probe_options() -> latency::ProbeOptions {
+    let config = load_settings().map(|s| s.config).unwrap_or_default();
+    latency::ProbeOptions::from_config(&config)
+}
+
+/// Pause or resume the backend monitoring scheduler and keep the tray menu in sync
+fn apply_monitoring_paused(app: &tauri::AppHandle, paused: bool) {
+    SCHEDULER.set_paused(app, paused);
+
+    if let Some(item) = PAUSE_MENU_ITEM.lock().as_ref() {
+        let label = if paused { "Resume Monitoring" } else { "Pause Monitoring" };
+        let _ = item.set_text(label);
     }
 }
 
+#[tauri::command]
+fn set_monitoring_paused(app: tauri::AppHandle, paused: bool) {
+    apply_monitoring_paused(&app, paused);
+}
+
+#[tauri::command]
+fn is_monitoring_paused() -> bool {
+    SCHEDULER.is_paused()
+}
+
+#[tauri::command]
+fn run_tests_now() {
+    SCHEDULER.test_now();
+}
+
 #[tauri::command]
 fn update_tray_icon(latency: f64, excellent: f64, good: f64, warning: f64) -> Result<(), String> {
     let thresholds = LatencyThresholds {
@@ -130,7 +205,11 @@ fn read_settings_for_mode(app: tauri::AppHandle, mode: String) -> Result<Setting
 
 #[tauri::command]
 fn write_settings_file(settings: SettingsFile) -> Result<(), String> {
-    save_settings(&settings).map_err(|e| e.to_string())
+    let settings = save_settings_update(settings).map_err(|e| e.to_string())?;
+    logger::set_retention_days(settings.config.retention_days);
+    // Let the scheduler pick up changed intervals or mode right away
+    SCHEDULER.reload();
+    Ok(())
 }
 
 #[tauri::command]
@@ -206,18 +285,76 @@ fn get_fslogix_storage_paths() -> Vec<FSLogixPath> {
     let mut paths = fslogix::get_fslogix_paths();
 
     // Apply muted state from settings file
-    if let Ok(settings) = load_settings() {
-        for path in &mut paths {
-            // Check if this path has a saved muted state
-            if let Some(state) = settings.fslogix_path_states.iter().find(|s| s.id == path.id) {
-                path.muted = Some(state.muted);
-            }
-        }
-    }
+    apply_fslogix_path_states(&mut paths);
 
     paths
 }
 
+/// Effective FSLogix Profile and ODFC settings with misconfiguration warnings
+#[tauri::command]
+fn get_fslogix_config() -> fslogix::FSLogixConfig {
+    fslogix::get_fslogix_config()
+}
+
+/// Recent FSLogix profile attach/detach sessions from the local profile logs
+/// The logs are read on a blocking thread so large log directories don't stall the UI.
+#[tauri::command]
+async fn get_fslogix_profile_sessions(hours: Option<u32>) -> Result<fslogix_logs::ProfileLogSummary, String> {
+    let hours = hours.unwrap_or(fslogix_logs::DEFAULT_LOG_HOURS);
+    let now = chrono::Local::now().naive_local();
+    let Some(directory) = fslogix_logs::default_log_directory() else {
+        return Err("FSLogix profile logs are only available on Windows".to_string());
+    };
+    tokio::task::spawn_blocking(move || fslogix_logs::read_profile_sessions(&directory, hours, now))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Alert state of every monitored endpoint and FSLogix path
+#[tauri::command]
+fn get_alert_states() -> alerts::AlertStates {
+    alerts::ALERTS.lock().states()
+}
+
+/// Alert incidents within the history retention, newest first
+#[tauri::command]
+fn get_incidents() -> Vec<alerts::Incident> {
+    alerts::ALERTS.lock().incidents()
+}
+
+/// Send a test event to the enabled webhooks, or only to the named one
+#[tauri::command]
+async fn send_test_webhook(name: Option<String>) -> Result<Vec<webhooks::WebhookDelivery>, String> {
+    webhooks::send_test(name.as_deref()).await
+}
+
+/// Open and list the share of every SMB-backed FSLogix path, keyed by path ID
+#[tauri::command]
+async fn check_fslogix_shares() -> HashMap<String, fslogix::ShareCheck> {
+    let limit = probe_options().timeout;
+    let accessor: Arc<dyn fslogix::ShareAccessor> = Arc::new(fslogix::FsShareAccessor);
+    let handles: Vec<_> = fslogix::get_fslogix_paths()
+        .into_iter()
+        .filter(|path| path.provider == fslogix::StorageProvider::Smb)
+        .map(|path| {
+            let accessor = accessor.clone();
+            tokio::spawn(async move {
+                let check = fslogix::check_share(&path, accessor, limit).await;
+                (path.id, check)
+            })
+        })
+        .collect();
+
+    let mut results = HashMap::new();
+    for handle in handles {
+        if let Ok((id, check)) = handle.await {
+            results.insert(id, check);
+        }
+    }
+    results
+}
+
 #[tauri::command]
 fn update_fslogix_path_muted(path_id: String, muted: bool) -> Result<(), String> {
     let mut settings = load_settings().map_err(|e| e.to_string())?;
@@ -236,6 +373,7 @@ fn update_fslogix_path_muted(path_id: String, muted: bool) -> Result<(), String>
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 fn update_endpoint(
     app: tauri::AppHandle,
     mode: String,
@@ -254,13 +392,18 @@ fn update_endpoint(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+fn get_latency_history(endpoint_id: Option<String>, from: i64, to: i64) -> Result<Vec<HistoryRecord>, String> {
+    let store = HistoryStore::open_default().map_err(|e| e.to_string())?;
+    store
+        .query(endpoint_id.as_deref(), from, to)
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 fn get_log_directory() -> Result<String, String> {
     let logger = Logger::new(30).map_err(|e| e.to_string())?;
-    Ok(logger.get_log_path().parent()
-        .unwrap_or(logger.get_log_path())
-        .to_string_lossy()
-        .to_string())
+    Ok(logger.get_log_path().to_string_lossy().to_string())
 }
 
 fn create_tray(app: &tauri::AppHandle) -> tauri::Result<()> {
@@ -298,16 +441,10 @@ fn create_tray(app: &tauri::AppHandle) -> tauri::Result<()> {
                 }
             }
             "pause" => {
-                // Pause/Resume handled by frontend
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.emit("tray-pause-clicked", ());
-                }
+                apply_monitoring_paused(app, !SCHEDULER.is_paused());
             }
             "test" => {
-                // Test Now handled by frontend
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.emit("tray-test-clicked", ());
-                }
+                SCHEDULER.test_now();
             }
             "settings" => {
                 if let Some(window) = app.get_webview_window("main") {
@@ -350,12 +487,19 @@ fn create_tray(app: &tauri::AppHandle) -> tauri::Result<()> {
         })
         .build(app)?;
 
-    // Store tray icon globally
+    // Store tray icon and pause menu item globally
     *TRAY_ICON.lock() = Some(tray);
+    *PAUSE_MENU_ITEM.lock() = Some(pause_i);
 
     Ok(())
 }
 
+/// Run a headless CLI command (e.g. `check`) if one was given on the command line
+/// Returns the exit code, or None if the GUI should start.
+pub fn run_cli(args: &[String]) -> Option<i32> {
+    cli::run(args)
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let app = tauri::Builder::default()
@@ -363,13 +507,24 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
         .setup(|app| {
+            // Start file logging first so settings initialization is captured
+            let retention_days = load_settings()
+                .map(|s| s.config.retention_days)
+                .unwrap_or(30);
+            if let Err(e) = logger::init(retention_days) {
+                eprintln!("Failed to initialize logging: {}", e);
+            }
+
             // Initialize settings file from bundled resource if not exists
-            if let Err(e) = initialize_settings(&app.handle()) {
-                eprintln!("Failed to initialize settings: {}", e);
+            if let Err(e) = initialize_settings(app.handle()) {
+                logger::error("App", &format!("Failed to initialize settings: {}", e));
             }
 
             // Create system tray - app starts minimized to tray
-            create_tray(&app.handle())?;
+            create_tray(app.handle())?;
+
+            // Start the backend monitoring loop
+            scheduler::start(app.handle().clone());
 
             Ok(())
         })
@@ -384,6 +539,11 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             test_latency,
+            inspect_tls,
+            test_stun,
+            test_smb,
+            traceroute,
+            diagnose_dns,
             update_tray_icon,
             send_notification,
             set_autostart,
@@ -398,6 +558,16 @@ pub fn run() {
             update_endpoint,
             get_fslogix_storage_paths,
             update_fslogix_path_muted,
+            get_fslogix_config,
+            check_fslogix_shares,
+            get_fslogix_profile_sessions,
+            get_alert_states,
+            get_incidents,
+            send_test_webhook,
+            set_monitoring_paused,
+            is_monitoring_paused,
+            run_tests_now,
+            get_latency_history,
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application");
@@ -1,17 +1,60 @@
-use std::fs;
-use std::path::PathBuf;
+use chrono::{DateTime, Local, NaiveDate};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
+const LOG_FILE_PREFIX: &str = "avd-health-monitor-";
+const LOG_FILE_EXTENSION: &str = "log";
+
+/// Severity of a log entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+/// A single structured log line (serialized as one JSON object per line)
+#[derive(Debug, Serialize)]
+struct LogEntry<'a> {
+    timestamp: String,
+    level: LogLevel,
+    module: &'a str,
+    message: &'a str,
+}
+
+/// Writes leveled JSON-lines log files with one file per local day
+/// (e.g. avd-health-monitor-2024-05-01.log) and deletes files older than the retention period.
 pub struct Logger {
     log_path: PathBuf,
+    retention_days: u32,
+    min_level: LogLevel,
+    current: Option<(NaiveDate, File)>,
 }
 
+// Global logger used by all backend modules, set up by `init`
+static LOGGER: Lazy<Mutex<Option<Logger>>> = Lazy::new(|| Mutex::new(None));
+
 impl Logger {
-    pub fn new(_retention_days: u32) -> std::io::Result<Self> {
+    pub fn new(retention_days: u32) -> std::io::Result<Self> {
         let log_dir = Self::get_log_directory()?;
+        Self::with_directory(log_dir, retention_days)
+    }
+
+    /// Create a logger writing to the given directory
+    pub fn with_directory(log_dir: PathBuf, retention_days: u32) -> std::io::Result<Self> {
         fs::create_dir_all(&log_dir)?;
 
         Ok(Self {
             log_path: log_dir,
+            retention_days,
+            min_level: if cfg!(debug_assertions) { LogLevel::Debug } else { LogLevel::Info },
+            current: None,
         })
     }
 
@@ -37,15 +80,210 @@ impl Logger {
     pub fn get_log_path(&self) -> &PathBuf {
         &self.log_path
     }
+
+    pub fn set_retention_days(&mut self, retention_days: u32) {
+        self.retention_days = retention_days;
+    }
+
+    /// Path of the log file for the given day
+    pub fn log_file_for(&self, day: NaiveDate) -> PathBuf {
+        self.log_path.join(format!(
+            "{}{}.{}",
+            LOG_FILE_PREFIX,
+            day.format("%Y-%m-%d"),
+            LOG_FILE_EXTENSION
+        ))
+    }
+
+    /// Write a log entry stamped with the current local time
+    pub fn log(&mut self, level: LogLevel, module: &str, message: &str) -> std::io::Result<()> {
+        self.log_at(Local::now(), level, module, message)
+    }
+
+    /// Write a log entry stamped with the given time, rotating to a new file when the day changes
+    fn log_at(&mut self, now: DateTime<Local>, level: LogLevel, module: &str, message: &str) -> std::io::Result<()> {
+        if level < self.min_level {
+            return Ok(());
+        }
+
+        let day = now.date_naive();
+        if self.current.as_ref().map(|(d, _)| *d) != Some(day) {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.log_file_for(day))?;
+            self.current = Some((day, file));
+            // A new day started - drop files that fell out of the retention period
+            self.cleanup_old_logs(day)?;
+        }
+
+        let entry = LogEntry {
+            timestamp: now.to_rfc3339(),
+            level,
+            module,
+            message,
+        };
+        let line = serde_json::to_string(&entry)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+        if let Some((_, file)) = self.current.as_mut() {
+            writeln!(file, "{}", line)?;
+        }
+        Ok(())
+    }
+
+    /// Delete log files older than the retention period relative to `today`
+    /// Returns the number of deleted files.
+    pub fn cleanup_old_logs(&self, today: NaiveDate) -> std::io::Result<usize> {
+        let cutoff = today - chrono::Duration::days(i64::from(self.retention_days));
+        let mut deleted = 0;
+
+        for entry in fs::read_dir(&self.log_path)? {
+            let path = entry?.path();
+            if let Some(day) = log_file_day(&path) {
+                if day < cutoff {
+                    fs::remove_file(&path)?;
+                    deleted += 1;
+                }
+            }
+        }
+
+        Ok(deleted)
+    }
+}
+
+/// Parse the day from a log file name, ignoring unrelated files
+fn log_file_day(path: &Path) -> Option<NaiveDate> {
+    if path.extension()?.to_str()? != LOG_FILE_EXTENSION {
+        return None;
+    }
+    let stem = path.file_stem()?.to_str()?;
+    let date = stem.strip_prefix(LOG_FILE_PREFIX)?;
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+/// Set up the global logger; entries logged before this are only written to stderr
+pub fn init(retention_days: u32) -> std::io::Result<()> {
+    let logger = Logger::new(retention_days)?;
+    *LOGGER.lock() = Some(logger);
+    Ok(())
+}
+
+/// Update the retention period of the global logger (e.g. after settings change)
+pub fn set_retention_days(retention_days: u32) {
+    if let Some(logger) = LOGGER.lock().as_mut() {
+        logger.set_retention_days(retention_days);
+    }
+}
+
+/// Write an entry to the global logger
+pub fn log(level: LogLevel, module: &str, message: &str) {
+    // Mirror to the console in development builds (and whenever the file logger is unavailable)
+    let mut guard = LOGGER.lock();
+    let written = match guard.as_mut() {
+        Some(logger) => logger.log(level, module, message).is_ok(),
+        None => false,
+    };
+    if cfg!(debug_assertions) || !written {
+        eprintln!("[{}] {:?}: {}", module, level, message);
+    }
+}
+
+pub fn debug(module: &str, message: &str) {
+    log(LogLevel::Debug, module, message);
+}
+
+pub fn info(module: &str, message: &str) {
+    log(LogLevel::Info, module, message);
+}
+
+pub fn warn(module: &str, message: &str) {
+    log(LogLevel::Warn, module, message);
+}
+
+pub fn error(module: &str, message: &str) {
+    log(LogLevel::Error, module, message);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
+
+    fn temp_logger(name: &str, retention_days: u32) -> Logger {
+        let dir = std::env::temp_dir().join(format!(
+            "avd-health-monitor-logs-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        let mut logger = Logger::with_directory(dir, retention_days).unwrap();
+        logger.min_level = LogLevel::Debug;
+        logger
+    }
+
+    fn local(year: i32, month: u32, day: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(year, month, day, 12, 0, 0).unwrap()
+    }
 
     #[test]
     fn test_logger_creation() {
         let logger = Logger::new(30);
         assert!(logger.is_ok());
     }
+
+    #[test]
+    fn test_writes_json_lines() {
+        let mut logger = temp_logger("json", 30);
+        let now = local(2024, 5, 1);
+        logger.log_at(now, LogLevel::Info, "Settings", "Loaded \"settings.json\"").unwrap();
+        logger.log_at(now, LogLevel::Error, "Latency", "Connection timeout").unwrap();
+
+        let content = fs::read_to_string(logger.log_file_for(now.date_naive())).unwrap();
+        let lines: Vec<serde_json::Value> = content
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["level"], "info");
+        assert_eq!(lines[0]["module"], "Settings");
+        assert_eq!(lines[0]["message"], "Loaded \"settings.json\"");
+        assert_eq!(lines[1]["level"], "error");
+    }
+
+    #[test]
+    fn test_min_level_filters_entries() {
+        let mut logger = temp_logger("level", 30);
+        logger.min_level = LogLevel::Warn;
+        let now = local(2024, 5, 1);
+        logger.log_at(now, LogLevel::Info, "Settings", "ignored").unwrap();
+
+        assert!(!logger.log_file_for(now.date_naive()).exists());
+    }
+
+    #[test]
+    fn test_rotates_daily_and_deletes_expired_files() {
+        let mut logger = temp_logger("rotate", 7);
+        logger.log_at(local(2024, 4, 1), LogLevel::Info, "Test", "old").unwrap();
+        logger.log_at(local(2024, 4, 28), LogLevel::Info, "Test", "recent").unwrap();
+
+        assert!(logger.log_file_for(local(2024, 4, 28).date_naive()).exists());
+        // Rotating to 2024-04-28 deleted the file from 2024-04-01
+        assert!(!logger.log_file_for(local(2024, 4, 1).date_naive()).exists());
+
+        logger.log_at(local(2024, 5, 1), LogLevel::Info, "Test", "today").unwrap();
+        assert!(logger.log_file_for(local(2024, 4, 28).date_naive()).exists());
+        assert!(logger.log_file_for(local(2024, 5, 1).date_naive()).exists());
+    }
+
+    #[test]
+    fn test_cleanup_ignores_unrelated_files() {
+        let logger = temp_logger("unrelated", 1);
+        let other = logger.get_log_path().join("notes.txt");
+        fs::write(&other, "keep me").unwrap();
+
+        logger.cleanup_old_logs(NaiveDate::from_ymd_opt(2030, 1, 1).unwrap()).unwrap();
+        assert!(other.exists());
+    }
 }
@@ -0,0 +1,426 @@
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::task::JoinHandle;
+
+use crate::alerts::{self, AlertState, AlertStates};
+use crate::fslogix::FSLogixPath;
+use crate::logger;
+use crate::scheduler::{EndpointResult, FSLogixResult};
+use crate::settings::{AppConfig, AppMode, Endpoint};
+
+/// Upper bounds of the probe duration histogram buckets in seconds
+const DURATION_BUCKETS: [f64; 12] = [0.005, 0.01, 0.025, 0.05, 0.075, 0.1, 0.15, 0.25, 0.5, 1.0, 2.5, 5.0];
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+/// How long a client may take to send its request before the connection is dropped
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Cumulative histogram in Prometheus layout
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    /// Observations less than or equal to each of `DURATION_BUCKETS`
+    buckets: [u64; DURATION_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(DURATION_BUCKETS) {
+            if value <= bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EndpointSeries {
+    /// `id`, `category`, `mode` and `required`, already escaped
+    labels: String,
+    /// Last latency in seconds, None when the last probe failed
+    latency: Option<f64>,
+    success: bool,
+    duration: Histogram,
+}
+
+#[derive(Debug, Clone)]
+struct FslogixSeries {
+    /// `id`, `type` and `host`, already escaped
+    labels: String,
+    latency: Option<f64>,
+    reachable: bool,
+}
+
+/// Latest probe results and probe duration histograms, fed by the scheduler
+#[derive(Debug, Default)]
+pub struct Metrics {
+    endpoints: BTreeMap<String, EndpointSeries>,
+    fslogix: BTreeMap<String, FslogixSeries>,
+}
+
+pub static METRICS: Lazy<Mutex<Metrics>> = Lazy::new(|| Mutex::new(Metrics::default()));
+
+/// The /metrics listener of a configured address and port, without a task when binding failed
+struct MetricsListener {
+    address: String,
+    port: u16,
+    task: Option<JoinHandle<()>>,
+}
+
+static LISTENER: Lazy<Mutex<Option<MetricsListener>>> = Lazy::new(|| Mutex::new(None));
+
+impl Metrics {
+    /// Record a round of endpoint results; endpoints missing from the round are dropped
+    pub fn record_endpoints(&mut self, endpoints: &[Endpoint], results: &[EndpointResult], mode: &AppMode) {
+        self.endpoints.retain(|id, _| results.iter().any(|r| &r.endpoint_id == id));
+
+        for result in results {
+            let Some(endpoint) = endpoints.iter().find(|e| e.id == result.endpoint_id) else { continue };
+            let labels = format!(
+                "id=\"{}\",category=\"{}\",mode=\"{}\",required=\"{}\"",
+                escape(&endpoint.id),
+                escape(endpoint.category.as_deref().unwrap_or("")),
+                match mode {
+                    AppMode::SessionHost => "sessionhost",
+                    AppMode::EndUser => "enduser",
+                },
+                endpoint.required.unwrap_or(false)
+            );
+
+            let series = self.endpoints.entry(endpoint.id.clone()).or_insert_with(|| EndpointSeries {
+                labels: String::new(),
+                latency: None,
+                success: false,
+                duration: Histogram::default(),
+            });
+            series.labels = labels;
+            series.success = result.success;
+            series.latency = result.success.then_some(result.latency / 1000.0);
+            // Failed probes count with the time spent until they gave up
+            let duration_ms = result.timings.as_ref().map_or(result.duration_ms, |t| t.total_ms);
+            series.duration.observe(duration_ms / 1000.0);
+        }
+    }
+
+    /// Record a round of FSLogix results; paths missing from the round are dropped
+    pub fn record_fslogix(&mut self, paths: &[FSLogixPath], results: &[FSLogixResult]) {
+        self.fslogix.retain(|id, _| results.iter().any(|r| &r.path_id == id));
+
+        for result in results {
+            let Some(path) = paths.iter().find(|p| p.id == result.path_id) else { continue };
+            self.fslogix.insert(
+                path.id.clone(),
+                FslogixSeries {
+                    labels: format!(
+                        "id=\"{}\",type=\"{}\",host=\"{}\"",
+                        escape(&path.id),
+                        escape(&path.path_type),
+                        escape(&path.hostname)
+                    ),
+                    latency: result.latency.map(|ms| ms / 1000.0),
+                    reachable: result.reachable,
+                },
+            );
+        }
+    }
+
+    /// Prometheus text exposition of all series
+    pub fn render(&self, alerts: &AlertStates) -> String {
+        let mut out = String::new();
+
+        header(&mut out, "avd_endpoint_up", "gauge", "1 if the last probe of the endpoint succeeded");
+        for series in self.endpoints.values() {
+            sample(&mut out, "avd_endpoint_up", &series.labels, if series.success { 1.0 } else { 0.0 });
+        }
+
+        header(&mut out, "avd_endpoint_latency_seconds", "gauge", "Latency of the last successful probe");
+        for series in self.endpoints.values() {
+            if let Some(latency) = series.latency {
+                sample(&mut out, "avd_endpoint_latency_seconds", &series.labels, latency);
+            }
+        }
+
+        header(
+            &mut out,
+            "avd_endpoint_probe_duration_seconds",
+            "histogram",
+            "Total probe time including DNS resolution",
+        );
+        for series in self.endpoints.values() {
+            let histogram = &series.duration;
+            for (count, bound) in histogram.buckets.iter().zip(DURATION_BUCKETS) {
+                let labels = format!("{},le=\"{}\"", series.labels, bound);
+                sample(&mut out, "avd_endpoint_probe_duration_seconds_bucket", &labels, *count as f64);
+            }
+            let labels = format!("{},le=\"+Inf\"", series.labels);
+            sample(&mut out, "avd_endpoint_probe_duration_seconds_bucket", &labels, histogram.count as f64);
+            sample(&mut out, "avd_endpoint_probe_duration_seconds_sum", &series.labels, histogram.sum);
+            sample(&mut out, "avd_endpoint_probe_duration_seconds_count", &series.labels, histogram.count as f64);
+        }
+
+        header(
+            &mut out,
+            "avd_endpoint_alert_state",
+            "gauge",
+            "Alert state of the endpoint: 0 ok, 1 degraded, 2 alerting, 3 recovered",
+        );
+        for (id, series) in &self.endpoints {
+            sample(&mut out, "avd_endpoint_alert_state", &series.labels, state_value(&alerts.endpoints, id));
+        }
+
+        header(&mut out, "avd_fslogix_up", "gauge", "1 if the FSLogix storage host was reachable in the last check");
+        for series in self.fslogix.values() {
+            sample(&mut out, "avd_fslogix_up", &series.labels, if series.reachable { 1.0 } else { 0.0 });
+        }
+
+        header(&mut out, "avd_fslogix_latency_seconds", "gauge", "Connect latency of the last FSLogix check");
+        for series in self.fslogix.values() {
+            if let Some(latency) = series.latency {
+                sample(&mut out, "avd_fslogix_latency_seconds", &series.labels, latency);
+            }
+        }
+
+        header(
+            &mut out,
+            "avd_fslogix_alert_state",
+            "gauge",
+            "Alert state of the FSLogix path: 0 ok, 1 degraded, 2 alerting, 3 recovered",
+        );
+        for (id, series) in &self.fslogix {
+            sample(&mut out, "avd_fslogix_alert_state", &series.labels, state_value(&alerts.fslogix, id));
+        }
+
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn sample(out: &mut String, name: &str, labels: &str, value: f64) {
+    let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+}
+
+fn state_value(states: &HashMap<String, AlertState>, id: &str) -> f64 {
+    match states.get(id) {
+        None | Some(AlertState::Ok) => 0.0,
+        Some(AlertState::Degraded) => 1.0,
+        Some(AlertState::Alerting) => 2.0,
+        Some(AlertState::Recovered) => 3.0,
+    }
+}
+
+/// Escape a label value (backslash, double quote and line feed)
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Current exposition of the global metrics and alert states
+pub fn render_metrics() -> String {
+    let alerts = alerts::ALERTS.lock().states();
+    METRICS.lock().render(&alerts)
+}
+
+/// Answer HTTP requests: GET /metrics returns the exposition, anything else 404
+pub async fn serve(listener: tokio::net::TcpListener) {
+    while let Ok((mut stream, _)) = listener.accept().await {
+        tokio::spawn(async move {
+            let mut buf = [0u8; 2048];
+            let Ok(Ok(n)) = tokio::time::timeout(READ_TIMEOUT, stream.read(&mut buf)).await else { return };
+            let request = String::from_utf8_lossy(&buf[..n]);
+            let mut parts = request.split_whitespace();
+            let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+
+            let response = if method == "GET" && path.split('?').next() == Some("/metrics") {
+                let body = render_metrics();
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    CONTENT_TYPE,
+                    body.len(),
+                    body
+                )
+            } else {
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+            };
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+    }
+}
+
+/// Start, move or stop the /metrics listener to match the config
+/// Called every scheduler cycle, so address and port changes apply without a restart.
+pub fn ensure_listener(config: &AppConfig) {
+    let wanted = config
+        .metrics_enabled
+        .then(|| (config.metrics_bind_address.trim().to_string(), config.metrics_port));
+    let mut listener = LISTENER.lock();
+    if listener.as_ref().map(|l| (l.address.clone(), l.port)) == wanted {
+        return;
+    }
+
+    if let Some(MetricsListener { address, port, task: Some(task) }) = listener.take() {
+        task.abort();
+        logger::info("Metrics", &format!("Stopped metrics listener on {}:{}", address, port));
+    }
+    let Some((address, port)) = wanted else { return };
+
+    // A failed bind is remembered as well, so it is only retried after the address or port changes
+    let bound = address
+        .parse::<std::net::IpAddr>()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))
+        .and_then(|ip| std::net::TcpListener::bind((ip, port)))
+        .and_then(|std_listener| {
+            std_listener.set_nonblocking(true)?;
+            tokio::net::TcpListener::from_std(std_listener)
+        })
+        .map(|tcp| tokio::spawn(serve(tcp)));
+    let task = match bound {
+        Ok(task) => {
+            logger::info("Metrics", &format!("Serving Prometheus metrics on {}:{}", address, port));
+            Some(task)
+        }
+        Err(e) => {
+            logger::error("Metrics", &format!("Failed to listen on {}:{}: {}", address, port, e));
+            None
+        }
+    };
+    *listener = Some(MetricsListener { address, port, task });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::latency::ProbeResult;
+
+    fn endpoint(id: &str, category: &str, required: bool) -> Endpoint {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "name": id,
+            "url": format!("{}.example.com", id),
+            "category": category,
+            "required": required,
+        }))
+        .unwrap()
+    }
+
+    fn result(id: &str, success: bool, latency: f64) -> EndpointResult {
+        EndpointResult {
+            endpoint_id: id.to_string(),
+            latency,
+            timestamp: 0,
+            success,
+            error: None,
+            duration_ms: if success { latency + 2.0 } else { 3_000.0 },
+            timings: success.then(|| ProbeResult {
+                dns_ms: 2.0,
+                connect_ms: latency,
+                tls_ms: None,
+                ttfb_ms: None,
+                total_ms: latency + 2.0,
+                latency_ms: latency,
+                resolved_ip: "10.0.0.1".to_string(),
+                addresses: Vec::new(),
+                partial_failure: false,
+                samples: None,
+            }),
+        }
+    }
+
+    fn states(endpoints: &[(&str, AlertState)]) -> AlertStates {
+        AlertStates {
+            endpoints: endpoints.iter().map(|(id, state)| (id.to_string(), *state)).collect(),
+            fslogix: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let mut histogram = Histogram::default();
+        histogram.observe(0.02);
+        histogram.observe(0.2);
+        histogram.observe(10.0);
+
+        assert_eq!(histogram.buckets[0], 0);
+        // le="0.025"
+        assert_eq!(histogram.buckets[2], 1);
+        // le="0.25"
+        assert_eq!(histogram.buckets[7], 2);
+        assert_eq!(histogram.buckets[DURATION_BUCKETS.len() - 1], 2);
+        assert_eq!(histogram.count, 3);
+    }
+
+    #[test]
+    fn test_render_endpoint_metrics() {
+        let mut metrics = Metrics::default();
+        let endpoints = vec![endpoint("gw", "Gateway", true), endpoint("cdn", "Optional \"CDN\"", false)];
+        metrics.record_endpoints(&endpoints, &[result("gw", true, 23.0), result("cdn", false, 0.0)], &AppMode::SessionHost);
+        metrics.record_endpoints(&endpoints, &[result("gw", true, 48.0), result("cdn", false, 0.0)], &AppMode::SessionHost);
+
+        let text = metrics.render(&states(&[("cdn", AlertState::Alerting)]));
+        let gw = r#"id="gw",category="Gateway",mode="sessionhost",required="true""#;
+        let cdn = r#"id="cdn",category="Optional \"CDN\"",mode="sessionhost",required="false""#;
+
+        assert!(text.contains("# TYPE avd_endpoint_up gauge\n"));
+        assert!(text.contains(&format!("avd_endpoint_up{{{}}} 1\n", gw)));
+        assert!(text.contains(&format!("avd_endpoint_up{{{}}} 0\n", cdn)));
+        assert!(text.contains(&format!("avd_endpoint_latency_seconds{{{}}} 0.048\n", gw)));
+        // No latency sample for a failed probe
+        assert!(!text.contains(&format!("avd_endpoint_latency_seconds{{{}}}", cdn)));
+
+        assert!(text.contains("# TYPE avd_endpoint_probe_duration_seconds histogram\n"));
+        assert!(text.contains(&format!("avd_endpoint_probe_duration_seconds_bucket{{{},le=\"0.025\"}} 1\n", gw)));
+        assert!(text.contains(&format!("avd_endpoint_probe_duration_seconds_bucket{{{},le=\"0.05\"}} 2\n", gw)));
+        assert!(text.contains(&format!("avd_endpoint_probe_duration_seconds_bucket{{{},le=\"+Inf\"}} 2\n", gw)));
+        assert!(text.contains(&format!("avd_endpoint_probe_duration_seconds_count{{{}}} 2\n", gw)));
+        // Failed probes are observed with their attempt duration
+        assert!(text.contains(&format!("avd_endpoint_probe_duration_seconds_bucket{{{},le=\"2.5\"}} 0\n", cdn)));
+        assert!(text.contains(&format!("avd_endpoint_probe_duration_seconds_bucket{{{},le=\"5\"}} 2\n", cdn)));
+        assert!(text.contains(&format!("avd_endpoint_probe_duration_seconds_count{{{}}} 2\n", cdn)));
+
+        assert!(text.contains(&format!("avd_endpoint_alert_state{{{}}} 0\n", gw)));
+        assert!(text.contains(&format!("avd_endpoint_alert_state{{{}}} 2\n", cdn)));
+    }
+
+    #[test]
+    fn test_removed_endpoints_are_dropped() {
+        let mut metrics = Metrics::default();
+        let endpoints = vec![endpoint("a", "Core", true), endpoint("b", "Core", true)];
+        metrics.record_endpoints(&endpoints, &[result("a", true, 10.0), result("b", true, 10.0)], &AppMode::EndUser);
+        metrics.record_endpoints(&endpoints, &[result("a", true, 10.0)], &AppMode::EndUser);
+
+        let text = metrics.render(&states(&[]));
+        assert!(text.contains(r#"avd_endpoint_up{id="a",category="Core",mode="enduser",required="true"} 1"#));
+        assert!(!text.contains(r#"id="b""#));
+    }
+
+    #[tokio::test]
+    async fn test_serve_metrics() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(serve(listener));
+
+        let get = |path: &'static str| async move {
+            let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+            let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path);
+            stream.write_all(request.as_bytes()).await.unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            response
+        };
+
+        let response = get("/metrics").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Type: text/plain; version=0.0.4"));
+        assert!(response.contains("# TYPE avd_endpoint_up gauge"));
+
+        assert!(get("/").await.starts_with("HTTP/1.1 404"));
+    }
+}
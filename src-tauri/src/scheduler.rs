@@ -0,0 +1,503 @@
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::Emitter;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+use crate::alerts;
+use crate::errors::ProbeError;
+use crate::fslogix;
+use crate::history::{HistoryRecord, HistoryStore, IncidentLog};
+use crate::latency::{self, ProbeOptions, ProbeResult};
+use crate::logger;
+use crate::metrics;
+use crate::settings::{load_fslogix_path_states, load_settings, load_settings_with_endpoints, AppConfig, AppMode};
+
+/// Event emitted with the results of an endpoint test cycle
+pub const ENDPOINT_RESULTS_EVENT: &str = "endpoint-results";
+/// Event emitted with the results of an FSLogix test cycle
+pub const FSLOGIX_RESULTS_EVENT: &str = "fslogix-results";
+/// Event emitted right before a test cycle starts (payload: "endpoints" or "fslogix")
+pub const TESTS_STARTED_EVENT: &str = "tests-started";
+/// Event emitted whenever monitoring is paused or resumed
+pub const MONITORING_STATE_EVENT: &str = "monitoring-state-changed";
+
+/// Result of a single endpoint probe, as sent to the frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointResult {
+    pub endpoint_id: String,
+    pub latency: f64,
+    /// Unix timestamp in milliseconds
+    pub timestamp: i64,
+    pub success: bool,
+    pub error: Option<ProbeError>,
+    /// Phase-level timing breakdown, None if the probe failed
+    pub timings: Option<ProbeResult>,
+    /// Wall time of the probe including retries, for the duration histogram
+    #[serde(skip)]
+    pub duration_ms: f64,
+}
+
+impl EndpointResult {
+    fn failed(endpoint_id: String, error: ProbeError, duration_ms: f64) -> Self {
+        Self {
+            endpoint_id,
+            latency: 0.0,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            success: false,
+            error: Some(error),
+            timings: None,
+            duration_ms,
+        }
+    }
+}
+
+impl From<&EndpointResult> for HistoryRecord {
+    fn from(result: &EndpointResult) -> Self {
+        Self {
+            endpoint_id: result.endpoint_id.clone(),
+            timestamp: result.timestamp,
+            latency: result.success.then_some(result.latency),
+            success: result.success,
+            error: result.error.as_ref().map(ToString::to_string),
+            error_code: result.error.as_ref().map(|e| e.code().to_string()),
+        }
+    }
+}
+
+/// Result of a single FSLogix path probe, as sent to the frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FSLogixResult {
+    pub path_id: String,
+    pub reachable: bool,
+    pub latency: Option<f64>,
+    pub error: Option<ProbeError>,
+    /// Private/public classification of the storage host
+    pub resolution: fslogix::StorageResolution,
+    /// Share existence and listability, when share checks are enabled
+    pub share: Option<fslogix::ShareCheck>,
+    /// Unix timestamp in milliseconds
+    pub timestamp: i64,
+}
+
+impl From<&FSLogixResult> for HistoryRecord {
+    fn from(result: &FSLogixResult) -> Self {
+        Self {
+            endpoint_id: result.path_id.clone(),
+            timestamp: result.timestamp,
+            latency: result.latency,
+            success: result.reachable,
+            error: result.error.as_ref().map(ToString::to_string),
+            error_code: result.error.as_ref().map(|e| e.code().to_string()),
+        }
+    }
+}
+
+/// Current monitoring state, as sent to the frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitoringState {
+    pub paused: bool,
+}
+
+/// Backend-owned monitoring loop state
+/// The loop itself runs on the Tauri async runtime; this struct is how
+/// commands and the tray menu talk to it.
+pub struct Scheduler {
+    paused: AtomicBool,
+    test_now: AtomicBool,
+    wake: Notify,
+}
+
+// Global scheduler instance shared by commands, the tray menu and the loop
+pub static SCHEDULER: Lazy<Arc<Scheduler>> = Lazy::new(|| Arc::new(Scheduler::new()));
+
+impl Scheduler {
+    fn new() -> Self {
+        Self {
+            paused: AtomicBool::new(false),
+            test_now: AtomicBool::new(false),
+            wake: Notify::new(),
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Pause or resume scheduled tests and notify the frontend
+    pub fn set_paused(&self, app: &tauri::AppHandle, paused: bool) {
+        self.paused.store(paused, Ordering::SeqCst);
+        let _ = app.emit(MONITORING_STATE_EVENT, MonitoringState { paused });
+        self.wake.notify_one();
+    }
+
+    /// Run endpoint and FSLogix tests immediately, even while paused
+    pub fn test_now(&self) {
+        self.test_now.store(true, Ordering::SeqCst);
+        self.wake.notify_one();
+    }
+
+    /// Wake the loop so it picks up changed intervals from the settings file
+    pub fn reload(&self) {
+        self.wake.notify_one();
+    }
+}
+
+/// Convert an interval in seconds from the settings file into a Duration
+/// A zero interval would make the loop spin, so it is clamped to one second
+fn interval_from_secs(secs: u32) -> Duration {
+    Duration::from_secs(u64::from(secs.max(1)))
+}
+
+/// Check whether a test that last ran at `last_run` is due again
+fn is_due(last_run: Option<Instant>, interval: Duration, now: Instant) -> bool {
+    match last_run {
+        Some(last) => now.duration_since(last) >= interval,
+        None => true,
+    }
+}
+
+/// Time at which a test that last ran at `last_run` becomes due
+fn next_due(last_run: Option<Instant>, interval: Duration, now: Instant) -> Instant {
+    match last_run {
+        Some(last) => last + interval,
+        None => now,
+    }
+}
+
+/// Start the monitoring loop on the Tauri async runtime
+pub fn start(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        run(app).await;
+    });
+}
+
+async fn run(app: tauri::AppHandle) {
+    let scheduler = SCHEDULER.clone();
+    let mut last_endpoint_run: Option<Instant> = None;
+    let mut last_fslogix_run: Option<Instant> = None;
+    let mut last_prune_day = None;
+
+    let history = match HistoryStore::open_default() {
+        Ok(store) => Some(store),
+        Err(e) => {
+            logger::error("Scheduler", &format!("Failed to open history store: {}", e));
+            None
+        }
+    };
+    match IncidentLog::open_default() {
+        Ok(log) => alerts::ALERTS.lock().open_incident_log(log),
+        Err(e) => logger::error("Scheduler", &format!("Failed to open incident log: {}", e)),
+    }
+
+    loop {
+        // Re-read the config each cycle so interval and mode changes apply without a restart
+        let config = load_settings().map(|s| s.config).unwrap_or_default();
+        let endpoint_interval = interval_from_secs(config.test_interval);
+        let fslogix_interval = interval_from_secs(config.fslogix_test_interval);
+        let fslogix_active = config.fslogix_enabled && config.mode == AppMode::SessionHost;
+        metrics::ensure_listener(&config);
+
+        // Enforce history and incident retention once per day
+        let today = chrono::Utc::now().date_naive();
+        if last_prune_day != Some(today) {
+            if let Some(store) = history.as_ref() {
+                if let Err(e) = store.prune(config.retention_days, chrono::Utc::now()) {
+                    logger::error("Scheduler", &format!("Failed to prune history: {}", e));
+                }
+            }
+            alerts::ALERTS.lock().prune_incident_log(config.retention_days, chrono::Utc::now());
+            last_prune_day = Some(today);
+        }
+
+        let forced = scheduler.test_now.swap(false, Ordering::SeqCst);
+        let paused = scheduler.is_paused();
+        let now = Instant::now();
+
+        let run_endpoints = forced || (!paused && is_due(last_endpoint_run, endpoint_interval, now));
+        let run_fslogix =
+            fslogix_active && (forced || (!paused && is_due(last_fslogix_run, fslogix_interval, now)));
+
+        if run_endpoints {
+            last_endpoint_run = Some(now);
+        }
+        if run_fslogix {
+            last_fslogix_run = Some(now);
+        }
+
+        if !fslogix_active {
+            clear_fslogix_results(&app, &config);
+        }
+
+        tokio::join!(
+            async {
+                if run_endpoints {
+                    run_endpoint_tests(&app, history.as_ref(), &config).await;
+                }
+            },
+            async {
+                if run_fslogix {
+                    run_fslogix_tests(&app, history.as_ref(), &config).await;
+                }
+            },
+        );
+
+        if scheduler.is_paused() {
+            // Nothing is due while paused - sleep until resumed or "Test Now"
+            scheduler.wake.notified().await;
+            continue;
+        }
+
+        let now = Instant::now();
+        let mut wake_at = next_due(last_endpoint_run, endpoint_interval, now);
+        if fslogix_active {
+            wake_at = wake_at.min(next_due(last_fslogix_run, fslogix_interval, now));
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep_until(wake_at) => {}
+            _ = scheduler.wake.notified() => {}
+        }
+    }
+}
+
+/// Probe all enabled endpoints concurrently, emit the results and raise alerts
+async fn run_endpoint_tests(app: &tauri::AppHandle, history: Option<&HistoryStore>, config: &AppConfig) {
+    let endpoints = match load_settings_with_endpoints(app) {
+        Ok(response) => response.endpoints,
+        Err(e) => {
+            logger::error("Scheduler", &format!("Failed to load endpoints: {}", e));
+            return;
+        }
+    };
+
+    let enabled: Vec<_> = endpoints.into_iter().filter(|e| e.enabled).collect();
+    if enabled.is_empty() {
+        clear_endpoint_results(app, config);
+        return;
+    }
+
+    let _ = app.emit(TESTS_STARTED_EVENT, "endpoints");
+
+    let options = ProbeOptions::from_config(config);
+    let probes = enabled.clone().into_iter().map(move |endpoint| async move {
+        let port = endpoint.port.unwrap_or(443);
+        let protocol = endpoint.protocol.as_deref().unwrap_or("tcp");
+        let options = options.for_endpoint(&endpoint);
+        let started = Instant::now();
+        let result = latency::probe_endpoint(&endpoint.url, port, protocol, &options).await;
+        let duration_ms = latency::as_ms(started.elapsed());
+
+        match result {
+            Ok(probe) => EndpointResult {
+                endpoint_id: endpoint.id,
+                latency: probe.latency_ms,
+                timestamp: chrono::Utc::now().timestamp_millis(),
+                success: true,
+                error: None,
+                timings: Some(probe),
+                duration_ms,
+            },
+            Err(e) => EndpointResult::failed(endpoint.id, e, duration_ms),
+        }
+    });
+
+    let results = join_all(probes, |index, error| EndpointResult::failed(enabled[index].id.clone(), error, 0.0)).await;
+    record_history(history, results.iter().map(HistoryRecord::from).collect());
+    metrics::METRICS.lock().record_endpoints(&enabled, &results, &config.mode);
+    alerts::process_endpoint_results(app, &enabled, &results, config);
+    let _ = app.emit(ENDPOINT_RESULTS_EVENT, results);
+}
+
+/// Probe all configured FSLogix storage paths concurrently, emit the results and raise alerts
+/// With share checks enabled, each share is also opened and listed through its UNC path.
+async fn run_fslogix_tests(app: &tauri::AppHandle, history: Option<&HistoryStore>, config: &AppConfig) {
+    let paths = fslogix::get_fslogix_paths();
+    if paths.is_empty() {
+        clear_fslogix_results(app, config);
+        return;
+    }
+
+    let _ = app.emit(TESTS_STARTED_EVENT, "fslogix");
+
+    let options = ProbeOptions::from_config(config);
+    let share_check = config.fslogix_share_check;
+    let probes = paths.clone().into_iter().map(move |path| async move {
+        let check = fslogix::check_path(&path, &options, share_check).await;
+        FSLogixResult {
+            path_id: path.id,
+            reachable: check.reachable,
+            latency: check.latency_ms,
+            error: check.error,
+            resolution: check.resolution,
+            share: check.share,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        }
+    });
+
+    let results = join_all(probes, |index, error| FSLogixResult {
+        path_id: paths[index].id.clone(),
+        reachable: false,
+        latency: None,
+        error: Some(error),
+        resolution: fslogix::StorageResolution {
+            exposure: fslogix::StorageExposure::Unresolvable,
+            addresses: Vec::new(),
+            private_link: false,
+            warning: None,
+        },
+        share: None,
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    })
+    .await;
+    record_history(history, results.iter().map(HistoryRecord::from).collect());
+    let path_states = load_fslogix_path_states(&paths).unwrap_or_default();
+    metrics::METRICS.lock().record_fslogix(&paths, &results);
+    alerts::process_fslogix_results(app, &paths, &results, &path_states, config);
+    let _ = app.emit(FSLOGIX_RESULTS_EVENT, results);
+}
+
+/// Evaluate an empty endpoint round so alert states, open incidents and metric series
+/// of endpoints that are no longer probed don't linger
+fn clear_endpoint_results(app: &tauri::AppHandle, config: &AppConfig) {
+    metrics::METRICS.lock().record_endpoints(&[], &[], &config.mode);
+    alerts::process_endpoint_results(app, &[], &[], config);
+}
+
+/// Same as `clear_endpoint_results` for FSLogix paths (disabled, End User mode or no paths found)
+fn clear_fslogix_results(app: &tauri::AppHandle, config: &AppConfig) {
+    metrics::METRICS.lock().record_fslogix(&[], &[]);
+    alerts::process_fslogix_results(app, &[], &[], &[], config);
+}
+
+/// Persist probe results to the on-disk history store
+fn record_history(history: Option<&HistoryStore>, records: Vec<HistoryRecord>) {
+    if let Some(store) = history {
+        if let Err(e) = store.append(&records) {
+            logger::error("Scheduler", &format!("Failed to write history: {}", e));
+        }
+    }
+}
+
+/// Run a set of futures concurrently on the runtime and collect their outputs in order
+/// A future that panics is logged and replaced by `failed(index, error)`, so its endpoint
+/// shows up as failed in the round instead of disappearing from the results.
+async fn join_all<I, F, T>(futures: I, failed: impl Fn(usize, ProbeError) -> T) -> Vec<T>
+where
+    I: IntoIterator<Item = F>,
+    F: std::future::Future<Output = T> + Send + 'static,
+    T: Send + 'static,
+{
+    let handles: Vec<_> = futures.into_iter().map(tokio::spawn).collect();
+    let mut results = Vec::with_capacity(handles.len());
+    for (index, handle) in handles.into_iter().enumerate() {
+        match handle.await {
+            Ok(result) => results.push(result),
+            Err(e) => {
+                logger::error("Scheduler", &format!("Probe task {} failed: {}", index, e));
+                results.push(failed(index, ProbeError::Other(format!("Probe task failed: {}", e))));
+            }
+        }
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval_zero_is_clamped() {
+        assert_eq!(interval_from_secs(0), Duration::from_secs(1));
+        assert_eq!(interval_from_secs(30), Duration::from_secs(30));
+    }
+
+    #[tokio::test]
+    async fn test_is_due() {
+        let now = Instant::now();
+        let interval = Duration::from_secs(10);
+
+        // Never run before - due immediately
+        assert!(is_due(None, interval, now));
+
+        // Ran just now - not due yet
+        assert!(!is_due(Some(now), interval, now));
+
+        // Ran an interval ago - due
+        assert!(is_due(Some(now), interval, now + interval));
+    }
+
+    #[tokio::test]
+    async fn test_next_due() {
+        let now = Instant::now();
+        let interval = Duration::from_secs(10);
+
+        assert_eq!(next_due(None, interval, now), now);
+        assert_eq!(next_due(Some(now), interval, now), now + interval);
+    }
+
+    #[test]
+    fn test_pause_and_test_now_flags() {
+        let scheduler = Scheduler::new();
+        assert!(!scheduler.is_paused());
+
+        scheduler.test_now();
+        assert!(scheduler.test_now.swap(false, Ordering::SeqCst));
+        assert!(!scheduler.test_now.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_endpoint_result_serialization() {
+        let result = EndpointResult {
+            endpoint_id: "sh-azure-login".to_string(),
+            latency: 12.5,
+            timestamp: 1_700_000_000_000,
+            success: true,
+            error: None,
+            timings: None,
+            duration_ms: 0.0,
+        };
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["endpointId"], "sh-azure-login");
+        assert_eq!(json["success"], true);
+    }
+
+    #[tokio::test]
+    async fn test_join_all_reports_panicked_tasks() {
+        let futures = [0u32, 1, 2].map(|n| async move {
+            if n == 1 {
+                panic!("probe bug");
+            }
+            Ok::<_, ProbeError>(n)
+        });
+        let results = join_all(futures, |_, error| Err(error)).await;
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0], Ok(0));
+        assert!(matches!(&results[1], Err(ProbeError::Other(message)) if message.contains("panicked")));
+        assert_eq!(results[2], Ok(2));
+    }
+
+    #[test]
+    fn test_failed_result_has_no_history_latency() {
+        let result = EndpointResult {
+            endpoint_id: "sh-azure-login".to_string(),
+            latency: 0.0,
+            timestamp: 1_700_000_000_000,
+            success: false,
+            error: Some(ProbeError::ConnectTimeout),
+            timings: None,
+            duration_ms: 0.0,
+        };
+        let record = HistoryRecord::from(&result);
+        assert_eq!(record.latency, None);
+        assert_eq!(record.error.as_deref(), Some("Connection timeout"));
+        assert_eq!(record.error_code.as_deref(), Some("CONNECT_TIMEOUT"));
+    }
+}
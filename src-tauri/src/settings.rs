@@ -1,24 +1,35 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use tauri::Manager;
 
+use crate::fslogix::FSLogixPath;
+use crate::logger;
+
 const SETTINGS_FILENAME: &str = "settings.json";
 const SESSIONHOST_ENDPOINTS_FILENAME: &str = "sessionhost-endpoints.json";
 const ENDUSER_ENDPOINTS_FILENAME: &str = "enduser-endpoints.json";
 
 /// Application mode - determines which endpoint file to use
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum AppMode {
+    #[default]
     SessionHost,
     EndUser,
 }
 
-impl Default for AppMode {
-    fn default() -> Self {
-        AppMode::SessionHost
-    }
+/// Statistic of a multi-sample probe used as the endpoint latency (thresholds, graphs, tray colour)
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LatencyStatistic {
+    Min,
+    Avg,
+    #[default]
+    Median,
+    P95,
+    Max,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,6 +80,15 @@ pub struct Endpoint {
     /// Known subdomains to test for wildcard endpoints
     #[serde(default)]
     pub known_subdomains: Option<Vec<String>>,
+    /// Per-probe timeout override in milliseconds (defaults to the global setting)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Retry count override (defaults to the global setting)
+    #[serde(default)]
+    pub retries: Option<u32>,
+    /// Retry backoff override in milliseconds (defaults to the global setting)
+    #[serde(default)]
+    pub retry_backoff_ms: Option<u64>,
 }
 
 fn default_true() -> bool {
@@ -106,6 +126,15 @@ pub struct EndpointDefinition {
     /// Known subdomains to test for wildcard endpoints
     #[serde(default)]
     pub known_subdomains: Option<Vec<String>>,
+    /// Per-probe timeout override in milliseconds (defaults to the global setting)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Retry count override (defaults to the global setting)
+    #[serde(default)]
+    pub retries: Option<u32>,
+    /// Retry backoff override in milliseconds (defaults to the global setting)
+    #[serde(default)]
+    pub retry_backoff_ms: Option<u64>,
 }
 
 /// Category in the endpoint JSON files
@@ -159,6 +188,54 @@ pub struct AppConfig {
     pub fslogix_alert_threshold: u32,
     #[serde(default = "default_alert_cooldown")]
     pub fslogix_alert_cooldown: u32,
+    /// Also open each FSLogix share and list it, not only connect to the storage host
+    #[serde(default)]
+    pub fslogix_share_check: bool,
+    /// Probe every resolved address (IPv4 and IPv6) instead of only the first one
+    #[serde(default)]
+    pub probe_all_addresses: bool,
+    /// Timeout in milliseconds for each phase of a probe (DNS, connect, TLS handshake, first response byte)
+    #[serde(default = "default_probe_timeout_ms")]
+    pub probe_timeout_ms: u64,
+    /// Timeout in milliseconds for each phase of an HTTP/HTTPS probe, which used to get
+    /// twice as long as a TCP connect
+    #[serde(default = "default_http_probe_timeout_ms")]
+    pub http_probe_timeout_ms: u64,
+    /// Extra attempts after a failed probe before it is reported as failed
+    #[serde(default)]
+    pub probe_retries: u32,
+    /// Delay in milliseconds before the first retry, doubled for each further retry
+    #[serde(default = "default_probe_retry_backoff_ms")]
+    pub probe_retry_backoff_ms: u64,
+    /// Number of samples taken per probe run (1 = single reading)
+    #[serde(default = "default_probe_samples")]
+    pub probe_samples: u32,
+    /// Pause in milliseconds between the samples of one run
+    #[serde(default = "default_probe_sample_interval_ms")]
+    pub probe_sample_interval_ms: u64,
+    /// Statistic of the samples reported as the endpoint latency
+    #[serde(default)]
+    pub latency_statistic: LatencyStatistic,
+    /// Write alerts and recoveries to the Windows Event Log (syslog/journald on Linux)
+    #[serde(default)]
+    pub event_log_enabled: bool,
+    /// Serve Prometheus metrics on `metrics_port`
+    #[serde(default)]
+    pub metrics_enabled: bool,
+    /// TCP port of the /metrics listener
+    #[serde(default = "default_metrics_port")]
+    pub metrics_port: u16,
+    /// Local address the /metrics listener binds to; loopback unless scraping from another host
+    #[serde(default = "default_metrics_bind_address")]
+    pub metrics_bind_address: String,
+}
+
+fn default_metrics_port() -> u16 {
+    9469
+}
+
+fn default_metrics_bind_address() -> String {
+    "127.0.0.1".to_string()
 }
 
 fn default_mode() -> AppMode {
@@ -193,6 +270,26 @@ fn default_fslogix_test_interval() -> u32 {
     60
 }
 
+fn default_probe_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_http_probe_timeout_ms() -> u64 {
+    10000
+}
+
+fn default_probe_retry_backoff_ms() -> u64 {
+    1000
+}
+
+fn default_probe_samples() -> u32 {
+    1
+}
+
+fn default_probe_sample_interval_ms() -> u64 {
+    200
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
@@ -210,6 +307,19 @@ impl Default for AppConfig {
             fslogix_test_interval: 60,
             fslogix_alert_threshold: 3,
             fslogix_alert_cooldown: 5,
+            fslogix_share_check: false,
+            probe_all_addresses: false,
+            probe_timeout_ms: 5000,
+            http_probe_timeout_ms: 10000,
+            probe_retries: 0,
+            probe_retry_backoff_ms: 1000,
+            probe_samples: 1,
+            probe_sample_interval_ms: 200,
+            latency_statistic: LatencyStatistic::Median,
+            event_log_enabled: false,
+            metrics_enabled: false,
+            metrics_port: 9469,
+            metrics_bind_address: default_metrics_bind_address(),
         }
     }
 }
@@ -231,6 +341,15 @@ pub struct CustomEndpoint {
     pub enabled: bool,
     #[serde(default = "default_true")]
     pub latency_critical: bool,
+    /// Per-probe timeout override in milliseconds (defaults to the global setting)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Retry count override (defaults to the global setting)
+    #[serde(default)]
+    pub retries: Option<u32>,
+    /// Retry backoff override in milliseconds (defaults to the global setting)
+    #[serde(default)]
+    pub retry_backoff_ms: Option<u64>,
 }
 
 fn default_custom_category() -> Option<String> {
@@ -241,13 +360,81 @@ fn default_custom_category() -> Option<String> {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FSLogixPathState {
-    /// The path ID (e.g., "fslogix-profile-0")
+    /// The path ID (e.g., "fslogix-profile-3f2a9c0d1e4b5a67")
     pub id: String,
     /// If true, alerts are suppressed for this path
     #[serde(default)]
     pub muted: bool,
 }
 
+/// Payload template of a webhook
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    /// Plain JSON with the host and the list of events
+    #[default]
+    Json,
+    /// Adaptive Card for Microsoft Teams incoming webhooks and workflows
+    Teams,
+    /// Block Kit message for Slack incoming webhooks
+    Slack,
+}
+
+/// Outbound webhook receiving alert and recovery events (stored in settings.json)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookConfig {
+    pub name: String,
+    pub url: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    /// Extra request headers, e.g. an Authorization token
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Retries after a failed delivery, with exponential backoff
+    #[serde(default = "default_webhook_max_retries")]
+    pub max_retries: u32,
+    /// Deliveries allowed per minute, further events are dropped (0 = unlimited)
+    #[serde(default = "default_webhook_max_per_minute")]
+    pub max_per_minute: u32,
+}
+
+fn default_webhook_max_retries() -> u32 {
+    3
+}
+
+fn default_webhook_max_per_minute() -> u32 {
+    10
+}
+
+/// Re-key FSLogix path states saved under the old index-based IDs and drop the states of
+/// paths that no longer exist. Returns true when the states changed and should be saved.
+pub fn reconcile_fslogix_path_states(states: &mut Vec<FSLogixPathState>, paths: &[FSLogixPath]) -> bool {
+    let before = states.len();
+    let mut migrated = false;
+
+    for path in paths {
+        if states.iter().any(|s| s.id == path.id) {
+            continue;
+        }
+        let legacy = path.legacy_id.as_deref();
+        if let Some(state) = states.iter_mut().find(|s| Some(s.id.as_str()) == legacy) {
+            state.id = path.id.clone();
+            migrated = true;
+        }
+    }
+
+    // An empty list means FSLogix is not configured (or not Windows) - keep the states
+    // rather than losing every muted path to a transient registry read
+    if !paths.is_empty() {
+        states.retain(|s| paths.iter().any(|p| p.id == s.id));
+    }
+
+    migrated || states.len() != before
+}
+
 /// Settings file structure - contains config only, endpoints are in separate files
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -262,6 +449,9 @@ pub struct SettingsFile {
     /// FSLogix path muted states
     #[serde(default)]
     pub fslogix_path_states: Vec<FSLogixPathState>,
+    /// Webhooks notified about alerts and recoveries
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
 }
 
 fn default_version() -> u32 {
@@ -275,6 +465,7 @@ impl Default for SettingsFile {
             config: AppConfig::default(),
             custom_endpoints: Vec::new(),
             fslogix_path_states: Vec::new(),
+            webhooks: Vec::new(),
         }
     }
 }
@@ -372,7 +563,8 @@ pub fn get_endpoint_file_path(mode: &AppMode) -> std::io::Result<PathBuf> {
 }
 
 /// Load endpoint file for the given mode
-pub fn load_endpoint_file(app: &tauri::AppHandle, mode: &AppMode) -> std::io::Result<EndpointFile> {
+/// Without an app handle (headless CLI), bundled resources are only searched next to the exe.
+pub fn load_endpoint_file(app: Option<&tauri::AppHandle>, mode: &AppMode) -> std::io::Result<EndpointFile> {
     let path = get_endpoint_file_path(mode)?;
     let filename = match mode {
         AppMode::SessionHost => SESSIONHOST_ENDPOINTS_FILENAME,
@@ -384,7 +576,7 @@ pub fn load_endpoint_file(app: &tauri::AppHandle, mode: &AppMode) -> std::io::Re
         let possible_resource_paths: Vec<PathBuf> = {
             let mut paths = Vec::new();
 
-            if let Ok(resource_path) = app.path().resource_dir() {
+            if let Some(resource_path) = app.and_then(|app| app.path().resource_dir().ok()) {
                 paths.push(resource_path.clone());
                 paths.push(resource_path.join("resources"));
             }
@@ -401,10 +593,8 @@ pub fn load_endpoint_file(app: &tauri::AppHandle, mode: &AppMode) -> std::io::Re
 
         for resource_dir in &possible_resource_paths {
             let source_path = resource_dir.join(filename);
-            if source_path.exists() {
-                if fs::copy(&source_path, &path).is_ok() {
-                    break;
-                }
+            if source_path.exists() && fs::copy(&source_path, &path).is_ok() {
+                break;
             }
         }
     }
@@ -466,6 +656,9 @@ fn endpoints_from_file(endpoint_file: &EndpointFile) -> Vec<Endpoint> {
                         // Keep the wildcard info for reference but this is an expanded endpoint
                         wildcard_pattern: Some(pattern.clone()),
                         known_subdomains: None, // Individual expanded endpoints don't have subdomains
+                        timeout_ms: ep_def.timeout_ms,
+                        retries: ep_def.retries,
+                        retry_backoff_ms: ep_def.retry_backoff_ms,
                     });
                 }
             } else {
@@ -485,6 +678,9 @@ fn endpoints_from_file(endpoint_file: &EndpointFile) -> Vec<Endpoint> {
                     latency_critical: ep_def.latency_critical,
                     wildcard_pattern: None,
                     known_subdomains: None,
+                    timeout_ms: ep_def.timeout_ms,
+                    retries: ep_def.retries,
+                    retry_backoff_ms: ep_def.retry_backoff_ms,
                 });
             }
         }
@@ -515,14 +711,9 @@ pub fn load_settings_with_endpoints(app: &tauri::AppHandle) -> std::io::Result<S
     load_settings_with_endpoints_for_mode(app, &settings, &settings.config.mode)
 }
 
-/// Load settings and resolve endpoints for a specific mode
-pub fn load_settings_with_endpoints_for_mode(
-    app: &tauri::AppHandle,
-    settings: &SettingsFile,
-    mode: &AppMode,
-) -> std::io::Result<SettingsResponse> {
-    let endpoint_file = load_endpoint_file(app, mode)?;
-    let mut endpoints = endpoints_from_file(&endpoint_file);
+/// Resolve the full endpoint list from an endpoint file plus the user's custom endpoints
+pub fn resolve_endpoints(endpoint_file: &EndpointFile, settings: &SettingsFile) -> Vec<Endpoint> {
+    let mut endpoints = endpoints_from_file(endpoint_file);
 
     // Add custom endpoints
     for custom in &settings.custom_endpoints {
@@ -541,9 +732,24 @@ pub fn load_settings_with_endpoints_for_mode(
             latency_critical: Some(custom.latency_critical),
             wildcard_pattern: None,
             known_subdomains: None,
+            timeout_ms: custom.timeout_ms,
+            retries: custom.retries,
+            retry_backoff_ms: custom.retry_backoff_ms,
         });
     }
 
+    endpoints
+}
+
+/// Load settings and resolve endpoints for a specific mode
+pub fn load_settings_with_endpoints_for_mode(
+    app: &tauri::AppHandle,
+    settings: &SettingsFile,
+    mode: &AppMode,
+) -> std::io::Result<SettingsResponse> {
+    let endpoint_file = load_endpoint_file(Some(app), mode)?;
+    let endpoints = resolve_endpoints(&endpoint_file, settings);
+
     // Create a config with the requested mode
     let mut config = settings.config.clone();
     config.mode = mode.clone();
@@ -565,12 +771,12 @@ pub fn initialize_settings(app: &tauri::AppHandle) -> std::io::Result<()> {
     let settings_path = get_settings_path()?;
     let settings_dir = get_settings_dir()?;
 
-    println!("[Settings] Settings directory: {:?}", settings_dir);
-    println!("[Settings] Settings file path: {:?}", settings_path);
+    logger::info("Settings", &format!("Settings directory: {:?}", settings_dir));
+    logger::info("Settings", &format!("Settings file path: {:?}", settings_path));
 
     // Create settings.json if it doesn't exist
     if !settings_path.exists() {
-        println!("[Settings] Creating default settings file");
+        logger::info("Settings", "Creating default settings file");
         let settings = SettingsFile::default();
         save_settings(&settings)?;
     }
@@ -597,38 +803,38 @@ pub fn initialize_settings(app: &tauri::AppHandle) -> std::io::Result<()> {
         paths
     };
 
-    println!("[Settings] Searching for resources in: {:?}", possible_resource_paths);
+    logger::debug("Settings", &format!("Searching for resources in: {:?}", possible_resource_paths));
 
     // Copy endpoint files to settings directory if they don't exist
     for filename in [SESSIONHOST_ENDPOINTS_FILENAME, ENDUSER_ENDPOINTS_FILENAME] {
         let target_path = settings_dir.join(filename);
         if !target_path.exists() {
-            println!("[Settings] Looking for resource file: {}", filename);
+            logger::debug("Settings", &format!("Looking for resource file: {}", filename));
 
             let mut copied = false;
             for resource_dir in &possible_resource_paths {
                 let source_path = resource_dir.join(filename);
-                println!("[Settings] Checking: {:?}", source_path);
+                logger::debug("Settings", &format!("Checking: {:?}", source_path));
 
                 if source_path.exists() {
                     match fs::copy(&source_path, &target_path) {
                         Ok(_) => {
-                            println!("[Settings] Successfully copied {} to {:?}", filename, target_path);
+                            logger::info("Settings", &format!("Successfully copied {} to {:?}", filename, target_path));
                             copied = true;
                             break;
                         }
                         Err(e) => {
-                            eprintln!("[Settings] Failed to copy {}: {}", filename, e);
+                            logger::error("Settings", &format!("Failed to copy {}: {}", filename, e));
                         }
                     }
                 }
             }
 
             if !copied {
-                eprintln!("[Settings] WARNING: Could not find resource file: {}", filename);
+                logger::warn("Settings", &format!("Could not find resource file: {}", filename));
             }
         } else {
-            println!("[Settings] File already exists: {:?}", target_path);
+            logger::debug("Settings", &format!("File already exists: {:?}", target_path));
         }
     }
 
@@ -644,7 +850,49 @@ pub fn save_settings(settings: &SettingsFile) -> std::io::Result<()> {
     Ok(())
 }
 
+/// Apply settings saved by the frontend on top of the existing file
+/// The UI only sends config and custom endpoints; webhooks and FSLogix path states are kept.
+pub fn merge_settings_update(existing: SettingsFile, update: SettingsFile) -> SettingsFile {
+    SettingsFile {
+        version: update.version,
+        config: update.config,
+        custom_endpoints: update.custom_endpoints,
+        ..existing
+    }
+}
+
+/// Load the FSLogix path states for the given paths, saving them back if they had to be migrated
+pub fn load_fslogix_path_states(paths: &[FSLogixPath]) -> std::io::Result<Vec<FSLogixPathState>> {
+    let mut settings = load_settings()?;
+    // Move states saved under index-based IDs and drop states of removed paths
+    if reconcile_fslogix_path_states(&mut settings.fslogix_path_states, paths) {
+        if let Err(e) = save_settings(&settings) {
+            logger::error("FSLogix", &format!("Failed to save migrated path states: {}", e));
+        }
+    }
+    Ok(settings.fslogix_path_states)
+}
+
+/// Set the saved muted state on each path
+pub fn apply_fslogix_path_states(paths: &mut [FSLogixPath]) {
+    if let Ok(states) = load_fslogix_path_states(paths) {
+        for path in paths.iter_mut() {
+            if let Some(state) = states.iter().find(|s| s.id == path.id) {
+                path.muted = Some(state.muted);
+            }
+        }
+    }
+}
+
+/// Save settings coming from the frontend without dropping fields it doesn't manage
+pub fn save_settings_update(update: SettingsFile) -> std::io::Result<SettingsFile> {
+    let settings = merge_settings_update(load_settings()?, update);
+    save_settings(&settings)?;
+    Ok(settings)
+}
+
 /// Update endpoint properties directly in the endpoint JSON file
+#[allow(clippy::too_many_arguments)]
 pub fn update_endpoint_state(
     app: &tauri::AppHandle,
     mode: &AppMode,
@@ -655,7 +903,7 @@ pub fn update_endpoint_state(
     url: Option<String>,
     port: Option<u16>,
 ) -> std::io::Result<()> {
-    let mut endpoint_file = load_endpoint_file(app, mode)?;
+    let mut endpoint_file = load_endpoint_file(Some(app), mode)?;
 
     // Find and update the endpoint
     'outer: for category in &mut endpoint_file.categories {
@@ -697,6 +945,8 @@ mod tests {
         assert_eq!(settings.config.mode, AppMode::SessionHost);
         assert_eq!(settings.config.test_interval, 10);
         assert_eq!(settings.config.alert_threshold, 3);
+        // The unauthenticated metrics listener stays on loopback unless configured otherwise
+        assert_eq!(settings.config.metrics_bind_address, "127.0.0.1");
     }
 
     #[test]
@@ -709,4 +959,87 @@ mod tests {
         let json = serde_json::to_string(&mode).unwrap();
         assert_eq!(json, "\"sessionhost\"");
     }
+
+    fn fslogix_path(id: &str, legacy_id: &str) -> FSLogixPath {
+        FSLogixPath {
+            id: id.to_string(),
+            path_type: "profile".to_string(),
+            path: r"\\server\profiles".to_string(),
+            hostname: "server".to_string(),
+            port: 445,
+            muted: None,
+            provider: crate::fslogix::StorageProvider::Smb,
+            cloud_cache: false,
+            legacy_id: Some(legacy_id.to_string()),
+        }
+    }
+
+    fn state(id: &str, muted: bool) -> FSLogixPathState {
+        FSLogixPathState { id: id.to_string(), muted }
+    }
+
+    #[test]
+    fn test_reconcile_fslogix_path_states() {
+        let paths = vec![
+            fslogix_path("fslogix-profile-aaaa", "fslogix-profile-0"),
+            fslogix_path("fslogix-profile-bbbb", "fslogix-profile-1"),
+        ];
+        let mut states = vec![
+            state("fslogix-profile-1", true),
+            state("fslogix-profile-aaaa", false),
+            state("fslogix-profile-cccc", true),
+        ];
+
+        assert!(reconcile_fslogix_path_states(&mut states, &paths));
+        assert_eq!(states.len(), 2);
+        // Legacy state moved to the new ID, the stale one is gone
+        assert!(states.iter().any(|s| s.id == "fslogix-profile-bbbb" && s.muted));
+        assert!(states.iter().any(|s| s.id == "fslogix-profile-aaaa" && !s.muted));
+
+        // Already migrated - nothing to save
+        assert!(!reconcile_fslogix_path_states(&mut states, &paths));
+
+        // No paths found: keep everything
+        assert!(!reconcile_fslogix_path_states(&mut states, &[]));
+        assert_eq!(states.len(), 2);
+    }
+
+    #[test]
+    fn test_webhook_defaults() {
+        let settings: SettingsFile = serde_json::from_str(
+            r#"{"webhooks": [{"name": "NOC", "url": "https://hooks.example.com/x", "format": "slack"}]}"#,
+        )
+        .unwrap();
+        let webhook = &settings.webhooks[0];
+        assert!(webhook.enabled);
+        assert_eq!(webhook.format, WebhookFormat::Slack);
+        assert_eq!(webhook.max_retries, 3);
+        assert_eq!(webhook.max_per_minute, 10);
+
+        assert!(serde_json::from_str::<SettingsFile>("{}").unwrap().webhooks.is_empty());
+    }
+
+    #[test]
+    fn test_settings_update_keeps_webhooks() {
+        let existing: SettingsFile = serde_json::from_str(
+            r#"{
+                "webhooks": [{"name": "NOC", "url": "https://hooks.example.com/x"}],
+                "fslogixPathStates": [{"id": "fslogix-profile-aaaa", "muted": true}]
+            }"#,
+        )
+        .unwrap();
+        // Same shape as the frontend's saveSettingsToFile payload
+        let update: SettingsFile = serde_json::from_str(
+            r#"{"version": 1, "config": {"retentionDays": 7}, "customEndpoints": [{"id": "custom-1", "name": "Intranet", "url": "intranet.example.com"}]}"#,
+        )
+        .unwrap();
+
+        let merged = merge_settings_update(existing, update);
+        assert_eq!(merged.config.retention_days, 7);
+        assert_eq!(merged.custom_endpoints.len(), 1);
+        assert_eq!(merged.webhooks.len(), 1);
+        assert_eq!(merged.webhooks[0].name, "NOC");
+        assert_eq!(merged.fslogix_path_states.len(), 1);
+        assert!(merged.fslogix_path_states[0].muted);
+    }
 }
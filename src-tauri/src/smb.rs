@@ -0,0 +1,521 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+use crate::errors::ProbeError;
+use crate::latency::{self, ProbeOptions, ProbeResult};
+
+/// SMB over TCP (direct hosting)
+pub const SMB_PORT: u16 = 445;
+
+const SMB2_MAGIC: [u8; 4] = [0xFE, b'S', b'M', b'B'];
+const SMB1_MAGIC: [u8; 4] = [0xFF, b'S', b'M', b'B'];
+const SMB2_HEADER_LEN: usize = 64;
+const SMB2_NEGOTIATE: u16 = 0x0000;
+const SMB1_COM_NEGOTIATE: u8 = 0x72;
+/// Dialects offered, oldest first: 2.0.2, 2.1, 3.0, 3.0.2, 3.1.1
+const DIALECTS: [u16; 5] = [0x0202, 0x0210, 0x0300, 0x0302, 0x0311];
+const DIALECT_311: u16 = 0x0311;
+const SIGNING_ENABLED: u16 = 0x0001;
+const SIGNING_REQUIRED: u16 = 0x0002;
+const CAP_ENCRYPTION: u32 = 0x0000_0040;
+const PREAUTH_INTEGRITY_CAPABILITIES: u16 = 0x0001;
+const ENCRYPTION_CAPABILITIES: u16 = 0x0002;
+const SIGNING_CAPABILITIES: u16 = 0x0008;
+const SHA_512: u16 = 0x0001;
+/// AES-128-CCM, AES-128-GCM, AES-256-CCM, AES-256-GCM
+const CIPHERS: [u16; 4] = [0x0001, 0x0002, 0x0003, 0x0004];
+/// HMAC-SHA256, AES-CMAC, AES-GMAC
+const SIGNING_ALGORITHMS: [u16; 3] = [0x0000, 0x0001, 0x0002];
+/// Largest response we accept; NEGOTIATE responses carry a SPNEGO blob of a few hundred bytes
+const MAX_RESPONSE_LEN: usize = 64 * 1024;
+
+/// Result of an SMB NEGOTIATE probe
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmbProbeResult {
+    /// Timing breakdown; `ttfbMs` is the NEGOTIATE round trip, `latencyMs` the TCP connect time
+    pub timings: ProbeResult,
+    /// Negotiated dialect, e.g. "3.1.1"
+    pub dialect: String,
+    pub signing_enabled: bool,
+    pub signing_required: bool,
+    /// Signing algorithm that will be used, e.g. "AES-GMAC"
+    pub signing_algorithm: String,
+    pub encryption_supported: bool,
+    /// Cipher selected by the server (SMB 3.1.1) or implied by the dialect (SMB 3.0)
+    pub cipher: Option<String>,
+}
+
+/// What the server chose in its NEGOTIATE response
+#[derive(Debug, Clone, PartialEq)]
+struct Negotiated {
+    dialect: u16,
+    security_mode: u16,
+    capabilities: u32,
+    /// Cipher from the encryption context (SMB 3.1.1), 0 if the server declined encryption
+    cipher: Option<u16>,
+    /// Algorithm from the signing context (SMB 3.1.1)
+    signing: Option<u16>,
+}
+
+/// Connect to an SMB server and perform the SMB2/3 NEGOTIATE handshake
+/// A server that accepts the TCP connection but never answers is reported as SMB_TIMEOUT, one that
+/// closes the connection is checked for SMB1 and reported as SMB1_ONLY if it answers an SMB1 NEGOTIATE.
+pub async fn test_smb(host: &str, port: u16, options: &ProbeOptions) -> Result<SmbProbeResult, ProbeError> {
+    let (mut stream, connection) = latency::establish(host, port, options).await?;
+
+    let start = Instant::now();
+    let negotiated = match timeout(options.timeout, negotiate(&mut stream)).await {
+        Ok(Ok(negotiated)) => negotiated,
+        Ok(Err(ProbeError::Smb1Only)) => return Err(ProbeError::Smb1Only),
+        Ok(Err(e)) => {
+            // SMB1-only servers drop the connection on an SMB2 NEGOTIATE
+            drop(stream);
+            return Err(if speaks_smb1(host, port, options).await { ProbeError::Smb1Only } else { e });
+        }
+        Err(_) => return Err(ProbeError::SmbTimeout),
+    };
+    let negotiate_time = start.elapsed();
+
+    // Thresholds and history were set against the plain TCP connect check, so the
+    // NEGOTIATE round trip is only reported in ttfbMs and total time
+    let mut timings = ProbeResult::new(&connection, None, Some(negotiate_time));
+    timings.latency_ms = timings.connect_ms;
+
+    Ok(SmbProbeResult {
+        timings,
+        dialect: dialect_name(negotiated.dialect),
+        signing_enabled: negotiated.security_mode & SIGNING_ENABLED != 0,
+        signing_required: negotiated.security_mode & SIGNING_REQUIRED != 0,
+        signing_algorithm: signing_algorithm_name(negotiated.signing.unwrap_or(match negotiated.dialect {
+            0x0202 | 0x0210 => 0x0000,
+            _ => 0x0001,
+        })),
+        encryption_supported: negotiated.cipher.map(|c| c != 0).unwrap_or(negotiated.capabilities & CAP_ENCRYPTION != 0),
+        cipher: match negotiated.cipher {
+            Some(0) => None,
+            Some(cipher) => Some(cipher_name(cipher)),
+            // SMB 3.0 and 3.0.2 only know AES-128-CCM
+            None if negotiated.capabilities & CAP_ENCRYPTION != 0 => Some(cipher_name(0x0001)),
+            None => None,
+        },
+    })
+}
+
+async fn negotiate(stream: &mut TcpStream) -> Result<Negotiated, ProbeError> {
+    stream.write_all(&negotiate_request()).await.map_err(ProbeError::from_io)?;
+    let response = read_message(stream).await?;
+    parse_negotiate_response(&response)
+}
+
+/// Whether the server answers an SMB1 NEGOTIATE with an SMB1 response
+async fn speaks_smb1(host: &str, port: u16, options: &ProbeOptions) -> bool {
+    let check = async {
+        let (mut stream, _) = latency::establish(host, port, options).await.ok()?;
+        stream.write_all(&smb1_negotiate_request()).await.ok()?;
+        let response = read_message(&mut stream).await.ok()?;
+        Some(response.starts_with(&SMB1_MAGIC))
+    };
+    matches!(timeout(options.timeout, check).await, Ok(Some(true)))
+}
+
+/// Read one message framed with the 4-byte direct TCP transport header
+async fn read_message(stream: &mut TcpStream) -> Result<Vec<u8>, ProbeError> {
+    let mut header = [0u8; 4];
+    stream.read_exact(&mut header).await.map_err(closed_or_io)?;
+    let len = u32::from_be_bytes(header) as usize & 0x00FF_FFFF;
+    if header[0] != 0 || len > MAX_RESPONSE_LEN {
+        return Err(ProbeError::SmbError("Response is not an SMB message".to_string()));
+    }
+
+    let mut message = vec![0u8; len];
+    stream.read_exact(&mut message).await.map_err(closed_or_io)?;
+    Ok(message)
+}
+
+fn closed_or_io(error: std::io::Error) -> ProbeError {
+    match error.kind() {
+        std::io::ErrorKind::UnexpectedEof => ProbeError::SmbError("Connection closed during NEGOTIATE".to_string()),
+        _ => ProbeError::from_io(error),
+    }
+}
+
+/// SMB2 NEGOTIATE request offering 2.0.2 to 3.1.1, with the 3.1.1 negotiate contexts
+fn negotiate_request() -> Vec<u8> {
+    let mut message = Vec::with_capacity(256);
+
+    // SMB2 header
+    message.extend_from_slice(&SMB2_MAGIC);
+    message.extend_from_slice(&(SMB2_HEADER_LEN as u16).to_le_bytes());
+    message.extend_from_slice(&0u16.to_le_bytes()); // CreditCharge
+    message.extend_from_slice(&0u32.to_le_bytes()); // Status
+    message.extend_from_slice(&SMB2_NEGOTIATE.to_le_bytes());
+    message.extend_from_slice(&1u16.to_le_bytes()); // CreditRequest
+    message.extend_from_slice(&0u32.to_le_bytes()); // Flags
+    message.extend_from_slice(&0u32.to_le_bytes()); // NextCommand
+    message.extend_from_slice(&0u64.to_le_bytes()); // MessageId
+    message.extend_from_slice(&0u32.to_le_bytes()); // Reserved
+    message.extend_from_slice(&0u32.to_le_bytes()); // TreeId
+    message.extend_from_slice(&0u64.to_le_bytes()); // SessionId
+    message.extend_from_slice(&[0u8; 16]); // Signature
+
+    // NEGOTIATE request
+    message.extend_from_slice(&36u16.to_le_bytes()); // StructureSize
+    message.extend_from_slice(&(DIALECTS.len() as u16).to_le_bytes());
+    message.extend_from_slice(&SIGNING_ENABLED.to_le_bytes());
+    message.extend_from_slice(&0u16.to_le_bytes()); // Reserved
+    message.extend_from_slice(&CAP_ENCRYPTION.to_le_bytes());
+    message.extend_from_slice(&random_bytes::<16>()); // ClientGuid
+    let context_offset_at = message.len();
+    message.extend_from_slice(&0u32.to_le_bytes()); // NegotiateContextOffset, patched below
+    message.extend_from_slice(&3u16.to_le_bytes()); // NegotiateContextCount
+    message.extend_from_slice(&0u16.to_le_bytes()); // Reserved2
+    for dialect in DIALECTS {
+        message.extend_from_slice(&dialect.to_le_bytes());
+    }
+
+    pad_to_8(&mut message);
+    let context_offset = message.len() as u32;
+    message[context_offset_at..context_offset_at + 4].copy_from_slice(&context_offset.to_le_bytes());
+
+    let mut preauth = Vec::new();
+    preauth.extend_from_slice(&1u16.to_le_bytes()); // HashAlgorithmCount
+    preauth.extend_from_slice(&32u16.to_le_bytes()); // SaltLength
+    preauth.extend_from_slice(&SHA_512.to_le_bytes());
+    preauth.extend_from_slice(&random_bytes::<32>());
+    push_context(&mut message, PREAUTH_INTEGRITY_CAPABILITIES, &preauth);
+
+    let mut encryption = (CIPHERS.len() as u16).to_le_bytes().to_vec();
+    CIPHERS.iter().for_each(|c| encryption.extend_from_slice(&c.to_le_bytes()));
+    pad_to_8(&mut message);
+    push_context(&mut message, ENCRYPTION_CAPABILITIES, &encryption);
+
+    let mut signing = (SIGNING_ALGORITHMS.len() as u16).to_le_bytes().to_vec();
+    SIGNING_ALGORITHMS.iter().for_each(|a| signing.extend_from_slice(&a.to_le_bytes()));
+    pad_to_8(&mut message);
+    push_context(&mut message, SIGNING_CAPABILITIES, &signing);
+
+    frame(message)
+}
+
+/// SMB1 NEGOTIATE request offering NT LM 0.12 and SMB 2.x, as sent by older clients
+fn smb1_negotiate_request() -> Vec<u8> {
+    let mut message = Vec::with_capacity(96);
+    message.extend_from_slice(&SMB1_MAGIC);
+    message.push(SMB1_COM_NEGOTIATE);
+    message.extend_from_slice(&0u32.to_le_bytes()); // Status
+    message.push(0x18); // Flags: case insensitive, canonicalized paths
+    message.extend_from_slice(&0xC853u16.to_le_bytes()); // Flags2: unicode, NT status, extended security
+    message.extend_from_slice(&[0u8; 12]); // PIDHigh, SecurityFeatures, Reserved
+    message.extend_from_slice(&0xFFFFu16.to_le_bytes()); // TID
+    message.extend_from_slice(&0xFEFFu16.to_le_bytes()); // PIDLow
+    message.extend_from_slice(&0u16.to_le_bytes()); // UID
+    message.extend_from_slice(&0u16.to_le_bytes()); // MID
+    message.push(0); // WordCount
+
+    let mut dialects = Vec::new();
+    for dialect in ["NT LM 0.12", "SMB 2.002", "SMB 2.???"] {
+        dialects.push(0x02);
+        dialects.extend_from_slice(dialect.as_bytes());
+        dialects.push(0);
+    }
+    message.extend_from_slice(&(dialects.len() as u16).to_le_bytes());
+    message.extend_from_slice(&dialects);
+
+    frame(message)
+}
+
+fn push_context(message: &mut Vec<u8>, context_type: u16, data: &[u8]) {
+    message.extend_from_slice(&context_type.to_le_bytes());
+    message.extend_from_slice(&(data.len() as u16).to_le_bytes());
+    message.extend_from_slice(&0u32.to_le_bytes()); // Reserved
+    message.extend_from_slice(data);
+}
+
+fn pad_to_8(message: &mut Vec<u8>) {
+    message.resize(message.len().next_multiple_of(8), 0);
+}
+
+/// Prefix the direct TCP transport header (zero byte and 24-bit length)
+fn frame(message: Vec<u8>) -> Vec<u8> {
+    let mut framed = (message.len() as u32).to_be_bytes().to_vec();
+    framed.extend_from_slice(&message);
+    framed
+}
+
+fn parse_negotiate_response(message: &[u8]) -> Result<Negotiated, ProbeError> {
+    if message.starts_with(&SMB1_MAGIC) {
+        return Err(ProbeError::Smb1Only);
+    }
+    if !message.starts_with(&SMB2_MAGIC) || message.len() < SMB2_HEADER_LEN + 64 {
+        return Err(ProbeError::SmbError("Response is not an SMB2 message".to_string()));
+    }
+
+    let status = read_u32(message, 8);
+    if status != 0 {
+        return Err(ProbeError::SmbError(format!("NEGOTIATE failed with status 0x{:08X}", status)));
+    }
+    if read_u16(message, 12) != SMB2_NEGOTIATE {
+        return Err(ProbeError::SmbError("Unexpected response command".to_string()));
+    }
+
+    let body = &message[SMB2_HEADER_LEN..];
+    let dialect = read_u16(body, 4);
+    if !DIALECTS.contains(&dialect) {
+        return Err(ProbeError::SmbError(format!("Server chose unknown dialect 0x{:04X}", dialect)));
+    }
+
+    let mut negotiated = Negotiated {
+        dialect,
+        security_mode: read_u16(body, 2),
+        capabilities: read_u32(body, 24),
+        cipher: None,
+        signing: None,
+    };
+
+    if dialect == DIALECT_311 {
+        let count = read_u16(body, 6);
+        let mut offset = read_u32(body, 60) as usize;
+        for _ in 0..count {
+            let Some(header) = message.get(offset..offset + 8) else { break };
+            let context_type = read_u16(header, 0);
+            let len = usize::from(read_u16(header, 2));
+            let Some(data) = message.get(offset + 8..offset + 8 + len) else { break };
+
+            // The server answers each context with exactly one selected value
+            if data.len() >= 4 {
+                match context_type {
+                    ENCRYPTION_CAPABILITIES => negotiated.cipher = Some(read_u16(data, 2)),
+                    SIGNING_CAPABILITIES => negotiated.signing = Some(read_u16(data, 2)),
+                    _ => {}
+                }
+            }
+            offset = (offset + 8 + len).next_multiple_of(8);
+        }
+        // A 3.1.1 server without an encryption context doesn't support encryption
+        negotiated.cipher.get_or_insert(0);
+    }
+
+    Ok(negotiated)
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+fn dialect_name(dialect: u16) -> String {
+    match dialect {
+        0x0202 => "2.0.2",
+        0x0210 => "2.1",
+        0x0300 => "3.0",
+        0x0302 => "3.0.2",
+        0x0311 => "3.1.1",
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+fn cipher_name(cipher: u16) -> String {
+    match cipher {
+        0x0001 => "AES-128-CCM".to_string(),
+        0x0002 => "AES-128-GCM".to_string(),
+        0x0003 => "AES-256-CCM".to_string(),
+        0x0004 => "AES-256-GCM".to_string(),
+        other => format!("0x{:04X}", other),
+    }
+}
+
+fn signing_algorithm_name(algorithm: u16) -> String {
+    match algorithm {
+        0x0000 => "HMAC-SHA256".to_string(),
+        0x0001 => "AES-CMAC".to_string(),
+        0x0002 => "AES-GMAC".to_string(),
+        other => format!("0x{:04X}", other),
+    }
+}
+
+/// Client GUID and preauth salt; they only need to differ between probes, not be secret
+fn random_bytes<const N: usize>() -> [u8; N] {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let mut state = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default()
+        ^ COUNTER.fetch_add(1, Ordering::Relaxed).rotate_left(32)
+        ^ u64::from(std::process::id());
+
+    let mut bytes = [0u8; N];
+    for byte in bytes.iter_mut() {
+        // xorshift64
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        *byte = state as u8;
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+    use std::time::Duration;
+    use tokio::net::TcpListener;
+
+    /// How the stub server reacts to an SMB2 NEGOTIATE
+    #[derive(Clone, Copy)]
+    enum Stub {
+        /// Answer with the given dialect, security mode, capabilities and (for 3.1.1) cipher and signing algorithm
+        Answer(u16, u16, u32, u16, u16),
+        /// Close SMB2 connections, answer SMB1 NEGOTIATEs
+        Smb1Only,
+        /// Accept and never answer
+        Silent,
+    }
+
+    fn negotiate_response(dialect: u16, security_mode: u16, capabilities: u32, cipher: u16, signing: u16) -> Vec<u8> {
+        let mut message = vec![0u8; SMB2_HEADER_LEN + 64];
+        message[..4].copy_from_slice(&SMB2_MAGIC);
+        message[4..6].copy_from_slice(&(SMB2_HEADER_LEN as u16).to_le_bytes());
+        message[14] = 1; // CreditResponse
+        message[16] = 0x01; // Flags: server to redirector
+
+        let body = SMB2_HEADER_LEN;
+        message[body..body + 2].copy_from_slice(&65u16.to_le_bytes());
+        message[body + 2..body + 4].copy_from_slice(&security_mode.to_le_bytes());
+        message[body + 4..body + 6].copy_from_slice(&dialect.to_le_bytes());
+        message[body + 24..body + 28].copy_from_slice(&capabilities.to_le_bytes());
+
+        if dialect == DIALECT_311 {
+            message[body + 6..body + 8].copy_from_slice(&3u16.to_le_bytes());
+            pad_to_8(&mut message);
+            let offset = message.len() as u32;
+            message[body + 60..body + 64].copy_from_slice(&offset.to_le_bytes());
+
+            let mut preauth = vec![1, 0, 32, 0];
+            preauth.extend_from_slice(&SHA_512.to_le_bytes());
+            preauth.extend_from_slice(&[7u8; 32]);
+            push_context(&mut message, PREAUTH_INTEGRITY_CAPABILITIES, &preauth);
+            pad_to_8(&mut message);
+            let mut encryption = 1u16.to_le_bytes().to_vec();
+            encryption.extend_from_slice(&cipher.to_le_bytes());
+            push_context(&mut message, ENCRYPTION_CAPABILITIES, &encryption);
+            pad_to_8(&mut message);
+            let mut signing_context = 1u16.to_le_bytes().to_vec();
+            signing_context.extend_from_slice(&signing.to_le_bytes());
+            push_context(&mut message, SIGNING_CAPABILITIES, &signing_context);
+        }
+        message
+    }
+
+    fn smb1_negotiate_response() -> Vec<u8> {
+        let mut message = vec![0u8; 37];
+        message[..4].copy_from_slice(&SMB1_MAGIC);
+        message[4] = SMB1_COM_NEGOTIATE;
+        message
+    }
+
+    async fn start_smb_server(stub: Stub) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let Ok(request) = read_message(&mut stream).await else { return };
+                    let response = match stub {
+                        Stub::Answer(dialect, mode, caps, cipher, signing) if request.starts_with(&SMB2_MAGIC) => {
+                            negotiate_response(dialect, mode, caps, cipher, signing)
+                        }
+                        Stub::Smb1Only if request.starts_with(&SMB1_MAGIC) => smb1_negotiate_response(),
+                        Stub::Silent => {
+                            tokio::time::sleep(Duration::from_secs(10)).await;
+                            return;
+                        }
+                        _ => return,
+                    };
+                    let _ = stream.write_all(&frame(response)).await;
+                });
+            }
+        });
+        addr
+    }
+
+    #[test]
+    fn test_negotiate_request_layout() {
+        let request = negotiate_request();
+        let message = &request[4..];
+        assert_eq!(u32::from_be_bytes(request[..4].try_into().unwrap()) as usize, message.len());
+        assert!(message.starts_with(&SMB2_MAGIC));
+        assert_eq!(read_u16(message, 64), 36);
+        assert_eq!(read_u16(message, 66), DIALECTS.len() as u16);
+
+        // Negotiate contexts start 8-byte aligned after the dialect list
+        let context_offset = read_u32(message, 64 + 28) as usize;
+        assert_eq!(context_offset % 8, 0);
+        assert_eq!(read_u16(message, context_offset), PREAUTH_INTEGRITY_CAPABILITIES);
+        assert_eq!(read_u16(message, 64 + 36 + 2 * (DIALECTS.len() - 1)), DIALECT_311);
+    }
+
+    #[tokio::test]
+    async fn test_smb311_with_encryption() {
+        let server = start_smb_server(Stub::Answer(0x0311, SIGNING_ENABLED | SIGNING_REQUIRED, CAP_ENCRYPTION, 0x0004, 0x0002)).await;
+
+        let result = test_smb("127.0.0.1", server.port(), &ProbeOptions::default()).await.unwrap();
+        assert_eq!(result.dialect, "3.1.1");
+        assert!(result.signing_enabled);
+        assert!(result.signing_required);
+        assert_eq!(result.signing_algorithm, "AES-GMAC");
+        assert!(result.encryption_supported);
+        assert_eq!(result.cipher.as_deref(), Some("AES-256-GCM"));
+        assert!(result.timings.ttfb_ms.is_some());
+        assert_eq!(result.timings.latency_ms, result.timings.connect_ms);
+        assert!(result.timings.total_ms >= result.timings.connect_ms + result.timings.ttfb_ms.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_smb21_without_encryption() {
+        let server = start_smb_server(Stub::Answer(0x0210, SIGNING_ENABLED, 0, 0, 0)).await;
+
+        let result = test_smb("127.0.0.1", server.port(), &ProbeOptions::default()).await.unwrap();
+        assert_eq!(result.dialect, "2.1");
+        assert!(!result.signing_required);
+        assert_eq!(result.signing_algorithm, "HMAC-SHA256");
+        assert!(!result.encryption_supported);
+        assert_eq!(result.cipher, None);
+    }
+
+    #[tokio::test]
+    async fn test_smb1_only_server() {
+        let server = start_smb_server(Stub::Smb1Only).await;
+
+        let error = test_smb("127.0.0.1", server.port(), &ProbeOptions::default()).await.unwrap_err();
+        assert_eq!(error, ProbeError::Smb1Only);
+    }
+
+    #[tokio::test]
+    async fn test_silent_server_times_out() {
+        let server = start_smb_server(Stub::Silent).await;
+        let options = ProbeOptions::default().with_overrides(Some(300), None, None);
+
+        let error = test_smb("127.0.0.1", server.port(), &options).await.unwrap_err();
+        assert_eq!(error, ProbeError::SmbTimeout);
+    }
+
+    #[test]
+    fn test_error_status_is_reported() {
+        let mut response = negotiate_response(0x0311, SIGNING_ENABLED, 0, 0, 0);
+        response[8..12].copy_from_slice(&0xC000_00BBu32.to_le_bytes()); // STATUS_NOT_SUPPORTED
+
+        let error = parse_negotiate_response(&response).unwrap_err();
+        assert_eq!(error.code(), "SMB_ERROR");
+        assert!(error.to_string().contains("0xC00000BB"));
+    }
+}
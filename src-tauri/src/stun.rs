@@ -0,0 +1,369 @@
+use serde::Serialize;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::net::UdpSocket;
+
+use crate::errors::ProbeError;
+use crate::latency::{self, ProbeOptions, ProbeResult};
+
+/// Default STUN/TURN port used by RDP Shortpath
+pub const STUN_PORT: u16 = 3478;
+
+const MAGIC_COOKIE: u32 = 0x2112_A442;
+const BINDING_REQUEST: u16 = 0x0001;
+const BINDING_SUCCESS: u16 = 0x0101;
+const BINDING_ERROR: u16 = 0x0111;
+const ATTR_MAPPED_ADDRESS: u16 = 0x0001;
+const ATTR_XOR_MAPPED_ADDRESS: u16 = 0x0020;
+const ATTR_OTHER_ADDRESS: u16 = 0x802C;
+const HEADER_LEN: usize = 20;
+/// Initial retransmission timeout, doubled for each retransmit (RFC 5389)
+const INITIAL_RTO: Duration = Duration::from_millis(500);
+
+/// How the NAT in front of this machine maps UDP traffic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NatBehavior {
+    /// The reflexive address is the local address - no NAT
+    None,
+    /// The same public mapping is used for different destinations (full cone / restricted cone)
+    EndpointIndependent,
+    /// Each destination gets a different public mapping (symmetric NAT) - direct Shortpath needs a TURN relay
+    AddressDependent,
+    /// Only one destination could be queried, so the mapping behaviour is unknown
+    Unknown,
+}
+
+/// Result of a STUN Binding probe
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StunProbeResult {
+    /// Timing breakdown; `connectMs` and `latencyMs` are the Binding request round trip
+    pub timings: ProbeResult,
+    /// Local address of the UDP socket
+    pub local_address: String,
+    /// Public address the server saw the request come from
+    pub reflexive_address: String,
+    pub nat_behavior: NatBehavior,
+    /// UDP reaches the server and the NAT keeps the same mapping across destinations
+    pub shortpath_viable: bool,
+}
+
+/// Decoded Binding Success response
+#[derive(Debug, Clone, PartialEq)]
+struct BindingResponse {
+    mapped: SocketAddr,
+    /// Alternate server address advertised by the server (RFC 5780 OTHER-ADDRESS)
+    other: Option<SocketAddr>,
+}
+
+/// Send a STUN Binding Request, measure the round trip and classify the NAT behaviour
+/// The mapping behaviour is determined by querying a second server address from the same local port:
+/// the OTHER-ADDRESS advertised by the server, or another resolved address of the host.
+pub async fn test_stun(host: &str, port: u16, options: &ProbeOptions) -> Result<StunProbeResult, ProbeError> {
+    let (addrs, dns) = latency::resolve_addresses(host, port, options.timeout).await?;
+    let server = addrs[0];
+
+    let bind_addr: SocketAddr = if server.is_ipv4() {
+        (Ipv4Addr::UNSPECIFIED, 0).into()
+    } else {
+        (Ipv6Addr::UNSPECIFIED, 0).into()
+    };
+    let socket = UdpSocket::bind(bind_addr).await.map_err(ProbeError::from_io)?;
+    socket.connect(server).await.map_err(ProbeError::from_io)?;
+    let local = socket.local_addr().map_err(ProbeError::from_io)?;
+
+    let (response, rtt) = binding_request(&socket, server, options.timeout).await?;
+
+    let second = response
+        .other
+        .or_else(|| addrs.iter().copied().find(|a| *a != server && a.is_ipv4() == server.is_ipv4()));
+    let nat_behavior = if response.mapped == local {
+        NatBehavior::None
+    } else if let Some(second) = second {
+        // Reuse the local port so the NAT mapping can be compared
+        drop(socket);
+        match UdpSocket::bind(local).await {
+            Ok(socket) => match socket.connect(second).await {
+                Ok(()) => match binding_request(&socket, second, options.timeout).await {
+                    Ok((other, _)) if other.mapped == response.mapped => NatBehavior::EndpointIndependent,
+                    Ok(_) => NatBehavior::AddressDependent,
+                    Err(_) => NatBehavior::Unknown,
+                },
+                Err(_) => NatBehavior::Unknown,
+            },
+            Err(_) => NatBehavior::Unknown,
+        }
+    } else {
+        NatBehavior::Unknown
+    };
+
+    Ok(StunProbeResult {
+        timings: ProbeResult::from_round_trip(server, dns, rtt),
+        local_address: local.to_string(),
+        reflexive_address: response.mapped.to_string(),
+        nat_behavior,
+        shortpath_viable: nat_behavior != NatBehavior::AddressDependent,
+    })
+}
+
+/// Send a Binding Request on a connected socket, retransmitting until the timeout
+async fn binding_request(
+    socket: &UdpSocket,
+    server: SocketAddr,
+    limit: Duration,
+) -> Result<(BindingResponse, Duration), ProbeError> {
+    let transaction_id = new_transaction_id();
+    let request = encode_binding_request(&transaction_id);
+    let deadline = Instant::now() + limit;
+    let mut rto = INITIAL_RTO;
+    let mut buf = [0u8; 1024];
+
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(ProbeError::StunTimeout);
+        }
+        socket.send(&request).await.map_err(ProbeError::from_io)?;
+        let sent = Instant::now();
+        let wait = rto.min(deadline - now);
+
+        // Ignore stray datagrams that don't belong to this transaction
+        let received = tokio::time::timeout(wait, async {
+            loop {
+                let len = socket.recv(&mut buf).await?;
+                if let Some(response) = decode_response(&buf[..len], &transaction_id) {
+                    return Ok::<_, std::io::Error>(response);
+                }
+            }
+        })
+        .await;
+
+        match received {
+            Ok(Ok(Ok(response))) => return Ok((response, sent.elapsed())),
+            Ok(Ok(Err(e))) => return Err(ProbeError::StunError(format!("{} from {}", e, server))),
+            Ok(Err(e)) => return Err(ProbeError::from_io(e)),
+            Err(_) => rto *= 2,
+        }
+    }
+}
+
+fn new_transaction_id() -> [u8; 12] {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default();
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    let mut id = [0u8; 12];
+    id[..8].copy_from_slice(&(nanos ^ count.rotate_left(32)).to_be_bytes());
+    id[8..].copy_from_slice(&std::process::id().to_be_bytes());
+    id
+}
+
+fn encode_binding_request(transaction_id: &[u8; 12]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(HEADER_LEN);
+    message.extend_from_slice(&BINDING_REQUEST.to_be_bytes());
+    message.extend_from_slice(&0u16.to_be_bytes());
+    message.extend_from_slice(&MAGIC_COOKIE.to_be_bytes());
+    message.extend_from_slice(transaction_id);
+    message
+}
+
+/// Decode a response for the given transaction
+/// Returns None for messages of other transactions, Some(Err) for error responses.
+fn decode_response(message: &[u8], transaction_id: &[u8; 12]) -> Option<Result<BindingResponse, String>> {
+    if message.len() < HEADER_LEN || message[8..20] != transaction_id[..] {
+        return None;
+    }
+    let message_type = u16::from_be_bytes([message[0], message[1]]);
+    let length = u16::from_be_bytes([message[2], message[3]]) as usize;
+    let attributes = message.get(HEADER_LEN..HEADER_LEN + length)?;
+
+    match message_type {
+        BINDING_SUCCESS => {}
+        BINDING_ERROR => return Some(Err("Binding error response".to_string())),
+        _ => return None,
+    }
+
+    let mut mapped = None;
+    let mut xor_mapped = None;
+    let mut other = None;
+    let mut offset = 0;
+    while offset + 4 <= attributes.len() {
+        let attr_type = u16::from_be_bytes([attributes[offset], attributes[offset + 1]]);
+        let attr_len = u16::from_be_bytes([attributes[offset + 2], attributes[offset + 3]]) as usize;
+        let Some(value) = attributes.get(offset + 4..offset + 4 + attr_len) else {
+            break;
+        };
+        match attr_type {
+            ATTR_MAPPED_ADDRESS => mapped = decode_address(value, None),
+            ATTR_XOR_MAPPED_ADDRESS => xor_mapped = decode_address(value, Some(transaction_id)),
+            ATTR_OTHER_ADDRESS => other = decode_address(value, None),
+            _ => {}
+        }
+        // Attributes are padded to a multiple of 4 bytes
+        offset += 4 + attr_len.div_ceil(4) * 4;
+    }
+
+    match xor_mapped.or(mapped) {
+        Some(mapped) => Some(Ok(BindingResponse { mapped, other })),
+        None => Some(Err("Binding response without a mapped address".to_string())),
+    }
+}
+
+/// Decode a (XOR-)MAPPED-ADDRESS style attribute value
+fn decode_address(value: &[u8], xor: Option<&[u8; 12]>) -> Option<SocketAddr> {
+    if value.len() < 8 {
+        return None;
+    }
+    let cookie = MAGIC_COOKIE.to_be_bytes();
+    let mut port = u16::from_be_bytes([value[2], value[3]]);
+    if xor.is_some() {
+        port ^= (MAGIC_COOKIE >> 16) as u16;
+    }
+
+    let ip = match value[1] {
+        0x01 => {
+            let mut octets: [u8; 4] = value[4..8].try_into().ok()?;
+            if xor.is_some() {
+                octets.iter_mut().zip(cookie).for_each(|(b, c)| *b ^= c);
+            }
+            IpAddr::V4(Ipv4Addr::from(octets))
+        }
+        0x02 => {
+            let mut octets: [u8; 16] = value.get(4..20)?.try_into().ok()?;
+            if let Some(transaction_id) = xor {
+                let key = cookie.iter().chain(transaction_id.iter());
+                octets.iter_mut().zip(key).for_each(|(b, k)| *b ^= k);
+            }
+            IpAddr::V6(Ipv6Addr::from(octets))
+        }
+        _ => return None,
+    };
+
+    Some(SocketAddr::new(ip, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encode a (XOR-)MAPPED-ADDRESS style attribute
+    fn address_attribute(attr_type: u16, addr: SocketAddr, transaction_id: Option<&[u8; 12]>) -> Vec<u8> {
+        let cookie = MAGIC_COOKIE.to_be_bytes();
+        let mut port = addr.port();
+        let (family, mut octets) = match addr.ip() {
+            IpAddr::V4(ip) => (0x01u8, ip.octets().to_vec()),
+            IpAddr::V6(ip) => (0x02u8, ip.octets().to_vec()),
+        };
+        if let Some(transaction_id) = transaction_id {
+            port ^= (MAGIC_COOKIE >> 16) as u16;
+            let key: Vec<u8> = cookie.iter().chain(transaction_id.iter()).copied().collect();
+            octets.iter_mut().zip(key).for_each(|(b, k)| *b ^= k);
+        }
+
+        let mut attr = Vec::new();
+        attr.extend_from_slice(&attr_type.to_be_bytes());
+        attr.extend_from_slice(&((4 + octets.len()) as u16).to_be_bytes());
+        attr.extend_from_slice(&[0, family]);
+        attr.extend_from_slice(&port.to_be_bytes());
+        attr.extend_from_slice(&octets);
+        attr
+    }
+
+    /// Build a Binding Success response for a request
+    fn binding_success(request: &[u8], mapped: SocketAddr, other: Option<SocketAddr>) -> Vec<u8> {
+        let transaction_id: [u8; 12] = request[8..20].try_into().unwrap();
+        let mut attributes = address_attribute(ATTR_XOR_MAPPED_ADDRESS, mapped, Some(&transaction_id));
+        if let Some(other) = other {
+            attributes.extend(address_attribute(ATTR_OTHER_ADDRESS, other, None));
+        }
+
+        let mut response = Vec::new();
+        response.extend_from_slice(&BINDING_SUCCESS.to_be_bytes());
+        response.extend_from_slice(&(attributes.len() as u16).to_be_bytes());
+        response.extend_from_slice(&request[4..20]);
+        response.extend(attributes);
+        response
+    }
+
+    /// Start a local STUN responder and return its address
+    /// `mapped` overrides the reflected address to simulate a NAT.
+    async fn start_stun_server(mapped: Option<SocketAddr>, other: Option<SocketAddr>) -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 1024];
+            while let Ok((len, from)) = socket.recv_from(&mut buf).await {
+                let response = binding_success(&buf[..len], mapped.unwrap_or(from), other);
+                let _ = socket.send_to(&response, from).await;
+            }
+        });
+        addr
+    }
+
+    #[test]
+    fn test_decode_xor_mapped_address() {
+        let transaction_id = new_transaction_id();
+        let request = encode_binding_request(&transaction_id);
+        assert_eq!(request.len(), HEADER_LEN);
+
+        for mapped in ["203.0.113.5:50000", "[2001:db8::1]:3478"] {
+            let mapped: SocketAddr = mapped.parse().unwrap();
+            let response = binding_success(&request, mapped, None);
+            let decoded = decode_response(&response, &transaction_id).unwrap().unwrap();
+            assert_eq!(decoded.mapped, mapped);
+        }
+
+        // Responses for other transactions are ignored
+        let response = binding_success(&request, "203.0.113.5:50000".parse().unwrap(), None);
+        assert!(decode_response(&response, &new_transaction_id()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stun_without_nat() {
+        let server = start_stun_server(None, None).await;
+
+        let result = test_stun("127.0.0.1", server.port(), &ProbeOptions::default()).await.unwrap();
+        assert_eq!(result.reflexive_address, result.local_address);
+        assert_eq!(result.nat_behavior, NatBehavior::None);
+        assert!(result.shortpath_viable);
+        assert_eq!(result.timings.latency_ms, result.timings.connect_ms);
+    }
+
+    #[tokio::test]
+    async fn test_stun_detects_symmetric_nat() {
+        let second = start_stun_server(Some("203.0.113.5:2000".parse().unwrap()), None).await;
+        let first = start_stun_server(Some("203.0.113.5:1000".parse().unwrap()), Some(second)).await;
+
+        let result = test_stun("127.0.0.1", first.port(), &ProbeOptions::default()).await.unwrap();
+        assert_eq!(result.reflexive_address, "203.0.113.5:1000");
+        assert_eq!(result.nat_behavior, NatBehavior::AddressDependent);
+        assert!(!result.shortpath_viable);
+    }
+
+    #[tokio::test]
+    async fn test_stun_detects_endpoint_independent_nat() {
+        let mapped: SocketAddr = "203.0.113.5:1000".parse().unwrap();
+        let second = start_stun_server(Some(mapped), None).await;
+        let first = start_stun_server(Some(mapped), Some(second)).await;
+
+        let result = test_stun("127.0.0.1", first.port(), &ProbeOptions::default()).await.unwrap();
+        assert_eq!(result.nat_behavior, NatBehavior::EndpointIndependent);
+        assert!(result.shortpath_viable);
+    }
+
+    #[tokio::test]
+    async fn test_stun_timeout_without_server() {
+        // A bound socket that never answers
+        let silent = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = silent.local_addr().unwrap().port();
+        let options = ProbeOptions::default().with_overrides(Some(300), None, None);
+
+        let result = test_stun("127.0.0.1", port, &options).await;
+        assert_eq!(result.unwrap_err(), ProbeError::StunTimeout);
+    }
+}
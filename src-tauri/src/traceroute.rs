@@ -0,0 +1,548 @@
+use hickory_resolver::TokioAsyncResolver;
+use serde::Serialize;
+use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+use std::io::ErrorKind;
+use std::mem::MaybeUninit;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::TcpSocket;
+use tokio::time::timeout;
+
+use crate::errors::ProbeError;
+use crate::latency::{self, as_ms, ProbeOptions};
+
+/// Default number of hops, same as tracert and traceroute
+pub const DEFAULT_MAX_HOPS: u8 = 30;
+/// Upper bound for the hop count (IP TTL is a byte, but nothing real is that far away)
+pub const MAX_HOPS_LIMIT: u8 = 64;
+
+const ICMP_V4_DEST_UNREACHABLE: u8 = 3;
+const ICMP_V4_TIME_EXCEEDED: u8 = 11;
+const ICMP_V6_DEST_UNREACHABLE: u8 = 1;
+const ICMP_V6_TIME_EXCEEDED: u8 = 3;
+const IPPROTO_TCP: u8 = 6;
+/// How often the ICMP listener checks whether all probes finished
+const LISTENER_POLL: Duration = Duration::from_millis(100);
+
+/// How the hops were discovered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TracerouteMethod {
+    /// TCP SYN to the endpoint's port with increasing TTL, so the trace follows the same firewall rules as the real traffic
+    Tcp,
+    /// ICMP echo with increasing TTL, used when ICMP responses can't be read for TCP probes
+    Icmp,
+}
+
+/// One hop of the path
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TracerouteHop {
+    pub ttl: u8,
+    /// Address of the router that answered, None if the hop did not answer
+    pub address: Option<String>,
+    /// Reverse DNS name of the address
+    pub hostname: Option<String>,
+    pub rtt_ms: Option<f64>,
+    /// The answer came from the destination itself
+    pub reached: bool,
+}
+
+/// Result of a traceroute
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TracerouteReport {
+    pub host: String,
+    /// Resolved destination address
+    pub address: String,
+    pub port: u16,
+    pub method: TracerouteMethod,
+    pub max_hops: u8,
+    pub hops: Vec<TracerouteHop>,
+    pub reached: bool,
+    /// Explains incomplete results, e.g. missing privileges to see intermediate hops
+    pub note: Option<String>,
+    /// RFC 3339 timestamp of the run
+    pub timestamp: String,
+}
+
+/// Outcome of one TCP SYN probe
+struct TcpProbe {
+    ttl: u8,
+    local_port: u16,
+    sent: Instant,
+    /// Round trip if the destination answered with SYN-ACK or RST
+    reached: Option<Duration>,
+}
+
+/// ICMP error received for one of the TCP probes
+struct IcmpReply {
+    local_port: u16,
+    from: IpAddr,
+    received: Instant,
+}
+
+/// Trace the network path to an endpoint
+/// All hops are probed in parallel with the per-attempt timeout from `options`, so a trace takes
+/// about one timeout regardless of the hop count. Hops after the destination are dropped.
+pub async fn traceroute(
+    host: &str,
+    port: u16,
+    max_hops: u8,
+    resolve_names: bool,
+    options: &ProbeOptions,
+) -> Result<TracerouteReport, ProbeError> {
+    let max_hops = max_hops.clamp(1, MAX_HOPS_LIMIT);
+    let (addrs, _) = latency::resolve_addresses(host, port, options.timeout).await?;
+    let target = addrs[0];
+
+    let mut note = None;
+    let (method, mut hops) = match IcmpListener::open(target.ip()) {
+        Some(listener) => (TracerouteMethod::Tcp, tcp_trace(target, max_hops, options.timeout, Some(listener)).await?),
+        None => match icmp_trace(target.ip(), max_hops, options.timeout).await? {
+            Some(hops) => (TracerouteMethod::Icmp, hops),
+            None => {
+                note = Some(
+                    "Intermediate hops need administrator rights to read ICMP responses; only the destination is shown"
+                        .to_string(),
+                );
+                (TracerouteMethod::Tcp, tcp_trace(target, max_hops, options.timeout, None).await?)
+            }
+        },
+    };
+
+    if let Some(last) = hops.iter().position(|hop| hop.reached) {
+        hops.truncate(last + 1);
+    }
+    if resolve_names {
+        resolve_hostnames(&mut hops, options.timeout).await;
+    }
+
+    Ok(TracerouteReport {
+        host: host.to_string(),
+        address: target.ip().to_string(),
+        port,
+        method,
+        max_hops,
+        reached: hops.iter().any(|hop| hop.reached),
+        hops,
+        note,
+        timestamp: chrono::Local::now().to_rfc3339(),
+    })
+}
+
+/// Send TCP SYNs with TTL 1..=max_hops and match the ICMP Time Exceeded answers by source port
+async fn tcp_trace(
+    target: SocketAddr,
+    max_hops: u8,
+    limit: Duration,
+    listener: Option<IcmpListener>,
+) -> Result<Vec<TracerouteHop>, ProbeError> {
+    let done = Arc::new(AtomicBool::new(false));
+    let deadline = Instant::now() + limit + LISTENER_POLL;
+    let collector = listener.map(|listener| {
+        let done = done.clone();
+        tokio::task::spawn_blocking(move || listener.collect(target, &done, deadline))
+    });
+
+    let handles: Vec<_> = (1..=max_hops).map(|ttl| tokio::spawn(tcp_probe(target, ttl, limit))).collect();
+    let mut probes = Vec::with_capacity(handles.len());
+    for handle in handles {
+        probes.push(handle.await.map_err(|e| ProbeError::Other(e.to_string()))??);
+    }
+
+    done.store(true, Ordering::Relaxed);
+    let replies = match collector {
+        Some(collector) => collector.await.unwrap_or_default(),
+        None => Vec::new(),
+    };
+
+    Ok(probes
+        .into_iter()
+        .map(|probe| {
+            if let Some(rtt) = probe.reached {
+                return TracerouteHop {
+                    ttl: probe.ttl,
+                    address: Some(target.ip().to_string()),
+                    hostname: None,
+                    rtt_ms: Some(as_ms(rtt)),
+                    reached: true,
+                };
+            }
+
+            match replies.iter().find(|reply| reply.local_port == probe.local_port) {
+                Some(reply) => TracerouteHop {
+                    ttl: probe.ttl,
+                    address: Some(reply.from.to_string()),
+                    hostname: None,
+                    rtt_ms: Some(as_ms(reply.received.saturating_duration_since(probe.sent))),
+                    reached: reply.from == target.ip(),
+                },
+                None => silent_hop(probe.ttl),
+            }
+        })
+        .collect())
+}
+
+/// Connect with a limited TTL; SYN-ACK or RST means the destination was reached
+async fn tcp_probe(target: SocketAddr, ttl: u8, limit: Duration) -> Result<TcpProbe, ProbeError> {
+    let socket = Socket::new(Domain::for_address(target), Type::STREAM, Some(Protocol::TCP)).map_err(ProbeError::from_io)?;
+    if target.is_ipv4() {
+        socket.set_ttl_v4(ttl.into())
+    } else {
+        socket.set_unicast_hops_v6(ttl.into())
+    }
+    .map_err(ProbeError::from_io)?;
+
+    // Bind first so the local port is known before the SYN goes out
+    socket.bind(&SockAddr::from(unspecified(target.ip()))).map_err(ProbeError::from_io)?;
+    let local_port = socket
+        .local_addr()
+        .map_err(ProbeError::from_io)?
+        .as_socket()
+        .map(|addr| addr.port())
+        .unwrap_or_default();
+    socket.set_nonblocking(true).map_err(ProbeError::from_io)?;
+    let socket = TcpSocket::from_std_stream(socket.into());
+
+    let sent = Instant::now();
+    let reached = match timeout(limit, socket.connect(target)).await {
+        Ok(Ok(_)) => Some(sent.elapsed()),
+        Ok(Err(e)) if e.kind() == ErrorKind::ConnectionRefused => Some(sent.elapsed()),
+        _ => None,
+    };
+
+    Ok(TcpProbe {
+        ttl,
+        local_port,
+        sent,
+        reached,
+    })
+}
+
+fn silent_hop(ttl: u8) -> TracerouteHop {
+    TracerouteHop {
+        ttl,
+        address: None,
+        hostname: None,
+        rtt_ms: None,
+        reached: false,
+    }
+}
+
+fn unspecified(ip: IpAddr) -> SocketAddr {
+    match ip {
+        IpAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        IpAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+    }
+}
+
+/// Raw ICMP socket receiving the Time Exceeded / Destination Unreachable errors for the TCP probes
+/// Opening it needs administrator rights on Windows and root or CAP_NET_RAW elsewhere.
+struct IcmpListener {
+    socket: Socket,
+}
+
+impl IcmpListener {
+    fn open(target: IpAddr) -> Option<Self> {
+        let (domain, protocol) = match target {
+            IpAddr::V4(_) => (Domain::IPV4, Protocol::ICMPV4),
+            IpAddr::V6(_) => (Domain::IPV6, Protocol::ICMPV6),
+        };
+        let socket = Socket::new(domain, Type::RAW, Some(protocol)).ok()?;
+        // Windows only delivers packets to bound raw sockets
+        socket.bind(&SockAddr::from(unspecified(target))).ok()?;
+        socket.set_read_timeout(Some(LISTENER_POLL)).ok()?;
+        Some(Self { socket })
+    }
+
+    /// Receive ICMP errors until all probes finished or the deadline passed
+    fn collect(&self, target: SocketAddr, done: &AtomicBool, deadline: Instant) -> Vec<IcmpReply> {
+        let mut replies: Vec<IcmpReply> = Vec::new();
+        let mut buf = [MaybeUninit::<u8>::uninit(); 1500];
+
+        while !done.load(Ordering::Relaxed) && Instant::now() < deadline {
+            let (len, from) = match self.socket.recv_from(&mut buf) {
+                Ok(received) => received,
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut | ErrorKind::Interrupted) => {
+                    continue
+                }
+                Err(_) => break,
+            };
+            let received = Instant::now();
+            // SAFETY: recv_from initialised the first `len` bytes
+            let packet: Vec<u8> = buf[..len].iter().map(|b| unsafe { b.assume_init() }).collect();
+
+            let parsed = match target {
+                SocketAddr::V4(_) => parse_icmp_v4(&packet, target),
+                SocketAddr::V6(_) => from
+                    .as_socket()
+                    .and_then(|from| parse_icmp_v6(&packet, target).map(|port| (from.ip(), port))),
+            };
+            if let Some((from, local_port)) = parsed {
+                // Keep the first answer per probe, the kernel retransmits unanswered SYNs
+                if !replies.iter().any(|reply| reply.local_port == local_port) {
+                    replies.push(IcmpReply {
+                        local_port,
+                        from,
+                        received,
+                    });
+                }
+            }
+        }
+
+        replies
+    }
+}
+
+/// Extract the reporting router and the local port of the quoted TCP SYN from an ICMPv4 error
+/// Raw IPv4 sockets deliver the packet including its IP header.
+fn parse_icmp_v4(packet: &[u8], target: SocketAddr) -> Option<(IpAddr, u16)> {
+    let header_len = usize::from(packet.first()? & 0x0f) * 4;
+    let source: [u8; 4] = packet.get(12..16)?.try_into().ok()?;
+    let icmp = packet.get(header_len..)?;
+    if !matches!(*icmp.first()?, ICMP_V4_TIME_EXCEEDED | ICMP_V4_DEST_UNREACHABLE) {
+        return None;
+    }
+
+    // The error quotes the original IP header and the first 8 bytes of the TCP header
+    let quoted = icmp.get(8..)?;
+    let quoted_len = usize::from(quoted.first()? & 0x0f) * 4;
+    let destination: [u8; 4] = quoted.get(16..20)?.try_into().ok()?;
+    if *quoted.get(9)? != IPPROTO_TCP || IpAddr::from(destination) != target.ip() {
+        return None;
+    }
+
+    let local_port = quoted_tcp_port(quoted.get(quoted_len..)?, target.port())?;
+    Some((IpAddr::from(source), local_port))
+}
+
+/// Extract the local port of the quoted TCP SYN from an ICMPv6 error
+/// Raw IPv6 sockets deliver the ICMPv6 message only, the router is the sender address.
+fn parse_icmp_v6(message: &[u8], target: SocketAddr) -> Option<u16> {
+    if !matches!(*message.first()?, ICMP_V6_TIME_EXCEEDED | ICMP_V6_DEST_UNREACHABLE) {
+        return None;
+    }
+
+    let quoted = message.get(8..)?;
+    let destination: [u8; 16] = quoted.get(24..40)?.try_into().ok()?;
+    if *quoted.get(6)? != IPPROTO_TCP || IpAddr::from(destination) != target.ip() {
+        return None;
+    }
+
+    quoted_tcp_port(quoted.get(40..)?, target.port())
+}
+
+/// Source port of a quoted TCP header, if it was sent to the traced port
+fn quoted_tcp_port(tcp: &[u8], port: u16) -> Option<u16> {
+    let ports = tcp.get(..4)?;
+    if u16::from_be_bytes([ports[2], ports[3]]) != port {
+        return None;
+    }
+    Some(u16::from_be_bytes([ports[0], ports[1]]))
+}
+
+/// ICMP echo trace through the Windows ICMP helper API, which works without administrator rights
+/// Returns None where no unprivileged ICMP is available (IPv6, other platforms).
+#[cfg(target_os = "windows")]
+async fn icmp_trace(target: IpAddr, max_hops: u8, limit: Duration) -> Result<Option<Vec<TracerouteHop>>, ProbeError> {
+    let IpAddr::V4(target) = target else {
+        return Ok(None);
+    };
+
+    let handles: Vec<_> = (1..=max_hops)
+        .map(|ttl| tokio::task::spawn_blocking(move || (ttl, icmp::echo(target, ttl, limit))))
+        .collect();
+
+    let mut hops = Vec::with_capacity(handles.len());
+    for handle in handles {
+        let (ttl, reply) = handle.await.map_err(|e| ProbeError::Other(e.to_string()))?;
+        hops.push(match reply.map_err(ProbeError::from_io)? {
+            Some(reply) => TracerouteHop {
+                ttl,
+                address: Some(reply.from.to_string()),
+                hostname: None,
+                rtt_ms: Some(as_ms(reply.rtt)),
+                reached: reply.reached,
+            },
+            None => silent_hop(ttl),
+        });
+    }
+
+    Ok(Some(hops))
+}
+
+#[cfg(not(target_os = "windows"))]
+async fn icmp_trace(_target: IpAddr, _max_hops: u8, _limit: Duration) -> Result<Option<Vec<TracerouteHop>>, ProbeError> {
+    Ok(None)
+}
+
+#[cfg(target_os = "windows")]
+mod icmp {
+    use std::net::Ipv4Addr;
+    use std::time::{Duration, Instant};
+    use windows_sys::Win32::Foundation::INVALID_HANDLE_VALUE;
+    use windows_sys::Win32::NetworkManagement::IpHelper::{
+        IcmpCloseHandle, IcmpCreateFile, IcmpSendEcho, ICMP_ECHO_REPLY, IP_OPTION_INFORMATION,
+        IP_SUCCESS,
+    };
+
+    const PAYLOAD: [u8; 32] = [0x61; 32];
+
+    /// Answer to an echo request with a limited TTL
+    pub struct EchoReply {
+        pub from: Ipv4Addr,
+        pub rtt: Duration,
+        /// Echo reply (or an error) from the destination itself rather than TTL expiry on the way
+        pub reached: bool,
+    }
+
+    /// Send one echo request with the given TTL and wait for the answer (blocking)
+    pub fn echo(target: Ipv4Addr, ttl: u8, limit: Duration) -> std::io::Result<Option<EchoReply>> {
+        let handle = unsafe { IcmpCreateFile() };
+        if handle.is_null() || handle == INVALID_HANDLE_VALUE {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        let options = IP_OPTION_INFORMATION {
+            Ttl: ttl,
+            Tos: 0,
+            Flags: 0,
+            OptionsSize: 0,
+            OptionsData: std::ptr::null_mut(),
+        };
+        // Room for one reply, the echoed payload and the quoted data of an ICMP error; u64 keeps it aligned
+        let mut buffer = vec![0u64; (std::mem::size_of::<ICMP_ECHO_REPLY>() + PAYLOAD.len() + 64) / 8];
+        let buffer_size = (buffer.len() * 8) as u32;
+        let timeout_ms = limit.as_millis().min(u32::MAX as u128) as u32;
+
+        let start = Instant::now();
+        let count = unsafe {
+            IcmpSendEcho(
+                handle,
+                u32::from_ne_bytes(target.octets()),
+                PAYLOAD.as_ptr().cast(),
+                PAYLOAD.len() as u16,
+                &options,
+                buffer.as_mut_ptr().cast(),
+                buffer_size,
+                timeout_ms,
+            )
+        };
+        let rtt = start.elapsed();
+        unsafe {
+            IcmpCloseHandle(handle);
+        }
+
+        // No reply within the timeout (IP_REQ_TIMED_OUT) or the hop dropped the request
+        if count == 0 {
+            return Ok(None);
+        }
+
+        let reply = unsafe { &*(buffer.as_ptr() as *const ICMP_ECHO_REPLY) };
+        let from = Ipv4Addr::from(reply.Address.to_ne_bytes());
+        Ok(Some(EchoReply {
+            from,
+            rtt,
+            reached: reply.Status == IP_SUCCESS || from == target,
+        }))
+    }
+}
+
+/// Fill in reverse DNS names, looked up in parallel
+/// Addresses without a PTR record or a lookup slower than `limit` keep no hostname.
+async fn resolve_hostnames(hops: &mut [TracerouteHop], limit: Duration) {
+    let resolver = match TokioAsyncResolver::tokio_from_system_conf() {
+        Ok(resolver) => Arc::new(resolver),
+        Err(_) => return,
+    };
+
+    let handles: Vec<_> = hops
+        .iter()
+        .map(|hop| {
+            let resolver = resolver.clone();
+            let address = hop.address.as_ref().and_then(|a| a.parse::<IpAddr>().ok());
+            tokio::spawn(async move {
+                let address = address?;
+                let lookup = timeout(limit, resolver.reverse_lookup(address)).await.ok()?.ok()?;
+                let name = lookup.iter().next()?.to_string();
+                Some(name.trim_end_matches('.').to_string())
+            })
+        })
+        .collect();
+
+    for (hop, handle) in hops.iter_mut().zip(handles) {
+        hop.hostname = handle.await.ok().flatten();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    /// ICMPv4 Time Exceeded from 10.0.0.1 quoting a SYN from port 50000 to 192.0.2.10:443
+    fn time_exceeded_v4(destination: [u8; 4], destination_port: u16) -> Vec<u8> {
+        let mut packet = vec![0x45, 0, 0, 56, 0, 0, 0, 0, 64, 1, 0, 0, 10, 0, 0, 1, 10, 0, 0, 2];
+        packet.extend_from_slice(&[ICMP_V4_TIME_EXCEEDED, 0, 0, 0, 0, 0, 0, 0]);
+        packet.extend_from_slice(&[0x45, 0, 0, 60, 0, 0, 0, 0, 1, IPPROTO_TCP, 0, 0, 10, 0, 0, 2]);
+        packet.extend_from_slice(&destination);
+        packet.extend_from_slice(&50000u16.to_be_bytes());
+        packet.extend_from_slice(&destination_port.to_be_bytes());
+        packet.extend_from_slice(&[0, 0, 0, 1]);
+        packet
+    }
+
+    #[test]
+    fn test_parse_icmp_v4_time_exceeded() {
+        let target: SocketAddr = "192.0.2.10:443".parse().unwrap();
+
+        let parsed = parse_icmp_v4(&time_exceeded_v4([192, 0, 2, 10], 443), target);
+        assert_eq!(parsed, Some(("10.0.0.1".parse().unwrap(), 50000)));
+
+        // Errors for other destinations or ports belong to other traffic
+        assert_eq!(parse_icmp_v4(&time_exceeded_v4([192, 0, 2, 11], 443), target), None);
+        assert_eq!(parse_icmp_v4(&time_exceeded_v4([192, 0, 2, 10], 80), target), None);
+        assert_eq!(parse_icmp_v4(&time_exceeded_v4([192, 0, 2, 10], 443)[..40], target), None);
+    }
+
+    #[test]
+    fn test_parse_icmp_v6_time_exceeded() {
+        let target: SocketAddr = "[2001:db8::10]:443".parse().unwrap();
+        let mut message = vec![ICMP_V6_TIME_EXCEEDED, 0, 0, 0, 0, 0, 0, 0];
+        message.extend_from_slice(&[0x60, 0, 0, 0, 0, 20, IPPROTO_TCP, 1]);
+        message.extend_from_slice(&"2001:db8::2".parse::<Ipv6Addr>().unwrap().octets());
+        message.extend_from_slice(&"2001:db8::10".parse::<Ipv6Addr>().unwrap().octets());
+        message.extend_from_slice(&[0xC3, 0x50, 0x01, 0xBB, 0, 0, 0, 1]);
+
+        assert_eq!(parse_icmp_v6(&message, target), Some(50000));
+        message[0] = 128; // Echo request
+        assert_eq!(parse_icmp_v6(&message, target), None);
+    }
+
+    #[tokio::test]
+    async fn test_traceroute_reaches_open_port() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let report = traceroute("127.0.0.1", port, 5, false, &ProbeOptions::default()).await.unwrap();
+        assert!(report.reached);
+        assert_eq!(report.address, "127.0.0.1");
+        assert_eq!(report.hops.len(), 1);
+        assert_eq!(report.hops[0].ttl, 1);
+        assert_eq!(report.hops[0].address.as_deref(), Some("127.0.0.1"));
+        assert!(report.hops[0].rtt_ms.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_traceroute_counts_reset_as_reached() {
+        // Nothing listens on the port, so the destination answers with RST
+        let port = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap().port();
+
+        let report = traceroute("127.0.0.1", port, 3, false, &ProbeOptions::default()).await.unwrap();
+        assert!(report.reached);
+        assert_eq!(report.hops.len(), 1);
+    }
+}
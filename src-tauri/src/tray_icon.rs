@@ -11,6 +11,7 @@ pub enum IconStatus {
 }
 
 impl IconStatus {
+    /// Status for a latency, which is the configured statistic (e.g. median or p95) for multi-sample probes
     pub fn from_latency(latency: f64, thresholds: &LatencyThresholds) -> Self {
         if latency <= thresholds.excellent {
             Self::Excellent
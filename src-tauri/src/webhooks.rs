@@ -0,0 +1,537 @@
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::alerts::{format_duration, AlertEvent, AlertEventKind, IncidentSource, Problem};
+use crate::logger;
+use crate::settings::{load_settings, WebhookConfig, WebhookFormat};
+
+const APP_NAME: &str = "AVD Health Monitor";
+/// Time allowed for one delivery attempt
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Delay before the first retry, doubled for each further retry
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
+/// Upper bound for a retry delay, whether backed off or requested through Retry-After
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookEventKind {
+    Alert,
+    Recovered,
+    /// Sent by the "send test webhook" command
+    Test,
+}
+
+/// An alert, recovery or test event as delivered to webhooks
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookEvent {
+    pub kind: WebhookEventKind,
+    pub source: IncidentSource,
+    /// Endpoint ID or FSLogix path ID
+    pub id: String,
+    pub name: String,
+    pub problem: Option<Problem>,
+    pub consecutive_failures: u32,
+    /// Milliseconds since the first failed check; for recoveries, the whole outage
+    pub outage_ms: i64,
+    /// Unix timestamp in milliseconds
+    pub timestamp: i64,
+}
+
+impl WebhookEvent {
+    pub fn from_alert(source: IncidentSource, event: &AlertEvent, timestamp: i64) -> Self {
+        Self {
+            kind: match event.kind {
+                AlertEventKind::Alert => WebhookEventKind::Alert,
+                AlertEventKind::Recovered => WebhookEventKind::Recovered,
+            },
+            source,
+            id: event.id.clone(),
+            name: event.name.clone(),
+            problem: event.problem.clone(),
+            consecutive_failures: event.consecutive_failures,
+            outage_ms: event.outage_ms,
+            timestamp,
+        }
+    }
+
+    fn test(timestamp: i64) -> Self {
+        Self {
+            kind: WebhookEventKind::Test,
+            source: IncidentSource::Endpoints,
+            id: "test".to_string(),
+            name: "Test webhook".to_string(),
+            problem: None,
+            consecutive_failures: 0,
+            outage_ms: 0,
+            timestamp,
+        }
+    }
+
+    /// One-line description used by the Teams and Slack templates
+    fn summary(&self) -> String {
+        match (self.kind, &self.problem) {
+            (WebhookEventKind::Recovered, _) => format!("recovered after {}", format_duration(self.outage_ms)),
+            (WebhookEventKind::Test, _) => "test event, webhook delivery works".to_string(),
+            (_, Some(Problem::HighLatency { latency, critical })) => {
+                format!("{} latency {:.0}ms", if *critical { "critical" } else { "high" }, latency)
+            }
+            (_, Some(Problem::Unreachable { error })) if !error.is_empty() => format!("unreachable ({})", error),
+            _ => "unreachable".to_string(),
+        }
+    }
+}
+
+/// Outcome of delivering one payload to one webhook
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    pub name: String,
+    pub success: bool,
+    /// HTTP status of the last attempt, None when no response was received
+    pub status: Option<u16>,
+    pub attempts: u32,
+    pub error: Option<String>,
+}
+
+/// Sliding one-minute window of deliveries per webhook URL
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    sent: HashMap<String, VecDeque<Instant>>,
+}
+
+impl RateLimiter {
+    /// Record a delivery to `key` and return true, or false when the limit is already reached
+    pub fn try_acquire(&mut self, key: &str, max_per_minute: u32, now: Instant) -> bool {
+        if max_per_minute == 0 {
+            return true;
+        }
+
+        let sent = self.sent.entry(key.to_string()).or_default();
+        while sent.front().is_some_and(|&t| now.duration_since(t) >= Duration::from_secs(60)) {
+            sent.pop_front();
+        }
+        if sent.len() >= max_per_minute as usize {
+            return false;
+        }
+        sent.push_back(now);
+        true
+    }
+}
+
+static RATE_LIMITER: Lazy<Mutex<RateLimiter>> = Lazy::new(|| Mutex::new(RateLimiter::default()));
+
+/// Name of this machine, shown in every payload so the NOC knows which host reported
+pub fn host_name() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// Title shared by the Teams and Slack templates
+fn title(host: &str, events: &[WebhookEvent]) -> String {
+    if events.iter().all(|e| e.kind == WebhookEventKind::Test) {
+        format!("Test webhook from {}", host)
+    } else if events.iter().all(|e| e.kind == WebhookEventKind::Recovered) {
+        format!("Recovered on {}", host)
+    } else {
+        format!("Alert on {}", host)
+    }
+}
+
+/// Build the request body of a webhook in the given format
+pub fn render(format: WebhookFormat, host: &str, events: &[WebhookEvent]) -> Value {
+    match format {
+        WebhookFormat::Json => json!({
+            "app": APP_NAME,
+            "host": host,
+            "events": events,
+        }),
+        WebhookFormat::Teams => {
+            let alerting = events.iter().any(|e| e.kind == WebhookEventKind::Alert);
+            let facts: Vec<Value> = events
+                .iter()
+                .map(|e| json!({ "title": e.name, "value": e.summary() }))
+                .collect();
+            json!({
+                "type": "message",
+                "attachments": [{
+                    "contentType": "application/vnd.microsoft.card.adaptive",
+                    "contentUrl": null,
+                    "content": {
+                        "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+                        "type": "AdaptiveCard",
+                        "version": "1.4",
+                        "body": [
+                            {
+                                "type": "TextBlock",
+                                "size": "Medium",
+                                "weight": "Bolder",
+                                "color": if alerting { "Attention" } else { "Good" },
+                                "text": title(host, events),
+                            },
+                            { "type": "TextBlock", "text": APP_NAME, "isSubtle": true, "spacing": "None" },
+                            { "type": "FactSet", "facts": facts },
+                        ],
+                    },
+                }],
+            })
+        }
+        WebhookFormat::Slack => {
+            let title = title(host, events);
+            let lines: Vec<String> = events.iter().map(|e| format!("• *{}*: {}", e.name, e.summary())).collect();
+            json!({
+                "text": title,
+                "blocks": [
+                    { "type": "header", "text": { "type": "plain_text", "text": title } },
+                    { "type": "section", "text": { "type": "mrkdwn", "text": lines.join("\n") } },
+                    { "type": "context", "elements": [{ "type": "mrkdwn", "text": APP_NAME }] },
+                ],
+            })
+        }
+    }
+}
+
+/// POST `body` to a webhook, retrying connection errors, timeouts, 408, 429 and 5xx responses
+pub async fn deliver(client: &reqwest::Client, webhook: &WebhookConfig, body: &Value, base_delay: Duration) -> WebhookDelivery {
+    let mut delivery = WebhookDelivery {
+        name: webhook.name.clone(),
+        success: false,
+        status: None,
+        attempts: 0,
+        error: None,
+    };
+
+    loop {
+        delivery.attempts += 1;
+        let mut request = client.post(&webhook.url).json(body);
+        for (name, value) in &webhook.headers {
+            request = request.header(name, value);
+        }
+
+        let mut retry_after = None;
+        let retryable = match request.send().await {
+            Ok(response) => {
+                let status = response.status();
+                delivery.status = Some(status.as_u16());
+                if status.is_success() {
+                    delivery.success = true;
+                    delivery.error = None;
+                    return delivery;
+                }
+                retry_after = response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .map(|secs| Duration::from_secs(secs).min(MAX_RETRY_DELAY));
+                delivery.error = Some(format!("HTTP {}", status));
+                status.is_server_error() || status.as_u16() == 408 || status.as_u16() == 429
+            }
+            Err(e) => {
+                delivery.status = None;
+                // Webhook URLs often carry the token, so keep them out of the message
+                delivery.error = Some(e.without_url().to_string());
+                true
+            }
+        };
+
+        if !retryable || delivery.attempts > webhook.max_retries {
+            return delivery;
+        }
+        let backoff = base_delay
+            .saturating_mul(2u32.saturating_pow(delivery.attempts - 1))
+            .min(MAX_RETRY_DELAY);
+        tokio::time::sleep(retry_after.unwrap_or(backoff)).await;
+    }
+}
+
+fn client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+}
+
+/// Deliver `events` to every webhook concurrently
+/// With `rate_limited`, webhooks over their per-minute limit are skipped.
+async fn send_all(webhooks: Vec<WebhookConfig>, events: Vec<WebhookEvent>, rate_limited: bool) -> Vec<WebhookDelivery> {
+    let host = host_name();
+    let client = client();
+
+    let deliveries = webhooks.into_iter().map(|webhook| {
+        let body = render(webhook.format, &host, &events);
+        let client = client.clone();
+        async move {
+            if rate_limited && !RATE_LIMITER.lock().try_acquire(&webhook.url, webhook.max_per_minute, Instant::now()) {
+                logger::warn("Webhooks", &format!("Rate limit reached for webhook {}, event dropped", webhook.name));
+                return WebhookDelivery {
+                    name: webhook.name,
+                    success: false,
+                    status: None,
+                    attempts: 0,
+                    error: Some("Rate limit reached".to_string()),
+                };
+            }
+
+            let delivery = deliver(&client, &webhook, &body, RETRY_BASE_DELAY).await;
+            if !delivery.success {
+                logger::error(
+                    "Webhooks",
+                    &format!(
+                        "Webhook {} failed after {} attempt(s): {}",
+                        delivery.name,
+                        delivery.attempts,
+                        delivery.error.as_deref().unwrap_or("unknown error")
+                    ),
+                );
+            }
+            delivery
+        }
+    });
+    join_deliveries(deliveries).await
+}
+
+/// Await all deliveries concurrently, keeping their order
+async fn join_deliveries<F>(futures: impl Iterator<Item = F>) -> Vec<WebhookDelivery>
+where
+    F: std::future::Future<Output = WebhookDelivery> + Send + 'static,
+{
+    let handles: Vec<_> = futures.map(tokio::spawn).collect();
+    let mut deliveries = Vec::with_capacity(handles.len());
+    for handle in handles {
+        if let Ok(delivery) = handle.await {
+            deliveries.push(delivery);
+        }
+    }
+    deliveries
+}
+
+/// Send a round of alert and recovery events to the enabled webhooks in the background
+pub fn dispatch(source: IncidentSource, events: &[AlertEvent]) {
+    if events.is_empty() {
+        return;
+    }
+    let webhooks: Vec<WebhookConfig> = match load_settings() {
+        Ok(settings) => settings.webhooks.into_iter().filter(|w| w.enabled).collect(),
+        Err(e) => {
+            logger::error("Webhooks", &format!("Failed to load webhooks: {}", e));
+            return;
+        }
+    };
+    if webhooks.is_empty() {
+        return;
+    }
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let events: Vec<WebhookEvent> = events.iter().map(|e| WebhookEvent::from_alert(source, e, now)).collect();
+    tokio::spawn(send_all(webhooks, events, true));
+}
+
+/// Send a test event to the enabled webhooks, or to the webhook with the given name even if disabled
+pub async fn send_test(name: Option<&str>) -> Result<Vec<WebhookDelivery>, String> {
+    let settings = load_settings().map_err(|e| e.to_string())?;
+    let webhooks: Vec<WebhookConfig> = settings
+        .webhooks
+        .into_iter()
+        .filter(|w| name.map_or(w.enabled, |name| w.name == name))
+        .collect();
+    if webhooks.is_empty() {
+        return Err(match name {
+            Some(name) => format!("No webhook named \"{}\" in settings.json", name),
+            None => "No enabled webhooks in settings.json".to_string(),
+        });
+    }
+
+    let now = chrono::Utc::now().timestamp_millis();
+    Ok(send_all(webhooks, vec![WebhookEvent::test(now)], false).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn webhook(url: String, max_retries: u32) -> WebhookConfig {
+        WebhookConfig {
+            name: "noc".to_string(),
+            url,
+            enabled: true,
+            format: WebhookFormat::Json,
+            headers: HashMap::from([("Authorization".to_string(), "Bearer secret".to_string())]),
+            max_retries,
+            max_per_minute: 10,
+        }
+    }
+
+    fn alert_event() -> WebhookEvent {
+        WebhookEvent {
+            kind: WebhookEventKind::Alert,
+            source: IncidentSource::Endpoints,
+            id: "gateway".to_string(),
+            name: "Gateway".to_string(),
+            problem: Some(Problem::HighLatency {
+                latency: 250.4,
+                critical: true,
+            }),
+            consecutive_failures: 3,
+            outage_ms: 120_000,
+            timestamp: 1_700_000_000_000,
+        }
+    }
+
+    /// Local HTTP server answering with `statuses` in turn (the last one repeats),
+    /// returning its port and the raw requests it received
+    async fn start_server(statuses: Vec<u16>) -> (u16, Arc<Mutex<Vec<String>>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let received = requests.clone();
+        tokio::spawn(async move {
+            let mut count = 0;
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                // Read until the whole body announced by Content-Length has arrived
+                while let Ok(n) = stream.read(&mut buf).await {
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|l| l.to_ascii_lowercase().strip_prefix("content-length:").map(|v| v.trim().to_string()))
+                            .and_then(|v| v.parse::<usize>().ok())
+                            .unwrap_or(0);
+                        if body.len() >= length {
+                            break;
+                        }
+                    }
+                }
+                received.lock().push(String::from_utf8_lossy(&request).into_owned());
+
+                let status = statuses[count.min(statuses.len() - 1)];
+                count += 1;
+                let response = format!("HTTP/1.1 {} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status);
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (port, requests)
+    }
+
+    #[tokio::test]
+    async fn test_deliver_posts_json_with_headers() {
+        let (port, requests) = start_server(vec![200]).await;
+        let webhook = webhook(format!("http://127.0.0.1:{}/hook", port), 3);
+        let body = render(WebhookFormat::Json, "SH-01", &[alert_event()]);
+
+        let delivery = deliver(&client(), &webhook, &body, Duration::from_millis(10)).await;
+        assert!(delivery.success);
+        assert_eq!(delivery.status, Some(200));
+        assert_eq!(delivery.attempts, 1);
+
+        let requests = requests.lock();
+        assert!(requests[0].starts_with("POST /hook"));
+        assert!(requests[0].to_ascii_lowercase().contains("authorization: bearer secret"));
+        let payload: Value = serde_json::from_str(requests[0].split_once("\r\n\r\n").unwrap().1).unwrap();
+        assert_eq!(payload["host"], "SH-01");
+        assert_eq!(payload["events"][0]["kind"], "alert");
+        assert_eq!(payload["events"][0]["problem"]["type"], "highLatency");
+        assert_eq!(payload["events"][0]["consecutiveFailures"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_deliver_retries_server_errors() {
+        let (port, requests) = start_server(vec![503, 500, 204]).await;
+        let webhook = webhook(format!("http://127.0.0.1:{}", port), 3);
+
+        let delivery = deliver(&client(), &webhook, &json!({}), Duration::from_millis(10)).await;
+        assert!(delivery.success);
+        assert_eq!(delivery.status, Some(204));
+        assert_eq!(delivery.attempts, 3);
+        assert_eq!(requests.lock().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_deliver_gives_up() {
+        // Out of retries
+        let (port, _) = start_server(vec![502]).await;
+        let delivery = deliver(&client(), &webhook(format!("http://127.0.0.1:{}", port), 2), &json!({}), Duration::from_millis(10)).await;
+        assert!(!delivery.success);
+        assert_eq!(delivery.attempts, 3);
+        assert_eq!(delivery.error.as_deref(), Some("HTTP 502 Bad Gateway"));
+
+        // Client errors are not retried
+        let (port, requests) = start_server(vec![404]).await;
+        let delivery = deliver(&client(), &webhook(format!("http://127.0.0.1:{}", port), 2), &json!({}), Duration::from_millis(10)).await;
+        assert!(!delivery.success);
+        assert_eq!(delivery.attempts, 1);
+        assert_eq!(requests.lock().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_deliver_error_hides_url() {
+        // Nothing listens on a port that was just released
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+
+        let url = format!("http://127.0.0.1:{}/webhookb2/secret-token", port);
+        let delivery = deliver(&client(), &webhook(url, 0), &json!({}), Duration::from_millis(10)).await;
+        assert!(!delivery.success);
+        let error = delivery.error.unwrap();
+        assert!(!error.contains("secret-token"), "{}", error);
+        assert!(!error.contains("127.0.0.1"), "{}", error);
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let mut limiter = RateLimiter::default();
+        let start = Instant::now();
+
+        assert!(limiter.try_acquire("a", 2, start));
+        assert!(limiter.try_acquire("a", 2, start + Duration::from_secs(10)));
+        assert!(!limiter.try_acquire("a", 2, start + Duration::from_secs(20)));
+        // Other webhooks have their own window
+        assert!(limiter.try_acquire("b", 2, start + Duration::from_secs(20)));
+        // The first delivery left the window
+        assert!(limiter.try_acquire("a", 2, start + Duration::from_secs(60)));
+        // 0 = unlimited
+        assert!((0..100).all(|_| limiter.try_acquire("c", 0, start)));
+    }
+
+    #[test]
+    fn test_render_templates() {
+        let mut recovered = alert_event();
+        recovered.kind = WebhookEventKind::Recovered;
+        recovered.problem = None;
+        recovered.outage_ms = 270_000;
+
+        let teams = render(WebhookFormat::Teams, "SH-01", &[alert_event(), recovered.clone()]);
+        let card = &teams["attachments"][0]["content"];
+        assert_eq!(teams["attachments"][0]["contentType"], "application/vnd.microsoft.card.adaptive");
+        assert_eq!(card["type"], "AdaptiveCard");
+        assert_eq!(card["body"][0]["text"], "Alert on SH-01");
+        assert_eq!(card["body"][0]["color"], "Attention");
+        assert_eq!(card["body"][2]["facts"][0]["value"], "critical latency 250ms");
+        assert_eq!(card["body"][2]["facts"][1]["value"], "recovered after 4m 30s");
+
+        let slack = render(WebhookFormat::Slack, "SH-01", &[recovered]);
+        assert_eq!(slack["text"], "Recovered on SH-01");
+        assert_eq!(slack["blocks"][0]["type"], "header");
+        assert_eq!(slack["blocks"][1]["text"]["text"], "• *Gateway*: recovered after 4m 30s");
+
+        let test = render(WebhookFormat::Slack, "SH-01", &[WebhookEvent::test(0)]);
+        assert_eq!(test["text"], "Test webhook from SH-01");
+    }
+}
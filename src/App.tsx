@@ -1,6 +1,7 @@
-import { useEffect, useState, useMemo, useRef, useCallback } from 'react';
+import { useEffect, useState, useMemo, useCallback } from 'react';
 import { Activity, Settings, Play, Pause, RotateCw, Loader2, FileEdit } from 'lucide-react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen, UnlistenFn } from '@tauri-apps/api/event';
 import { useAppStore } from './store/useAppStore';
 import { Dashboard } from './components/Dashboard';
 import { SettingsPanel } from './components/SettingsPanel';
@@ -8,22 +9,19 @@ import { ErrorBoundary } from './components/ErrorBoundary';
 import { cn } from './lib/utils';
 import { useTrayIcon } from './hooks/useTrayIcon';
 import { useSettingsSync } from './hooks/useSettingsSync';
-import { testMultipleEndpoints } from './services/latencyService';
-import { fetchFSLogixPaths, testAllFSLogixPaths } from './services/fslogixService';
+import { fetchFSLogixPaths } from './services/fslogixService';
+import type { ScheduledEndpointResult, ScheduledFSLogixResult } from './types';
 
 function AppContent() {
   const {
     currentView,
     setCurrentView,
-    isMonitoring,
     isPaused,
     setMonitoring,
     setPaused,
-    endpoints,
     config,
     updateLatency,
     endpointStatuses,
-    fslogixStatuses,
     setAllEndpointsLoading,
     setFSLogixPaths,
     updateFSLogixStatus,
@@ -34,11 +32,6 @@ function AppContent() {
   const [isTesting, setIsTesting] = useState(false);
   const [appVersion, setAppVersion] = useState<string>('');
 
-  // Request deduplication: track if a test is currently in progress
-  const isTestingRef = useRef(false);
-  // Track if initial test has been run after endpoints are loaded
-  const hasRunInitialTest = useRef(false);
-
   // Calculate average latency across all endpoints
   const averageLatency = useMemo(() => {
     const latencies = Array.from(endpointStatuses.values())
@@ -53,15 +46,6 @@ function AppContent() {
   useTrayIcon({
     averageLatency,
     thresholds: config.thresholds,
-    notificationsEnabled: config.notificationsEnabled,
-    endpointStatuses, // Pass endpoint statuses for detailed notifications
-    fslogixStatuses, // Pass FSLogix statuses for storage connectivity alerts
-    fslogixEnabled: config.fslogixEnabled, // Whether FSLogix monitoring is enabled
-    isSessionHostMode: config.mode === 'sessionhost', // FSLogix only applies to session hosts
-    alertThreshold: config.alertThreshold, // Consecutive checks before notification
-    fslogixAlertThreshold: config.fslogixAlertThreshold, // Consecutive FSLogix failures before alert
-    alertCooldown: config.alertCooldown, // Minutes between repeated alerts
-    fslogixAlertCooldown: config.fslogixAlertCooldown, // Minutes between repeated FSLogix alerts
   });
 
   // Use settings sync hook to load settings from JSON on startup and watch for changes
@@ -121,98 +105,25 @@ function AppContent() {
     fetchVersion();
   }, []);
 
-  // Request deduplication: track if FSLogix test is currently in progress
-  const isFSLogixTestingRef = useRef(false);
-
-  // Endpoint latency testing function with deduplication
-  // Uses fresh state from the store to avoid stale closure issues after mode switch
-  const runEndpointTests = useCallback(async () => {
-    // Prevent overlapping requests
-    if (isTestingRef.current) {
-      console.log('Endpoint test already in progress, skipping...');
-      return;
-    }
-
-    // Get fresh state from store to avoid stale closure after mode switch
-    const state = useAppStore.getState();
-    const currentEndpoints = state.endpoints;
-    const enabledEndpoints = currentEndpoints.filter((e) => e.enabled);
-
-    if (enabledEndpoints.length === 0) {
-      console.log('[App] No enabled endpoints to test');
-      return;
-    }
-
-    isTestingRef.current = true;
-    setIsTesting(true);
-    setAllEndpointsLoading(true);
-
+  // Ask the backend scheduler to run endpoint and FSLogix tests immediately
+  const runAllTests = useCallback(async () => {
     try {
-      // Test all endpoints in parallel using the latency service
-      const results = await testMultipleEndpoints(enabledEndpoints);
-      // Process results - pass error for proper error message handling
-      results.forEach((result) => {
-        if (result.success) {
-          updateLatency(result.endpointId, result.latency, true);
-        } else {
-          console.error(`Failed to test endpoint ${result.endpointId}:`, result.error);
-          // Pass the error through so the store can create proper error messages
-          updateLatency(result.endpointId, 0, false, result.error);
-        }
-      });
+      await invoke('run_tests_now');
     } catch (error) {
-      // Handle unexpected errors during testing
-      console.error('Unexpected error during endpoint tests:', error);
-      // Mark all endpoints as failed
-      enabledEndpoints.forEach((endpoint) => {
-        updateLatency(endpoint.id, 0, false, error);
-      });
-    } finally {
-      isTestingRef.current = false;
-      setIsTesting(false);
-      setAllEndpointsLoading(false);
-    }
-  }, [updateLatency, setAllEndpointsLoading]);
-
-  // FSLogix connectivity testing function (separate from endpoint tests)
-  const runFSLogixTests = useCallback(async () => {
-    // Prevent overlapping requests
-    if (isFSLogixTestingRef.current) {
-      console.log('FSLogix test already in progress, skipping...');
-      return;
-    }
-
-    // Get fresh state from store
-    const state = useAppStore.getState();
-    const currentFSLogixPaths = state.fslogixPaths;
-    const fslogixEnabled = state.config.fslogixEnabled;
-    const isSessionHostMode = state.config.mode === 'sessionhost';
-
-    // Only test FSLogix in Session Host mode, when enabled, and paths exist
-    if (!isSessionHostMode || !fslogixEnabled || currentFSLogixPaths.length === 0) {
-      return;
+      console.error('Failed to trigger tests:', error);
     }
+  }, []);
 
-    isFSLogixTestingRef.current = true;
-    setAllFSLogixLoading(true);
-
+  // Pause/resume the backend scheduler; the store follows the monitoring-state-changed event
+  const togglePaused = useCallback(async () => {
+    const currentIsPaused = useAppStore.getState().isPaused;
     try {
-      await testAllFSLogixPaths(currentFSLogixPaths, (result) => {
-        updateFSLogixStatus(result.pathId, result.reachable, result.latency, result.error);
-      });
+      await invoke('set_monitoring_paused', { paused: !currentIsPaused });
     } catch (error) {
-      console.error('Unexpected error during FSLogix tests:', error);
-    } finally {
-      isFSLogixTestingRef.current = false;
-      setAllFSLogixLoading(false);
+      console.error('Failed to change monitoring state:', error);
     }
-  }, [updateFSLogixStatus, setAllFSLogixLoading]);
+  }, []);
 
-  // Combined test function for manual "Test Now" button and initial tests
-  const runAllTests = useCallback(async () => {
-    // Run both tests in parallel
-    await Promise.all([runEndpointTests(), runFSLogixTests()]);
-  }, [runEndpointTests, runFSLogixTests]);
   // Log directory info on startup
   useEffect(() => {
     const logDirectoryInfo = async () => {
@@ -242,44 +153,77 @@ function AppContent() {
     loadFSLogixPaths();
   }, [setFSLogixPaths]);
 
-  // Run initial test when endpoints are first loaded
-  // This ensures tests run immediately on app startup, regardless of the test interval
+  // Sync the paused state with the backend scheduler on startup
   useEffect(() => {
-    const enabledEndpoints = endpoints.filter((e) => e.enabled);
-
-    // Only run once when we have real endpoints loaded (more than the default)
-    if (!hasRunInitialTest.current && enabledEndpoints.length > 1 && isMonitoring && !isPaused) {
-      hasRunInitialTest.current = true;
-      console.log('[App] Running initial test for', enabledEndpoints.length, 'endpoints');
-      runAllTests();
-    }
-  }, [endpoints.length, isMonitoring, isPaused, runAllTests]);
+    invoke<boolean>('is_monitoring_paused')
+      .then((paused) => setPaused(paused))
+      .catch((error) => console.error('Failed to read monitoring state:', error));
+  }, [setPaused]);
 
-  // Endpoint latency testing loop (runs on testInterval)
+  // Receive test results from the backend monitoring scheduler
+  // The scheduler owns the test intervals so monitoring continues while the webview is throttled
   useEffect(() => {
-    if (!isMonitoring || isPaused) return;
-
-    // Set up the interval for endpoint tests
-    const interval = setInterval(runEndpointTests, config.testInterval * 1000);
-
-    return () => clearInterval(interval);
-  }, [isMonitoring, isPaused, config.testInterval, runEndpointTests]);
-
-  // FSLogix connectivity testing loop (runs on fslogixTestInterval, separate from endpoints)
-  useEffect(() => {
-    if (!isMonitoring || isPaused) return;
-
-    // Only run FSLogix tests in Session Host mode when enabled
-    const state = useAppStore.getState();
-    if (state.config.mode !== 'sessionhost' || !state.config.fslogixEnabled) {
-      return;
-    }
+    let isMounted = true;
+    const unlistenFunctions: UnlistenFn[] = [];
+
+    const setupListeners = async (): Promise<void> => {
+      const listeners = await Promise.all([
+        listen<'endpoints' | 'fslogix'>('tests-started', (event) => {
+          if (event.payload === 'endpoints') {
+            setIsTesting(true);
+            setAllEndpointsLoading(true);
+          } else {
+            setAllFSLogixLoading(true);
+          }
+        }),
+        listen<ScheduledEndpointResult[]>('endpoint-results', (event) => {
+          event.payload.forEach((result) => {
+            if (result.success) {
+              updateLatency(result.endpointId, result.latency, true, undefined, result.timings);
+            } else {
+              console.error(`Failed to test endpoint ${result.endpointId}:`, result.error);
+              // Pass the error through so the store can create proper error messages
+              updateLatency(result.endpointId, 0, false, result.error);
+            }
+          });
+          setIsTesting(false);
+          setAllEndpointsLoading(false);
+        }),
+        listen<ScheduledFSLogixResult[]>('fslogix-results', (event) => {
+          event.payload.forEach((result) => {
+            updateFSLogixStatus(
+              result.pathId,
+              result.reachable,
+              result.latency,
+              result.error?.message ?? null,
+              result.resolution,
+              result.share
+            );
+          });
+          setAllFSLogixLoading(false);
+        }),
+        listen<{ paused: boolean }>('monitoring-state-changed', (event) => {
+          setPaused(event.payload.paused);
+        }),
+      ]);
+
+      if (isMounted) {
+        unlistenFunctions.push(...listeners);
+      } else {
+        // Component unmounted while setting up, cleanup immediately
+        listeners.forEach((unlisten) => unlisten());
+      }
+    };
 
-    // Set up the interval for FSLogix tests
-    const interval = setInterval(runFSLogixTests, config.fslogixTestInterval * 1000);
+    setupListeners().catch((error) => {
+      console.error('Failed to listen for scheduler events:', error);
+    });
 
-    return () => clearInterval(interval);
-  }, [isMonitoring, isPaused, config.fslogixTestInterval, config.mode, config.fslogixEnabled, runFSLogixTests]);
+    return () => {
+      isMounted = false;
+      unlistenFunctions.forEach((unlisten) => unlisten());
+    };
+  }, [updateLatency, updateFSLogixStatus, setAllEndpointsLoading, setAllFSLogixLoading, setPaused]);
 
   // Watch for pending test trigger (e.g., after mode switch)
   // Use Zustand subscribe to avoid React re-render timing issues
@@ -290,10 +234,7 @@ function AppContent() {
         if (state.pendingTestTrigger && !prevState.pendingTestTrigger) {
           // Clear the trigger immediately
           useAppStore.getState().clearTestTrigger();
-          // Small delay to ensure endpoints are fully loaded in the store
-          setTimeout(() => {
-            runAllTests();
-          }, 100);
+          runAllTests();
         }
       }
     );
@@ -304,32 +245,18 @@ function AppContent() {
     runAllTests();
   }, [runAllTests]);
 
-  // Listen for tray menu events
+  // Listen for tray menu events (pause and "Test Now" are handled by the backend scheduler)
   useEffect(() => {
-    const handleTrayPause = () => {
-      // Get fresh isPaused value from store to avoid stale closure
-      const currentIsPaused = useAppStore.getState().isPaused;
-      setPaused(!currentIsPaused);
-    };
-
-    const handleTrayTest = () => {
-      runAllTests();
-    };
-
     const handleTraySettings = () => {
       setCurrentView('settings');
     };
 
-    window.addEventListener('tray-pause', handleTrayPause);
-    window.addEventListener('tray-test', handleTrayTest);
     window.addEventListener('tray-settings', handleTraySettings);
 
     return () => {
-      window.removeEventListener('tray-pause', handleTrayPause);
-      window.removeEventListener('tray-test', handleTrayTest);
       window.removeEventListener('tray-settings', handleTraySettings);
     };
-  }, [setPaused, runAllTests, setCurrentView]);
+  }, [setCurrentView]);
 
   return (
     <div className="min-h-screen bg-gradient-to-br from-gray-50 to-gray-100 dark:from-gray-900 dark:to-gray-800">
@@ -351,7 +278,7 @@ function AppContent() {
             <div className="flex items-center space-x-2">
               {/* Monitoring Controls */}
               <button
-                onClick={() => setPaused(!isPaused)}
+                onClick={togglePaused}
                 className={cn(
                   'px-3 py-2 rounded-lg transition-colors flex items-center gap-2',
                   isPaused
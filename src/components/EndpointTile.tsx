@@ -1,6 +1,6 @@
 import { Check, AlertTriangle, XCircle, Loader2, WifiOff, Power, CheckCircle, BellOff, Bell } from 'lucide-react';
 import type { Endpoint, EndpointStatus } from '../types';
-import { cn, getStatusColor, getStatusBgColor, isLatencyCritical } from '../lib/utils';
+import { cn, formatProbeTimings, formatSampleStats, getStatusColor, getStatusBgColor, isLatencyCritical } from '../lib/utils';
 import { useAppStore } from '../store/useAppStore';
 import { useMemo, useState, useCallback } from 'react';
 
@@ -160,6 +160,7 @@ export function EndpointTile({ endpoint, status }: EndpointTileProps) {
   const isEnabled = endpoint.enabled;
   const isMuted = endpoint.muted === true;
   const history = status?.history ?? [];
+  const timings = status?.timings ?? null;
   const showLatency = isLatencyCritical(endpoint);
   const isReachable = latency !== null && !error;
 
@@ -317,6 +318,41 @@ export function EndpointTile({ endpoint, status }: EndpointTileProps) {
         </div>
       )}
 
+      {/* Timing breakdown - shows where the time is spent */}
+      {showLatency && timings && !error && !isLoading && (
+        <p
+          className="text-[10px] text-gray-500 dark:text-gray-400 truncate mb-1"
+          title={`Resolved to ${timings.resolvedIp} · Total ${timings.totalMs.toFixed(0)} ms including DNS`}
+        >
+          {formatProbeTimings(timings)}
+        </p>
+      )}
+
+      {/* Multi-sample statistics - jitter and loss matter more for RDP than a single reading */}
+      {showLatency && timings?.samples && !error && !isLoading && (
+        <p
+          className={cn(
+            'text-[10px] truncate mb-1',
+            timings.samples.failures > 0 ? 'text-orange-500 dark:text-orange-400' : 'text-gray-500 dark:text-gray-400'
+          )}
+          title={`${timings.samples.count} samples · min ${timings.samples.minMs.toFixed(0)} · avg ${timings.samples.avgMs.toFixed(0)} · max ${timings.samples.maxMs.toFixed(0)} ms`}
+        >
+          {formatSampleStats(timings.samples)}
+        </p>
+      )}
+
+      {/* Partial failure - some resolved addresses are unreachable */}
+      {timings?.partialFailure && !error && !isLoading && (
+        <p
+          className="text-[10px] text-orange-500 dark:text-orange-400 truncate mb-1"
+          title={timings.addresses
+            .map((a) => `${a.address} (${a.family}): ${a.success ? `${a.connectMs?.toFixed(0)} ms` : a.error?.message}`)
+            .join('\n')}
+        >
+          {timings.addresses.filter((a) => !a.success).length} of {timings.addresses.length} addresses failing
+        </p>
+      )}
+
       {/* Endpoint name */}
       <h3
         className="text-sm font-medium text-gray-900 dark:text-white truncate"
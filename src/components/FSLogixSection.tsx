@@ -1,7 +1,37 @@
-import { Check, XCircle, Loader2, HardDrive, FolderOpen, Bell, BellOff } from 'lucide-react';
+import { Check, XCircle, Loader2, HardDrive, FolderOpen, Bell, BellOff, AlertTriangle } from 'lucide-react';
+import { useEffect, useState } from 'react';
 import { useAppStore } from '../store/useAppStore';
+import { fetchFSLogixConfig } from '../services/fslogixService';
 import { cn } from '../lib/utils';
-import type { FSLogixPath, FSLogixStatus } from '../types';
+import type { FSLogixPath, FSLogixStatus, ShareAccess, StorageExposure } from '../types';
+
+const EXPOSURE_BADGES: Record<StorageExposure, { label: string; className: string }> = {
+  private: {
+    label: 'Private',
+    className: 'bg-green-100 dark:bg-green-900/30 text-green-700 dark:text-green-300',
+  },
+  publicAzure: {
+    label: 'Public Azure',
+    className: 'bg-amber-100 dark:bg-amber-900/30 text-amber-700 dark:text-amber-300',
+  },
+  public: {
+    label: 'Public',
+    className: 'bg-amber-100 dark:bg-amber-900/30 text-amber-700 dark:text-amber-300',
+  },
+  unresolvable: {
+    label: 'No DNS',
+    className: 'bg-red-100 dark:bg-red-900/30 text-red-700 dark:text-red-300',
+  },
+};
+
+const SHARE_ACCESS_LABELS: Record<ShareAccess, string> = {
+  ok: 'Share accessible',
+  hostUnreachable: 'Share host unreachable',
+  shareMissing: 'Share not found',
+  accessDenied: 'Share access denied',
+  error: 'Share check failed',
+  pending: 'Share check still pending',
+};
 
 interface FSLogixTileProps {
   path: FSLogixPath;
@@ -16,6 +46,8 @@ function FSLogixTile({ path, status, onToggleMuted }: FSLogixTileProps) {
   const error = status?.error ?? null;
   const hasBeenTested = status?.lastUpdated !== null && status?.lastUpdated !== undefined;
   const isMuted = path.muted === true;
+  const resolution = status?.resolution ?? null;
+  const share = status?.share ?? null;
 
   // Get status icon
   const getStatusIcon = () => {
@@ -109,6 +141,25 @@ function FSLogixTile({ path, status, onToggleMuted }: FSLogixTileProps) {
         >
           {typeBadge.label}
         </span>
+        {path.cloudCache && (
+          <span
+            className="text-[10px] font-medium px-2 py-0.5 rounded-full uppercase tracking-wide bg-cyan-100 dark:bg-cyan-900/30 text-cyan-700 dark:text-cyan-300"
+            title={path.provider === 'azureBlob' ? 'Cloud Cache - Azure blob provider' : 'Cloud Cache - SMB provider'}
+          >
+            {path.provider === 'azureBlob' ? 'CCD Blob' : 'CCD'}
+          </span>
+        )}
+        {resolution && (
+          <span
+            className={cn(
+              'text-[10px] font-medium px-2 py-0.5 rounded-full uppercase tracking-wide',
+              EXPOSURE_BADGES[resolution.exposure].className
+            )}
+            title={resolution.addresses.join(', ') || undefined}
+          >
+            {EXPOSURE_BADGES[resolution.exposure].label}
+          </span>
+        )}
         {isMuted && (
           <span className="text-[10px] text-yellow-600 dark:text-yellow-400 flex items-center gap-1">
             <BellOff className="w-3 h-3" />
@@ -148,6 +199,32 @@ function FSLogixTile({ path, status, onToggleMuted }: FSLogixTileProps) {
       <p className="text-[10px] text-gray-400 dark:text-gray-500 mt-1 truncate" title={`Testing: ${path.hostname}:${path.port}`}>
         {path.hostname}:{path.port}
       </p>
+
+      {/* Private Endpoint bypassed: the account resolves to a public address */}
+      {resolution?.warning && (
+        <p className="flex items-start gap-1 text-[10px] text-amber-600 dark:text-amber-400 mt-1" title={resolution.warning}>
+          <AlertTriangle className="w-3 h-3 flex-shrink-0 mt-0.5" />
+          <span>Private Endpoint bypassed - resolves publicly</span>
+        </p>
+      )}
+
+      {/* Share-level access (existence and listability of the UNC path) */}
+      {share && (
+        <p
+          className={cn(
+            'flex items-start gap-1 text-[10px] mt-1',
+            share.access === 'ok' ? 'text-green-600 dark:text-green-400' : 'text-red-600 dark:text-red-400'
+          )}
+          title={share.detail ?? undefined}
+        >
+          {share.access === 'ok' ? (
+            <Check className="w-3 h-3 flex-shrink-0 mt-0.5" />
+          ) : (
+            <XCircle className="w-3 h-3 flex-shrink-0 mt-0.5" />
+          )}
+          <span>{SHARE_ACCESS_LABELS[share.access]}</span>
+        </p>
+      )}
     </div>
   );
 }
@@ -158,6 +235,13 @@ export function FSLogixSection() {
   const fslogixEnabled = useAppStore((state) => state.config.fslogixEnabled);
   const mode = useAppStore((state) => state.config.mode);
   const updateFSLogixPathMuted = useAppStore((state) => state.updateFSLogixPathMuted);
+  const [configWarnings, setConfigWarnings] = useState<string[]>([]);
+
+  // Registry misconfigurations (e.g. Cloud Cache overriding VHDLocations)
+  useEffect(() => {
+    if (mode !== 'sessionhost' || !fslogixEnabled) return;
+    fetchFSLogixConfig().then((config) => setConfigWarnings(config?.warnings ?? []));
+  }, [mode, fslogixEnabled, fslogixPaths]);
 
   // Don't render anything if:
   // - Not in Session Host mode (FSLogix is only relevant on AVD session hosts)
@@ -172,6 +256,16 @@ export function FSLogixSection() {
       <h2 className="text-sm font-semibold text-gray-500 dark:text-gray-400 uppercase tracking-wide mb-3">
         FSLogix Storage
       </h2>
+      {configWarnings.length > 0 && (
+        <ul className="mb-3 space-y-1">
+          {configWarnings.map((warning) => (
+            <li key={warning} className="flex items-start gap-1 text-xs text-amber-600 dark:text-amber-400">
+              <AlertTriangle className="w-3.5 h-3.5 flex-shrink-0 mt-0.5" />
+              <span>{warning}</span>
+            </li>
+          ))}
+        </ul>
+      )}
       <div className="grid grid-cols-2 md:grid-cols-3 lg:grid-cols-3 xl:grid-cols-4 gap-4">
         {fslogixPaths.map((path) => {
           const status = fslogixStatuses.get(path.id);
@@ -2,9 +2,11 @@ import { useMemo, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { ArrowLeft, XCircle, Monitor, User, ExternalLink, Plus, Trash2, Edit2, Check, X, Loader2, Wifi, BellOff, Bell, HardDrive, FolderOpen, Settings, Globe, ChevronDown, ChevronUp } from 'lucide-react';
 import { useAppStore } from '../store/useAppStore';
-import type { AppConfig, AppMode, CustomEndpoint } from '../types';
+import type { AppConfig, AppMode, CustomEndpoint, EndpointProtocol, LatencyStatistic, ProbeResult, WebhookDelivery } from '../types';
 import { cn, validateThresholds, validateEndpointUrl } from '../lib/utils';
 import { useSettingsSync } from '../hooks/useSettingsSync';
+import { parseBackendError } from '../errors';
+import { sendTestWebhook } from '../services/webhookService';
 
 export function SettingsPanel() {
   const {
@@ -37,6 +39,11 @@ export function SettingsPanel() {
   const [isTesting, setIsTesting] = useState(false);
   const [testResult, setTestResult] = useState<{ success: boolean; latency?: number; error?: string } | null>(null);
 
+  // Webhook test state
+  const [isTestingWebhooks, setIsTestingWebhooks] = useState(false);
+  const [webhookResults, setWebhookResults] = useState<WebhookDelivery[] | null>(null);
+  const [webhookError, setWebhookError] = useState<string | null>(null);
+
   // Edit mode state for custom endpoints
   const [editingId, setEditingId] = useState<string | null>(null);
   const [editForm, setEditForm] = useState<Partial<CustomEndpoint>>({});
@@ -86,6 +93,21 @@ export function SettingsPanel() {
     }
   };
 
+  // Send a test event to the configured webhooks
+  const handleTestWebhooks = async () => {
+    setIsTestingWebhooks(true);
+    setWebhookResults(null);
+    setWebhookError(null);
+
+    try {
+      setWebhookResults(await sendTestWebhook());
+    } catch (error) {
+      setWebhookError(parseBackendError(error).message);
+    } finally {
+      setIsTestingWebhooks(false);
+    }
+  };
+
   // Test connection to endpoint
   const handleTestConnection = async () => {
     if (!newEndpoint.url) {
@@ -104,16 +126,16 @@ export function SettingsPanel() {
     setUrlError(null);
 
     try {
-      const latency = await invoke<number>('test_latency', {
+      const timings = await invoke<ProbeResult>('test_latency', {
         endpoint: newEndpoint.url,
         port: newEndpoint.port || 443,
         protocol: newEndpoint.protocol || 'tcp',
       });
-      setTestResult({ success: true, latency });
+      setTestResult({ success: true, latency: timings.latencyMs });
     } catch (error) {
       setTestResult({
         success: false,
-        error: error instanceof Error ? error.message : 'Connection failed',
+        error: parseBackendError(error).message,
       });
     } finally {
       setIsTesting(false);
@@ -437,6 +459,157 @@ export function SettingsPanel() {
               </p>
             </div>
 
+            {/* Probe all resolved addresses */}
+            <div className="flex items-center justify-between">
+              <div>
+                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300">
+                  Probe All Addresses
+                </label>
+                <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                  Test every resolved IPv4/IPv6 address and flag endpoints where only some respond
+                </p>
+              </div>
+              <button
+                onClick={() =>
+                  setConfig({ probeAllAddresses: !config.probeAllAddresses })
+                }
+                className={cn(
+                  'relative inline-flex h-6 w-11 items-center rounded-full transition-colors',
+                  config.probeAllAddresses ? 'bg-primary-500' : 'bg-gray-300 dark:bg-gray-600'
+                )}
+              >
+                <span
+                  className={cn(
+                    'inline-block h-4 w-4 transform rounded-full bg-white transition-transform',
+                    config.probeAllAddresses ? 'translate-x-6' : 'translate-x-1'
+                  )}
+                />
+              </button>
+            </div>
+
+            {/* Probe Timeouts */}
+            <div>
+              <div className="grid grid-cols-2 gap-4">
+                <div>
+                  <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                    Probe Timeout (ms)
+                  </label>
+                  <input
+                    type="number"
+                    min="500"
+                    max="60000"
+                    step="500"
+                    value={config.probeTimeoutMs}
+                    onChange={(e) => setConfig({ probeTimeoutMs: Math.max(500, Math.min(60000, parseInt(e.target.value) || 5000)) })}
+                    className="w-full px-3 py-2 bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 rounded-lg text-gray-900 dark:text-white focus:ring-2 focus:ring-primary-500 focus:border-transparent"
+                  />
+                </div>
+                <div>
+                  <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                    HTTP Timeout (ms)
+                  </label>
+                  <input
+                    type="number"
+                    min="500"
+                    max="60000"
+                    step="500"
+                    value={config.httpProbeTimeoutMs}
+                    onChange={(e) => setConfig({ httpProbeTimeoutMs: Math.max(500, Math.min(60000, parseInt(e.target.value) || 10000)) })}
+                    className="w-full px-3 py-2 bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 rounded-lg text-gray-900 dark:text-white focus:ring-2 focus:ring-primary-500 focus:border-transparent"
+                  />
+                </div>
+              </div>
+              <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                Time allowed for each step of a test - DNS, connect, TLS, first response byte (500-60000 ms). HTTP/HTTPS tests use the HTTP timeout. Endpoints can override both with timeoutMs.
+              </p>
+            </div>
+
+            {/* Probe Retries */}
+            <div className="grid grid-cols-2 gap-4">
+              <div>
+                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                  Retries
+                </label>
+                <input
+                  type="number"
+                  min="0"
+                  max="5"
+                  value={config.probeRetries}
+                  onChange={(e) => setConfig({ probeRetries: Math.max(0, Math.min(5, parseInt(e.target.value) || 0)) })}
+                  className="w-full px-3 py-2 bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 rounded-lg text-gray-900 dark:text-white focus:ring-2 focus:ring-primary-500 focus:border-transparent"
+                />
+              </div>
+              <div>
+                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                  Retry Backoff (ms)
+                </label>
+                <input
+                  type="number"
+                  min="0"
+                  max="30000"
+                  step="100"
+                  value={config.probeRetryBackoffMs}
+                  onChange={(e) => setConfig({ probeRetryBackoffMs: Math.max(0, Math.min(30000, parseInt(e.target.value) || 0)) })}
+                  className="w-full px-3 py-2 bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 rounded-lg text-gray-900 dark:text-white focus:ring-2 focus:ring-primary-500 focus:border-transparent"
+                />
+              </div>
+              <p className="col-span-2 text-xs text-gray-500 dark:text-gray-400">
+                Retry failed tests before reporting them (0-5). The backoff doubles after each retry.
+              </p>
+            </div>
+
+            {/* Multi-sample probing */}
+            <div className="grid grid-cols-3 gap-4">
+              <div>
+                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                  Samples
+                </label>
+                <input
+                  type="number"
+                  min="1"
+                  max="20"
+                  value={config.probeSamples}
+                  onChange={(e) => setConfig({ probeSamples: Math.max(1, Math.min(20, parseInt(e.target.value) || 1)) })}
+                  className="w-full px-3 py-2 bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 rounded-lg text-gray-900 dark:text-white focus:ring-2 focus:ring-primary-500 focus:border-transparent"
+                />
+              </div>
+              <div>
+                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                  Interval (ms)
+                </label>
+                <input
+                  type="number"
+                  min="0"
+                  max="5000"
+                  step="50"
+                  value={config.probeSampleIntervalMs}
+                  disabled={config.probeSamples <= 1}
+                  onChange={(e) => setConfig({ probeSampleIntervalMs: Math.max(0, Math.min(5000, parseInt(e.target.value) || 0)) })}
+                  className="w-full px-3 py-2 bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 rounded-lg text-gray-900 dark:text-white focus:ring-2 focus:ring-primary-500 focus:border-transparent disabled:opacity-50"
+                />
+              </div>
+              <div>
+                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                  Statistic
+                </label>
+                <select
+                  value={config.latencyStatistic}
+                  disabled={config.probeSamples <= 1}
+                  onChange={(e) => setConfig({ latencyStatistic: e.target.value as LatencyStatistic })}
+                  className="w-full px-3 py-2 bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 rounded-lg text-gray-900 dark:text-white focus:ring-2 focus:ring-primary-500 focus:border-transparent disabled:opacity-50"
+                >
+                  <option value="min">Min</option>
+                  <option value="avg">Average</option>
+                  <option value="median">Median</option>
+                  <option value="p95">95th percentile</option>
+                  <option value="max">Max</option>
+                </select>
+              </div>
+              <p className="col-span-3 text-xs text-gray-500 dark:text-gray-400">
+                Take several samples per test (1-20) to measure jitter and loss. The statistic drives thresholds, graphs and the tray colour.
+              </p>
+            </div>
+
             {/* Notifications */}
             <div className="flex items-center justify-between">
               <div>
@@ -501,6 +674,144 @@ export function SettingsPanel() {
               </p>
             </div>
 
+            {/* Event Log */}
+            <div className="flex items-center justify-between">
+              <div>
+                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300">
+                  Write to Event Log
+                </label>
+                <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                  Record alerts and recoveries in the Windows Application log (syslog on Linux) for SIEM collection
+                </p>
+              </div>
+              <button
+                onClick={() =>
+                  setConfig({ eventLogEnabled: !config.eventLogEnabled })
+                }
+                className={cn(
+                  'relative inline-flex h-6 w-11 items-center rounded-full transition-colors',
+                  config.eventLogEnabled ? 'bg-primary-500' : 'bg-gray-300 dark:bg-gray-600'
+                )}
+              >
+                <span
+                  className={cn(
+                    'inline-block h-4 w-4 transform rounded-full bg-white transition-transform',
+                    config.eventLogEnabled ? 'translate-x-6' : 'translate-x-1'
+                  )}
+                />
+              </button>
+            </div>
+
+            {/* Prometheus Metrics */}
+            <div className="flex items-center justify-between">
+              <div>
+                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300">
+                  Prometheus Metrics
+                </label>
+                <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                  Serve latency, reachability and alert state on http://{config.metricsBindAddress}:{config.metricsPort}/metrics
+                </p>
+              </div>
+              <button
+                onClick={() =>
+                  setConfig({ metricsEnabled: !config.metricsEnabled })
+                }
+                className={cn(
+                  'relative inline-flex h-6 w-11 items-center rounded-full transition-colors',
+                  config.metricsEnabled ? 'bg-primary-500' : 'bg-gray-300 dark:bg-gray-600'
+                )}
+              >
+                <span
+                  className={cn(
+                    'inline-block h-4 w-4 transform rounded-full bg-white transition-transform',
+                    config.metricsEnabled ? 'translate-x-6' : 'translate-x-1'
+                  )}
+                />
+              </button>
+            </div>
+
+            {/* Metrics Port - only show when metrics are enabled */}
+            {config.metricsEnabled && (
+              <div>
+                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                  Metrics Port
+                </label>
+                <input
+                  type="number"
+                  min="1024"
+                  max="65535"
+                  value={config.metricsPort}
+                  onChange={(e) => setConfig({ metricsPort: Math.max(1024, Math.min(65535, parseInt(e.target.value) || 9469)) })}
+                  className="w-full px-3 py-2 bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 rounded-lg text-gray-900 dark:text-white focus:ring-2 focus:ring-primary-500 focus:border-transparent"
+                />
+              </div>
+            )}
+
+            {/* Metrics Bind Address - only show when metrics are enabled */}
+            {config.metricsEnabled && (
+              <div>
+                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                  Metrics Bind Address
+                </label>
+                <input
+                  type="text"
+                  value={config.metricsBindAddress}
+                  onChange={(e) => setConfig({ metricsBindAddress: e.target.value.trim() })}
+                  className="w-full px-3 py-2 bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 rounded-lg text-gray-900 dark:text-white focus:ring-2 focus:ring-primary-500 focus:border-transparent"
+                />
+                <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                  127.0.0.1 only accepts local scrapers; use 0.0.0.0 (and allow the port in the firewall) for remote scraping. The metrics are not authenticated
+                </p>
+              </div>
+            )}
+
+            {/* Webhooks */}
+            <div>
+              <div className="flex items-center justify-between">
+                <div>
+                  <label className="block text-sm font-medium text-gray-700 dark:text-gray-300">
+                    Webhooks
+                  </label>
+                  <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                    Alerts and recoveries are also posted to the webhooks in settings.json
+                  </p>
+                </div>
+                <button
+                  onClick={handleTestWebhooks}
+                  disabled={isTestingWebhooks}
+                  className="px-3 py-2 bg-gray-100 dark:bg-gray-600 hover:bg-gray-200 dark:hover:bg-gray-500 disabled:bg-gray-100 disabled:dark:bg-gray-700 disabled:cursor-not-allowed text-gray-700 dark:text-gray-300 rounded-lg transition-colors flex items-center justify-center gap-2"
+                  title="Send Test Webhook"
+                >
+                  {isTestingWebhooks ? (
+                    <Loader2 className="w-4 h-4 animate-spin" />
+                  ) : (
+                    <Globe className="w-4 h-4" />
+                  )}
+                  <span className="text-sm">Send Test</span>
+                </button>
+              </div>
+              {webhookError && (
+                <p className="text-xs text-red-500 flex items-center gap-1 mt-2">
+                  <XCircle className="w-3 h-3" />
+                  {webhookError}
+                </p>
+              )}
+              {webhookResults?.map((delivery) => (
+                <p
+                  key={delivery.name}
+                  className={cn(
+                    'text-xs flex items-center gap-1 mt-2',
+                    delivery.success ? 'text-green-600 dark:text-green-400' : 'text-red-500'
+                  )}
+                >
+                  {delivery.success ? <Check className="w-3 h-3" /> : <XCircle className="w-3 h-3" />}
+                  {delivery.name}: {delivery.success
+                    ? `delivered (HTTP ${delivery.status})`
+                    : `${delivery.error ?? 'failed'} after ${delivery.attempts} attempt(s)`}
+                </p>
+              ))}
+            </div>
+
             {/* Graph Time Range */}
             <div>
               <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
@@ -781,12 +1092,21 @@ export function SettingsPanel() {
                   />
                   <select
                     value={newEndpoint.protocol || 'tcp'}
-                    onChange={(e) => setNewEndpoint({ ...newEndpoint, protocol: e.target.value as 'tcp' | 'http' | 'https' })}
+                    onChange={(e) => {
+                      const protocol = e.target.value as EndpointProtocol;
+                      // STUN/TURN servers listen on 3478 and SMB on 445 rather than 443
+                      const defaultPort = protocol === 'stun' ? 3478 : protocol === 'smb' ? 445 : undefined;
+                      const port = defaultPort && (newEndpoint.port ?? 443) === 443 ? defaultPort : newEndpoint.port;
+                      setNewEndpoint({ ...newEndpoint, protocol, port });
+                    }}
                     className="px-3 py-2 bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 rounded-lg text-gray-900 dark:text-white text-sm focus:ring-2 focus:ring-primary-500 focus:border-transparent"
                   >
                     <option value="tcp">TCP</option>
+                    <option value="tls">TLS</option>
                     <option value="http">HTTP</option>
                     <option value="https">HTTPS</option>
+                    <option value="stun">STUN (UDP)</option>
+                    <option value="smb">SMB</option>
                   </select>
                   <button
                     onClick={handleTestConnection}
@@ -1098,6 +1418,36 @@ export function SettingsPanel() {
                 </div>
               )}
 
+              {/* FSLogix Share Check - only show when FSLogix is enabled */}
+              {config.fslogixEnabled && (
+                <div className="flex items-center justify-between">
+                  <div>
+                    <label className="block text-sm font-medium text-gray-700 dark:text-gray-300">
+                      Check Share Access
+                    </label>
+                    <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                      Open and list each share through its full UNC path to detect missing shares and denied access
+                    </p>
+                  </div>
+                  <button
+                    onClick={() =>
+                      setConfig({ fslogixShareCheck: !config.fslogixShareCheck })
+                    }
+                    className={cn(
+                      'relative inline-flex h-6 w-11 items-center rounded-full transition-colors',
+                      config.fslogixShareCheck ? 'bg-purple-500' : 'bg-gray-300 dark:bg-gray-600'
+                    )}
+                  >
+                    <span
+                      className={cn(
+                        'inline-block h-4 w-4 transform rounded-full bg-white transition-transform',
+                        config.fslogixShareCheck ? 'translate-x-6' : 'translate-x-1'
+                      )}
+                    />
+                  </button>
+                </div>
+              )}
+
               {/* Discovered FSLogix Paths - show when enabled and paths exist */}
               {config.fslogixEnabled && fslogixPaths.length > 0 && (
                 <div className="border-t border-gray-200 dark:border-gray-700 pt-3">
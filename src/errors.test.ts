@@ -0,0 +1,37 @@
+import { describe, it, expect } from 'vitest';
+import { ErrorCode, parseBackendError, getUserFriendlyErrorMessage, isProbeError } from './errors';
+
+describe('errors', () => {
+  describe('parseBackendError', () => {
+    it('should use the code of structured probe errors', () => {
+      const error = parseBackendError({ code: 'DNS_NXDOMAIN', message: 'Name does not resolve: x.example' });
+      expect(error.code).toBe(ErrorCode.DNS_NXDOMAIN);
+      expect(error.message).toBe('Name does not resolve: x.example');
+    });
+
+    it('should distinguish firewall blocks from unresolvable names', () => {
+      const timeout = parseBackendError({ code: 'CONNECT_TIMEOUT', message: 'Connection timeout' });
+      const nxdomain = parseBackendError({ code: 'DNS_NXDOMAIN', message: 'Name does not resolve: x.example' });
+      expect(getUserFriendlyErrorMessage(timeout)).not.toBe(getUserFriendlyErrorMessage(nxdomain));
+    });
+
+    it('should map unknown backend codes to a network error', () => {
+      const error = parseBackendError({ code: 'SOMETHING_NEW', message: 'Something new' });
+      expect(error.code).toBe(ErrorCode.NETWORK_ERROR);
+    });
+
+    it('should fall back to message matching for plain strings', () => {
+      expect(parseBackendError('Connection refused').code).toBe(ErrorCode.CONNECTION_REFUSED);
+      expect(parseBackendError('operation timed out').code).toBe(ErrorCode.NETWORK_TIMEOUT);
+    });
+  });
+
+  describe('isProbeError', () => {
+    it('should only accept objects with a code and message', () => {
+      expect(isProbeError({ code: 'HTTP_ERROR', message: 'HTTP error' })).toBe(true);
+      expect(isProbeError('Connection refused')).toBe(false);
+      expect(isProbeError(null)).toBe(false);
+      expect(isProbeError({ message: 'no code' })).toBe(false);
+    });
+  });
+});
@@ -3,6 +3,8 @@
  * Provides structured error handling for both frontend and backend operations
  */
 
+import type { ProbeError } from './types';
+
 // Base error class for all application errors
 export class AppError extends Error {
   readonly code: ErrorCode;
@@ -28,6 +30,22 @@ export enum ErrorCode {
   CONNECTION_REFUSED = 'CONNECTION_REFUSED',
   CONNECTION_RESET = 'CONNECTION_RESET',
 
+  // Probe errors reported by the backend with a stable code
+  DNS_NXDOMAIN = 'DNS_NXDOMAIN',
+  DNS_TIMEOUT = 'DNS_TIMEOUT',
+  CONNECT_TIMEOUT = 'CONNECT_TIMEOUT',
+  NETWORK_UNREACHABLE = 'NETWORK_UNREACHABLE',
+  TLS_FAILURE = 'TLS_FAILURE',
+  CERTIFICATE_INVALID = 'CERTIFICATE_INVALID',
+  HTTP_ERROR = 'HTTP_ERROR',
+  HTTP_TIMEOUT = 'HTTP_TIMEOUT',
+  PROXY_AUTH_REQUIRED = 'PROXY_AUTH_REQUIRED',
+  STUN_TIMEOUT = 'STUN_TIMEOUT',
+  STUN_ERROR = 'STUN_ERROR',
+  SMB_TIMEOUT = 'SMB_TIMEOUT',
+  SMB1_ONLY = 'SMB1_ONLY',
+  SMB_ERROR = 'SMB_ERROR',
+
   // Backend/Tauri errors
   TAURI_INVOKE_FAILED = 'TAURI_INVOKE_FAILED',
   TRAY_ICON_UPDATE_FAILED = 'TRAY_ICON_UPDATE_FAILED',
@@ -89,12 +107,32 @@ export class TauriError extends AppError {
   }
 }
 
-// Parse backend error messages into appropriate error types
+// Type guard for structured probe errors returned by the backend
+export function isProbeError(error: unknown): error is ProbeError {
+  return (
+    typeof error === 'object' &&
+    error !== null &&
+    typeof (error as ProbeError).code === 'string' &&
+    typeof (error as ProbeError).message === 'string'
+  );
+}
+
+function isErrorCode(code: string): code is ErrorCode {
+  return (Object.values(ErrorCode) as string[]).includes(code);
+}
+
+// Parse backend errors into appropriate error types
+// Structured probe errors carry their own code; plain messages fall back to string matching
 export function parseBackendError(error: unknown, endpoint?: string): AppError {
   if (error instanceof AppError) {
     return error;
   }
 
+  if (isProbeError(error)) {
+    const code = isErrorCode(error.code) ? error.code : ErrorCode.NETWORK_ERROR;
+    return new ConnectionError(error.message, code, endpoint);
+  }
+
   const message = error instanceof Error ? error.message : String(error);
   const lowerMessage = message.toLowerCase();
 
@@ -138,6 +176,34 @@ export function getUserFriendlyErrorMessage(error: AppError): string {
       return 'Connection refused';
     case ErrorCode.CONNECTION_RESET:
       return 'Connection reset';
+    case ErrorCode.DNS_NXDOMAIN:
+      return 'Name does not resolve';
+    case ErrorCode.DNS_TIMEOUT:
+      return 'DNS lookup timed out';
+    case ErrorCode.CONNECT_TIMEOUT:
+      return 'No response (blocked by firewall?)';
+    case ErrorCode.NETWORK_UNREACHABLE:
+      return 'Network unreachable';
+    case ErrorCode.TLS_FAILURE:
+      return 'TLS handshake failed';
+    case ErrorCode.CERTIFICATE_INVALID:
+      return 'Untrusted certificate';
+    case ErrorCode.HTTP_ERROR:
+      return 'Invalid HTTP response';
+    case ErrorCode.HTTP_TIMEOUT:
+      return 'HTTP request timed out';
+    case ErrorCode.PROXY_AUTH_REQUIRED:
+      return 'Proxy authentication required';
+    case ErrorCode.STUN_TIMEOUT:
+      return 'No UDP response (Shortpath blocked?)';
+    case ErrorCode.STUN_ERROR:
+      return 'STUN server error';
+    case ErrorCode.SMB_TIMEOUT:
+      return 'No SMB response (port 445 filtered?)';
+    case ErrorCode.SMB1_ONLY:
+      return 'Server only supports SMB1';
+    case ErrorCode.SMB_ERROR:
+      return 'SMB negotiation failed';
     case ErrorCode.NETWORK_ERROR:
       return 'Network error';
     case ErrorCode.TAURI_INVOKE_FAILED:
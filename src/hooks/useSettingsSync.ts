@@ -2,6 +2,10 @@ import { useEffect, useCallback, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useAppStore } from '../store/useAppStore';
 import type { SettingsResponse, AppConfig } from '../types';
+import { fetchLatencyHistory } from '../services/historyService';
+
+// How much history to load into the graphs on startup (24 hours in milliseconds)
+const HISTORY_LOAD_WINDOW_MS = 24 * 60 * 60 * 1000;
 
 /**
  * Hook to synchronize settings between the JSON file and the app store.
@@ -26,6 +30,19 @@ export function useSettingsSync() {
       alertThreshold: response.config.alertThreshold,
       alertCooldown: response.config.alertCooldown,
       graphTimeRange: response.config.graphTimeRange,
+      probeAllAddresses: response.config.probeAllAddresses,
+      probeTimeoutMs: response.config.probeTimeoutMs,
+      httpProbeTimeoutMs: response.config.httpProbeTimeoutMs ?? 10000,
+      probeRetries: response.config.probeRetries,
+      probeRetryBackoffMs: response.config.probeRetryBackoffMs,
+      probeSamples: response.config.probeSamples,
+      probeSampleIntervalMs: response.config.probeSampleIntervalMs,
+      latencyStatistic: response.config.latencyStatistic,
+      fslogixShareCheck: response.config.fslogixShareCheck,
+      eventLogEnabled: response.config.eventLogEnabled,
+      metricsEnabled: response.config.metricsEnabled,
+      metricsPort: response.config.metricsPort,
+      metricsBindAddress: response.config.metricsBindAddress ?? '127.0.0.1',
     };
 
     // Update store with settings from file
@@ -37,8 +54,11 @@ export function useSettingsSync() {
     // Set endpoints from the mode-specific JSON file (enabled/muted are stored directly in the file)
     setEndpoints(response.endpoints);
 
-    // Restore history data from localStorage for the loaded endpoints
-    useAppStore.getState().restoreHistoryForEndpoints(response.endpoints);
+    // Restore history data from the backend history store for the loaded endpoints
+    const now = Date.now();
+    fetchLatencyHistory(now - HISTORY_LOAD_WINDOW_MS, now).then((records) => {
+      useAppStore.getState().restoreHistoryForEndpoints(response.endpoints, records);
+    });
   }, [setConfig, setEndpoints, setModeInfo]);
 
   // Load settings from the backend JSON file (includes resolved endpoints from mode-specific JSON)
@@ -1,5 +1,5 @@
 import { describe, it, expect } from 'vitest';
-import { getLatencyStatus, formatLatency, getStatusColor, getStatusBgColor } from './utils';
+import { getLatencyStatus, formatLatency, formatProbeTimings, formatSampleStats, getStatusColor, getStatusBgColor } from './utils';
 
 describe('utils', () => {
   describe('getLatencyStatus', () => {
@@ -64,4 +64,53 @@ describe('utils', () => {
       expect(getStatusBgColor('unknown')).toBe('bg-gray-500');
     });
   });
+
+  describe('formatProbeTimings', () => {
+    const timings = {
+      dnsMs: 2.4,
+      connectMs: 14.2,
+      tlsMs: null,
+      ttfbMs: null,
+      totalMs: 16.6,
+      latencyMs: 14.2,
+      resolvedIp: '20.50.1.1',
+      addresses: [],
+      partialFailure: false,
+      samples: null,
+    };
+
+    it('should list only the phases that were measured', () => {
+      expect(formatProbeTimings(timings)).toBe('DNS 2 · TCP 14 ms');
+    });
+
+    it('should include TLS and time to first byte for https probes', () => {
+      expect(formatProbeTimings({ ...timings, tlsMs: 21.3, ttfbMs: 40.1 })).toBe(
+        'DNS 2 · TCP 14 · TLS 21 · TTFB 40 ms'
+      );
+    });
+  });
+
+  describe('formatSampleStats', () => {
+    const stats = {
+      count: 5,
+      failures: 0,
+      minMs: 10.2,
+      avgMs: 21.0,
+      medianMs: 19.6,
+      p95Ms: 35.1,
+      maxMs: 35.1,
+      jitterMs: 3.4,
+      failureRatio: 0,
+    };
+
+    it('should show median, p95 and jitter', () => {
+      expect(formatSampleStats(stats)).toBe('med 20 · p95 35 · jitter 3 ms');
+    });
+
+    it('should include the loss when samples failed', () => {
+      expect(formatSampleStats({ ...stats, failures: 1, failureRatio: 0.2 })).toBe(
+        'med 20 · p95 35 · jitter 3 ms · 20% loss'
+      );
+    });
+  });
 });
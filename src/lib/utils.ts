@@ -1,6 +1,6 @@
 import { type ClassValue, clsx } from 'clsx';
 import { twMerge } from 'tailwind-merge';
-import type { Endpoint, LatencyStatus, LatencyThresholds } from '../types';
+import type { Endpoint, LatencyStatus, LatencyThresholds, ProbeResult, SampleStats } from '../types';
 
 export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs));
@@ -153,6 +153,33 @@ export function formatLatency(latency: number | null): string {
   return `${latency.toFixed(1)}ms`;
 }
 
+// Format a probe timing breakdown, e.g. "DNS 2 · TCP 14 · TLS 21 · TTFB 40 ms"
+export function formatProbeTimings(timings: ProbeResult): string {
+  const phases: [string, number | null][] = [
+    ['DNS', timings.dnsMs],
+    ['TCP', timings.connectMs],
+    ['TLS', timings.tlsMs],
+    ['TTFB', timings.ttfbMs],
+  ];
+  const parts = phases
+    .filter((phase): phase is [string, number] => phase[1] !== null)
+    .map(([label, ms]) => `${label} ${ms.toFixed(0)}`);
+  return `${parts.join(' · ')} ms`;
+}
+
+// Format multi-sample statistics, e.g. "med 20 · p95 35 · jitter 3 ms · 20% loss"
+export function formatSampleStats(stats: SampleStats): string {
+  const parts = [
+    `med ${stats.medianMs.toFixed(0)}`,
+    `p95 ${stats.p95Ms.toFixed(0)}`,
+    `jitter ${stats.jitterMs.toFixed(0)} ms`,
+  ];
+  if (stats.failures > 0) {
+    parts.push(`${Math.round(stats.failureRatio * 100)}% loss`);
+  }
+  return parts.join(' · ');
+}
+
 export function formatTimestamp(timestamp: number): string {
   return new Date(timestamp).toLocaleTimeString();
 }
@@ -1,5 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { FSLogixPath } from '../types';
+import type { FSLogixConfig, FSLogixPath, ProbeResult, ProfileLogSummary } from '../types';
+import { isProbeError } from '../errors';
 
 /**
  * FSLogix service for reading storage paths from registry and testing connectivity.
@@ -31,33 +32,57 @@ export async function fetchFSLogixPaths(): Promise<FSLogixPath[]> {
   }
 }
 
+/**
+ * Fetch the FSLogix Profile and ODFC configuration from Windows Registry.
+ * Policy values take precedence over local values.
+ * @returns Effective settings with misconfiguration warnings, null if the call failed
+ */
+export async function fetchFSLogixConfig(): Promise<FSLogixConfig | null> {
+  try {
+    return await invoke<FSLogixConfig>('get_fslogix_config');
+  } catch (error) {
+    console.error('[fslogixService] Failed to fetch FSLogix configuration:', error);
+    return null;
+  }
+}
+
+/**
+ * Summarize recent profile attach/detach sessions from the FSLogix profile logs.
+ * Reads %ProgramData%\FSLogix\Logs\Profile\*.log.
+ * @param hours Optional window in hours (default: 24)
+ * @returns Sessions newest first with their errors and error codes
+ * @throws Error if the logs can't be read
+ */
+export async function fetchProfileSessions(hours?: number): Promise<ProfileLogSummary> {
+  return invoke<ProfileLogSummary>('get_fslogix_profile_sessions', { hours });
+}
+
 /**
  * Test connectivity to a single FSLogix storage path.
- * Uses TCP connection to port 445 (SMB).
+ * Uses TCP connection to port 445 (SMB), or HTTPS for Cloud Cache Azure blob providers.
  * @param path The FSLogix path to test
  * @returns FSLogixTestResult with connectivity status
  */
 export async function testFSLogixConnectivity(path: FSLogixPath): Promise<FSLogixTestResult> {
   try {
-    const latency = await invoke<number>('test_latency', {
+    const timings = await invoke<ProbeResult>('test_latency', {
       endpoint: path.hostname,
       port: path.port,
-      protocol: 'tcp',
+      protocol: path.provider === 'azureBlob' ? 'https' : 'tcp',
     });
 
     return {
       pathId: path.id,
       reachable: true,
-      latency,
+      latency: timings.latencyMs,
       error: null,
     };
   } catch (error) {
-    const errorMessage = error instanceof Error ? error.message : String(error);
     return {
       pathId: path.id,
       reachable: false,
       latency: null,
-      error: errorMessage,
+      error: isProbeError(error) || error instanceof Error ? error.message : String(error),
     };
   }
 }
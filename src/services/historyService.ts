@@ -0,0 +1,40 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { HistoryRecord, Incident } from '../types';
+
+/**
+ * History service for reading probe results from the backend history store.
+ * Abstracts Tauri IPC calls for latency history queries.
+ */
+
+/**
+ * Fetch stored probe results in a time range.
+ * @param from Start of the range (Unix milliseconds, inclusive)
+ * @param to End of the range (Unix milliseconds, inclusive)
+ * @param endpointId Optional endpoint or FSLogix path ID to filter by
+ * @returns Array of HistoryRecord objects ordered by timestamp, empty on error
+ */
+export async function fetchLatencyHistory(
+  from: number,
+  to: number,
+  endpointId?: string
+): Promise<HistoryRecord[]> {
+  try {
+    return await invoke<HistoryRecord[]>('get_latency_history', { endpointId, from, to });
+  } catch (error) {
+    console.error('[historyService] Failed to fetch latency history:', error);
+    return [];
+  }
+}
+
+/**
+ * Fetch the alert incidents kept within the history retention period.
+ * @returns Array of Incident objects, newest first, empty on error
+ */
+export async function fetchIncidents(): Promise<Incident[]> {
+  try {
+    return await invoke<Incident[]>('get_incidents');
+  } catch (error) {
+    console.error('[historyService] Failed to fetch incidents:', error);
+    return [];
+  }
+}
@@ -1,5 +1,8 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { Endpoint, LatencyResult } from '../types';
+import type { DnsProbeResult, Endpoint, EndpointProtocol, LatencyResult, ProbeResult, ShareCheck, SmbProbeResult, StunProbeResult, TlsProbeResult, TracerouteReport } from '../types';
+
+// Per-endpoint timeout and retry overrides (global settings apply where unset)
+type ProbePolicy = Pick<Endpoint, 'timeoutMs' | 'retries' | 'retryBackoffMs'>;
 import { parseBackendError } from '../errors';
 
 /**
@@ -11,16 +14,82 @@ import { parseBackendError } from '../errors';
  * Test latency to a single endpoint.
  * @param endpoint The endpoint URL to test
  * @param port Optional port number (default: 443)
- * @param protocol Optional protocol ('tcp', 'http', 'https') (default: 'tcp')
- * @returns The latency in milliseconds
+ * @param protocol Optional protocol ('tcp', 'tls', 'http', 'https', 'stun') (default: 'tcp')
+ * @param policy Optional timeout and retry overrides
+ * @returns The timing breakdown (DNS, connect, TLS, time to first byte, total)
  * @throws Error if the test fails
  */
 export async function testLatency(
   endpoint: string,
   port?: number,
-  protocol?: 'tcp' | 'http' | 'https'
-): Promise<number> {
-  return invoke<number>('test_latency', { endpoint, port, protocol });
+  protocol?: EndpointProtocol,
+  policy: ProbePolicy = {}
+): Promise<ProbeResult> {
+  return invoke<ProbeResult>('test_latency', { endpoint, port, protocol, ...policy });
+}
+
+/**
+ * Perform a TLS handshake and inspect the presented certificate chain.
+ * @param endpoint The hostname to connect to
+ * @param port Optional port number (default: 443)
+ * @returns Handshake timings and certificate details
+ * @throws Error if the connection or handshake fails
+ */
+export async function inspectTls(endpoint: string, port?: number): Promise<TlsProbeResult> {
+  return invoke<TlsProbeResult>('inspect_tls', { endpoint, port });
+}
+
+/**
+ * Send a STUN Binding request and classify the NAT behaviour (RDP Shortpath reachability).
+ * @param endpoint The STUN/TURN server hostname
+ * @param port Optional port number (default: 3478)
+ * @returns Round trip, reflexive address and NAT behaviour
+ * @throws Error if the server doesn't answer
+ */
+export async function testStun(endpoint: string, port?: number): Promise<StunProbeResult> {
+  return invoke<StunProbeResult>('test_stun', { endpoint, port });
+}
+
+/**
+ * Perform the SMB2/3 NEGOTIATE handshake and report dialect, signing and encryption.
+ * @param endpoint The file server or storage account hostname
+ * @param port Optional port number (default: 445)
+ * @returns Negotiated dialect, security capabilities and handshake time
+ * @throws Error if the server doesn't answer, only speaks SMB1 or rejects the handshake
+ */
+export async function testSmb(endpoint: string, port?: number): Promise<SmbProbeResult> {
+  return invoke<SmbProbeResult>('test_smb', { endpoint, port });
+}
+
+/**
+ * Resolve an endpoint with explicit A/AAAA queries, showing the CNAME chain, TTLs and answering resolver.
+ * @param endpoint The hostname to resolve
+ * @param server Optional DNS server to ask instead of the system resolvers (e.g. '168.63.129.16')
+ * @returns Records, CNAME chain and resolution time
+ * @throws Error if no resolver answers
+ */
+export async function diagnoseDns(endpoint: string, server?: string): Promise<DnsProbeResult> {
+  return invoke<DnsProbeResult>('diagnose_dns', { endpoint, server });
+}
+
+/**
+ * Trace the network path to an endpoint with TCP SYN probes (ICMP echo where TCP hops can't be read).
+ * @param endpoint The destination hostname
+ * @param port Optional destination port (default: 443)
+ * @param maxHops Optional maximum number of hops (default: 30)
+ * @returns Per-hop address, reverse DNS name and round trip
+ * @throws Error if the hostname doesn't resolve
+ */
+export async function traceroute(endpoint: string, port?: number, maxHops?: number): Promise<TracerouteReport> {
+  return invoke<TracerouteReport>('traceroute', { endpoint, port, maxHops });
+}
+
+/**
+ * Open and list the share of every configured FSLogix path through its full UNC path.
+ * @returns Share access result per FSLogix path ID
+ */
+export async function checkFSLogixShares(): Promise<Record<string, ShareCheck>> {
+  return invoke<Record<string, ShareCheck>>('check_fslogix_shares');
 }
 
 /**
@@ -32,12 +101,17 @@ export async function testEndpointLatency(endpoint: Endpoint): Promise<LatencyRe
   const timestamp = Date.now();
 
   try {
-    const latency = await testLatency(endpoint.url, endpoint.port, endpoint.protocol);
+    const timings = await testLatency(endpoint.url, endpoint.port, endpoint.protocol, {
+      timeoutMs: endpoint.timeoutMs,
+      retries: endpoint.retries,
+      retryBackoffMs: endpoint.retryBackoffMs,
+    });
     return {
       endpointId: endpoint.id,
-      latency,
+      latency: timings.latencyMs,
       timestamp,
       success: true,
+      timings,
     };
   } catch (error) {
     const parsedError = parseBackendError(error, endpoint.url);
@@ -0,0 +1,17 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { WebhookDelivery } from '../types';
+
+/**
+ * Webhook service for the outbound alert webhooks configured in settings.json.
+ * Abstracts Tauri IPC calls for webhook deliveries.
+ */
+
+/**
+ * Send a test event to the enabled webhooks, or only to the named webhook.
+ * @param name Optional webhook name, also reaches a disabled webhook
+ * @returns Delivery result per webhook
+ * @throws Error message when no matching webhook is configured
+ */
+export async function sendTestWebhook(name?: string): Promise<WebhookDelivery[]> {
+  return invoke<WebhookDelivery[]>('send_test_webhook', { name });
+}
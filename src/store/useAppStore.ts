@@ -1,7 +1,7 @@
 import { create } from 'zustand';
 import { persist, createJSONStorage } from 'zustand/middleware';
 import { invoke } from '@tauri-apps/api/core';
-import type { Endpoint, AppConfig, EndpointStatus, LatencyThresholds, EndpointError, ModeInfo, CustomEndpoint, FSLogixPath, FSLogixStatus } from '../types';
+import type { Endpoint, AppConfig, EndpointStatus, LatencyThresholds, EndpointError, ModeInfo, CustomEndpoint, FSLogixPath, FSLogixStatus, HistoryRecord, ProbeResult, StorageResolution, ShareCheck } from '../types';
 import { getLatencyStatus } from '../lib/utils';
 import { parseBackendError, getUserFriendlyErrorMessage } from '../errors';
 
@@ -73,6 +73,19 @@ const DEFAULT_CONFIG: AppConfig = {
   fslogixTestInterval: 60,
   fslogixAlertThreshold: 3,
   fslogixAlertCooldown: 5,
+  fslogixShareCheck: false,
+  probeAllAddresses: false,
+  probeTimeoutMs: 5000,
+  httpProbeTimeoutMs: 10000,
+  probeRetries: 0,
+  probeRetryBackoffMs: 1000,
+  probeSamples: 1,
+  probeSampleIntervalMs: 200,
+  latencyStatistic: 'median',
+  eventLogEnabled: false,
+  metricsEnabled: false,
+  metricsPort: 9469,
+  metricsBindAddress: '127.0.0.1',
 };
 
 interface AppState {
@@ -113,7 +126,13 @@ interface AppState {
   removeCustomEndpoint: (id: string) => void;
   setCustomEndpoints: (endpoints: CustomEndpoint[]) => void;
 
-  updateLatency: (endpointId: string, latency: number, success: boolean, error?: unknown) => void;
+  updateLatency: (
+    endpointId: string,
+    latency: number,
+    success: boolean,
+    error?: unknown,
+    timings?: ProbeResult | null
+  ) => void;
   setEndpointLoading: (endpointId: string, isLoading: boolean) => void;
   setAllEndpointsLoading: (isLoading: boolean) => void;
   clearEndpointError: (endpointId: string) => void;
@@ -124,12 +143,19 @@ interface AppState {
 
   getEndpointStatus: (endpointId: string) => EndpointStatus | undefined;
 
-  // Restore history for endpoints (used when loading endpoints from settings.json)
-  restoreHistoryForEndpoints: (endpoints: Endpoint[]) => void;
+  // Restore history for endpoints from the backend history store (used when loading endpoints from settings.json)
+  restoreHistoryForEndpoints: (endpoints: Endpoint[], records: HistoryRecord[]) => void;
 
   // FSLogix actions
   setFSLogixPaths: (paths: FSLogixPath[]) => void;
-  updateFSLogixStatus: (pathId: string, reachable: boolean, latency: number | null, error: string | null) => void;
+  updateFSLogixStatus: (
+    pathId: string,
+    reachable: boolean,
+    latency: number | null,
+    error: string | null,
+    resolution?: StorageResolution | null,
+    share?: ShareCheck | null
+  ) => void;
   setFSLogixLoading: (pathId: string, isLoading: boolean) => void;
   setAllFSLogixLoading: (isLoading: boolean) => void;
   updateFSLogixPathMuted: (pathId: string, muted: boolean) => void;
@@ -138,72 +164,16 @@ interface AppState {
 // Storage key for localStorage
 const STORAGE_KEY = 'avd-health-monitor-state';
 
-// How long to keep history data (24 hours in milliseconds)
-const HISTORY_RETENTION_MS = 24 * 60 * 60 * 1000;
-
-// Serializable history entry for localStorage
-interface SerializedHistory {
-  [endpointId: string]: {
-    history: Array<{ timestamp: number; latency: number }>;
-    lastUpdated: number | null;
-  };
-}
+// Maximum number of samples kept in memory per endpoint for the graphs
+const MAX_HISTORY_SAMPLES = 100;
 
 // Persisted state interface (subset of AppState that we want to persist)
+// Latency history is stored by the backend, not in localStorage
 interface PersistedState {
   config: AppConfig;
   customEndpoints: CustomEndpoint[];
-  historyData?: SerializedHistory;
 }
 
-// Helper to clean up history data older than 24 hours
-const cleanupOldHistory = (
-  history: Array<{ timestamp: number; latency: number }>
-): Array<{ timestamp: number; latency: number }> => {
-  const cutoff = Date.now() - HISTORY_RETENTION_MS;
-  return history.filter((h) => h.timestamp > cutoff);
-};
-
-// Helper to serialize endpointStatuses Map to a plain object for localStorage
-const serializeHistory = (statuses: Map<string, EndpointStatus>): SerializedHistory => {
-  const result: SerializedHistory = {};
-  statuses.forEach((status, endpointId) => {
-    result[endpointId] = {
-      history: cleanupOldHistory(status.history),
-      lastUpdated: status.lastUpdated,
-    };
-  });
-  return result;
-};
-
-// Helper to restore history from localStorage into endpointStatuses Map
-const deserializeHistory = (
-  historyData: SerializedHistory | undefined,
-  endpoints: Endpoint[]
-): Map<string, EndpointStatus> => {
-  const statuses = new Map<string, EndpointStatus>();
-  if (!historyData) return statuses;
-
-  endpoints.forEach((endpoint) => {
-    const saved = historyData[endpoint.id];
-    if (saved && saved.history.length > 0) {
-      const cleanedHistory = cleanupOldHistory(saved.history);
-      if (cleanedHistory.length > 0) {
-        statuses.set(endpoint.id, {
-          endpoint,
-          currentLatency: cleanedHistory[cleanedHistory.length - 1]?.latency ?? null,
-          status: 'unknown',
-          lastUpdated: saved.lastUpdated,
-          history: cleanedHistory,
-          error: null,
-          isLoading: false,
-        });
-      }
-    }
-  });
-  return statuses;
-};
-
 export const useAppStore = create<AppState>()(
   persist(
     (set, get) => ({
@@ -411,7 +381,7 @@ export const useAppStore = create<AppState>()(
         set({ customEndpoints });
       },
 
-      updateLatency: (endpointId, latency, success, error?) =>
+      updateLatency: (endpointId, latency, success, error?, timings?) =>
         set((state) => {
           const endpoint = state.endpoints.find((ep) => ep.id === endpointId);
           if (!endpoint) return state;
@@ -422,7 +392,7 @@ export const useAppStore = create<AppState>()(
 
           const newHistory = success
             ? [
-                ...(currentStatus?.history || []).slice(-100),
+                ...(currentStatus?.history || []).slice(-MAX_HISTORY_SAMPLES),
                 { timestamp, latency },
               ]
             : currentStatus?.history || [];
@@ -446,6 +416,7 @@ export const useAppStore = create<AppState>()(
             history: newHistory,
             error: success ? null : endpointError,
             isLoading: false,
+            timings: success ? timings ?? null : null,
           };
 
           newStatuses.set(endpointId, status);
@@ -473,6 +444,7 @@ export const useAppStore = create<AppState>()(
                 history: [],
                 error: null,
                 isLoading,
+                timings: null,
               };
 
           newStatuses.set(endpointId, status);
@@ -499,6 +471,7 @@ export const useAppStore = create<AppState>()(
                   history: [],
                   error: null,
                   isLoading,
+                  timings: null,
                 };
 
             newStatuses.set(endpoint.id, status);
@@ -534,43 +507,41 @@ export const useAppStore = create<AppState>()(
         return state.endpointStatuses.get(endpointId);
       },
 
-      restoreHistoryForEndpoints: (endpoints) =>
+      restoreHistoryForEndpoints: (endpoints, records) =>
         set((state) => {
-          const storageData = localStorage.getItem(STORAGE_KEY);
-          if (!storageData) return state;
-
-          try {
-            const parsed = JSON.parse(storageData);
-            const historyData = parsed?.state?.historyData as SerializedHistory | undefined;
-            if (!historyData) return state;
-
-            const newStatuses = new Map(state.endpointStatuses);
-
-            endpoints.forEach((endpoint) => {
-              const savedHistory = historyData[endpoint.id];
-              const currentStatus = newStatuses.get(endpoint.id);
-
-              if (savedHistory && savedHistory.history.length > 0) {
-                const cleanedHistory = cleanupOldHistory(savedHistory.history);
-                if (cleanedHistory.length > 0) {
-                  newStatuses.set(endpoint.id, {
-                    endpoint,
-                    currentLatency: currentStatus?.currentLatency ?? cleanedHistory[cleanedHistory.length - 1]?.latency ?? null,
-                    status: currentStatus?.status ?? 'unknown',
-                    lastUpdated: currentStatus?.lastUpdated ?? savedHistory.lastUpdated,
-                    history: currentStatus?.history?.length ? currentStatus.history : cleanedHistory,
-                    error: currentStatus?.error ?? null,
-                    isLoading: currentStatus?.isLoading ?? false,
-                  });
-                }
-              }
-            });
+          if (records.length === 0) return state;
 
-            return { endpointStatuses: newStatuses };
-          } catch (error) {
-            console.error('[useAppStore] Failed to restore history:', error);
-            return state;
-          }
+          // Group successful samples by endpoint (failed probes have no latency to graph)
+          const historyByEndpoint = new Map<string, Array<{ timestamp: number; latency: number }>>();
+          records.forEach((record) => {
+            if (!record.success || record.latency === null) return;
+            const history = historyByEndpoint.get(record.endpointId) ?? [];
+            history.push({ timestamp: record.timestamp, latency: record.latency });
+            historyByEndpoint.set(record.endpointId, history);
+          });
+
+          const newStatuses = new Map(state.endpointStatuses);
+
+          endpoints.forEach((endpoint) => {
+            const savedHistory = (historyByEndpoint.get(endpoint.id) ?? []).slice(-MAX_HISTORY_SAMPLES);
+            const currentStatus = newStatuses.get(endpoint.id);
+
+            if (savedHistory.length > 0) {
+              const lastSample = savedHistory[savedHistory.length - 1];
+              newStatuses.set(endpoint.id, {
+                endpoint,
+                currentLatency: currentStatus?.currentLatency ?? lastSample.latency,
+                status: currentStatus?.status ?? 'unknown',
+                lastUpdated: currentStatus?.lastUpdated ?? lastSample.timestamp,
+                history: currentStatus?.history?.length ? currentStatus.history : savedHistory,
+                error: currentStatus?.error ?? null,
+                isLoading: currentStatus?.isLoading ?? false,
+                timings: currentStatus?.timings ?? null,
+              });
+            }
+          });
+
+          return { endpointStatuses: newStatuses };
         }),
 
       // FSLogix actions
@@ -578,7 +549,7 @@ export const useAppStore = create<AppState>()(
         set({ fslogixPaths: paths });
       },
 
-      updateFSLogixStatus: (pathId, reachable, latency, error) =>
+      updateFSLogixStatus: (pathId, reachable, latency, error, resolution, share) =>
         set((state) => {
           const path = state.fslogixPaths.find((p) => p.id === pathId);
           if (!path) return state;
@@ -600,6 +571,8 @@ export const useAppStore = create<AppState>()(
             isLoading: false,
             lastUpdated: timestamp,
             consecutiveFailures,
+            resolution: resolution ?? currentStatus?.resolution ?? null,
+            share: share ?? null,
           };
 
           newStatuses.set(pathId, status);
@@ -624,6 +597,8 @@ export const useAppStore = create<AppState>()(
                 isLoading,
                 lastUpdated: null,
                 consecutiveFailures: 0,
+                resolution: null,
+                share: null,
               };
 
           newStatuses.set(pathId, status);
@@ -647,6 +622,8 @@ export const useAppStore = create<AppState>()(
                   isLoading,
                   lastUpdated: null,
                   consecutiveFailures: 0,
+                  resolution: null,
+                  share: null,
                 };
 
             newStatuses.set(path.id, status);
@@ -687,7 +664,6 @@ export const useAppStore = create<AppState>()(
       partialize: (state): PersistedState => ({
         config: state.config,
         customEndpoints: state.customEndpoints,
-        historyData: serializeHistory(state.endpointStatuses),
       }),
       merge: (persistedState, currentState) => {
         const persisted = persistedState as PersistedState | undefined;
@@ -698,20 +674,17 @@ export const useAppStore = create<AppState>()(
             : currentState.config,
           customEndpoints: persisted?.customEndpoints ?? [],
           endpoints: currentState.endpoints,
-          endpointStatuses: persisted?.historyData
-            ? deserializeHistory(persisted.historyData, currentState.endpoints)
-            : currentState.endpointStatuses,
         };
       },
-      version: 9,
+      version: 10,
       migrate: (persistedState, version) => {
         const state = persistedState as PersistedState;
-        if (version < 9) {
+        if (version < 10) {
           // Migration to v9: Remove endpoint overrides (now stored in endpoint JSON files)
+          // Migration to v10: Drop localStorage history (now stored by the backend)
           return {
             config: { ...DEFAULT_CONFIG, ...(state.config || {}) },
             customEndpoints: state.customEndpoints || [],
-            historyData: state.historyData || {},
           };
         }
         return state;
@@ -1,3 +1,7 @@
+// Probe protocol: "tls" times the TLS handshake and inspects the certificate chain,
+// "stun" sends a STUN Binding request over UDP (RDP Shortpath), "smb" performs the SMB2/3 NEGOTIATE handshake
+export type EndpointProtocol = 'tcp' | 'tls' | 'http' | 'https' | 'stun' | 'smb';
+
 export interface Endpoint {
   id: string;
   name: string;
@@ -6,13 +10,16 @@ export interface Endpoint {
   enabled: boolean;
   muted?: boolean; // If true, endpoint is monitored but alerts are suppressed
   port?: number; // Default: 443 for TCP
-  protocol?: 'tcp' | 'http' | 'https'; // Default: 'tcp'
+  protocol?: EndpointProtocol; // Default: 'tcp'
   category?: string; // For grouping endpoints (e.g., 'Core AVD', 'Monitoring', 'Certificates')
   required?: boolean; // Whether this endpoint is required or optional
   purpose?: string; // Description of what this endpoint is for
   latencyCritical?: boolean; // If true, show latency in ms; if false, just show reachable/unreachable
   wildcardPattern?: string; // If set, this is a wildcard endpoint (e.g., "*.wvd.microsoft.com")
   knownSubdomains?: string[]; // Known subdomains to test for wildcard endpoints
+  timeoutMs?: number; // Per-probe timeout override (default: AppConfig.probeTimeoutMs)
+  retries?: number; // Retry count override (default: AppConfig.probeRetries)
+  retryBackoffMs?: number; // Retry backoff override (default: AppConfig.probeRetryBackoffMs)
 }
 
 // Application mode - determines which endpoints are loaded
@@ -25,6 +32,51 @@ export interface ModeInfo {
   source?: string;
 }
 
+// Probe failure from the backend, with a stable code matching ErrorCode in errors.ts
+export interface ProbeError {
+  code: string;
+  message: string;
+}
+
+// Connect result for a single resolved address (when all addresses are probed)
+export interface AddressResult {
+  address: string;
+  family: 'ipv4' | 'ipv6';
+  success: boolean;
+  connectMs: number | null;
+  error: ProbeError | null;
+}
+
+// Statistic of a multi-sample probe used as the endpoint latency
+export type LatencyStatistic = 'min' | 'avg' | 'median' | 'p95' | 'max';
+
+// Statistics over the samples of one probe run (all times in milliseconds)
+export interface SampleStats {
+  count: number;
+  failures: number;
+  minMs: number;
+  avgMs: number;
+  medianMs: number;
+  p95Ms: number;
+  maxMs: number;
+  jitterMs: number; // Mean absolute difference between consecutive samples
+  failureRatio: number; // 0.0 - 1.0
+}
+
+// Phase-level timing breakdown of a single probe (all times in milliseconds)
+export interface ProbeResult {
+  dnsMs: number;
+  connectMs: number;
+  tlsMs: number | null; // tls and https probes only
+  ttfbMs: number | null; // Time to first byte, http and https probes only
+  totalMs: number; // Including DNS resolution
+  latencyMs: number; // Total excluding DNS resolution - used for thresholds and graphs
+  resolvedIp: string;
+  addresses: AddressResult[]; // Empty unless all resolved addresses are probed
+  partialFailure: boolean; // Some resolved addresses failed while others succeeded
+  samples: SampleStats | null; // Multi-sample runs only - latencyMs is then the configured statistic
+}
+
 export interface LatencyResult {
   endpointId: string;
   latency: number;
@@ -32,6 +84,133 @@ export interface LatencyResult {
   success: boolean;
   error?: string;
   errorCode?: string;
+  timings?: ProbeResult;
+}
+
+// Endpoint test result emitted by the backend monitoring scheduler ("endpoint-results" event)
+export interface ScheduledEndpointResult {
+  endpointId: string;
+  latency: number;
+  timestamp: number;
+  success: boolean;
+  error: ProbeError | null;
+  timings: ProbeResult | null;
+}
+
+// FSLogix test result emitted by the backend monitoring scheduler ("fslogix-results" event)
+export interface ScheduledFSLogixResult {
+  pathId: string;
+  reachable: boolean;
+  latency: number | null;
+  error: ProbeError | null;
+  resolution: StorageResolution; // Private/public classification of the storage host
+  share: ShareCheck | null; // Set when share checks are enabled
+  timestamp: number;
+}
+
+export type StorageExposure = 'private' | 'publicAzure' | 'public' | 'unresolvable';
+
+// How an FSLogix storage host resolves from this machine
+export interface StorageResolution {
+  exposure: StorageExposure;
+  addresses: string[];
+  privateLink: boolean; // CNAME to a privatelink zone - the account has a Private Endpoint
+  warning: string | null; // Set when a Private Endpoint is bypassed (resolves publicly)
+}
+
+export type ShareAccess = 'ok' | 'hostUnreachable' | 'shareMissing' | 'accessDenied' | 'error' | 'pending';
+
+// Result of opening and listing an FSLogix share through its UNC path
+export interface ShareCheck {
+  access: ShareAccess;
+  detail: string | null; // OS error message when the share could not be listed
+  durationMs: number;
+}
+
+// Certificate presented during a TLS handshake
+export interface CertificateInfo {
+  subject: string;
+  issuer: string;
+  subjectAltNames: string[];
+  notBefore: string; // RFC 3339
+  notAfter: string; // RFC 3339
+  expiresInDays: number;
+}
+
+// Result of a TLS handshake probe (inspect_tls command)
+export interface TlsProbeResult {
+  timings: ProbeResult;
+  protocolVersion: string | null;
+  certificates: CertificateInfo[]; // Leaf certificate first
+  trusted: boolean;
+  trustError: string | null;
+  unexpectedIssuer: boolean; // Chain not issued by Microsoft/DigiCert - likely TLS interception
+}
+
+// NAT mapping behaviour seen by a STUN probe
+export type NatBehavior = 'none' | 'endpointIndependent' | 'addressDependent' | 'unknown';
+
+// Result of a STUN Binding probe (test_stun command)
+export interface StunProbeResult {
+  timings: ProbeResult; // connectMs and latencyMs are the request round trip
+  localAddress: string;
+  reflexiveAddress: string; // Public address seen by the server
+  natBehavior: NatBehavior;
+  shortpathViable: boolean; // False behind a symmetric NAT - Shortpath then needs a TURN relay
+}
+
+export interface DnsRecord {
+  name: string;
+  recordType: 'A' | 'AAAA' | 'CNAME';
+  value: string;
+  ttl: number; // Seconds
+}
+
+// Result of a DNS diagnostic query (diagnose_dns command)
+export interface DnsProbeResult {
+  host: string;
+  server: string; // Resolver that answered (ip:port)
+  systemResolver: boolean; // False when a specific server was queried
+  failedServers: string[]; // Resolvers tried before one answered
+  responseCode: string; // NOERROR, NXDOMAIN, SERVFAIL, ...
+  cnameChain: string[]; // From the host to the canonical name
+  records: DnsRecord[];
+  addresses: string[];
+  resolutionMs: number;
+}
+
+// Result of an SMB NEGOTIATE probe (test_smb command)
+export interface SmbProbeResult {
+  timings: ProbeResult; // ttfbMs is the NEGOTIATE round trip
+  dialect: string; // e.g. "3.1.1"
+  signingEnabled: boolean;
+  signingRequired: boolean;
+  signingAlgorithm: string; // HMAC-SHA256, AES-CMAC or AES-GMAC
+  encryptionSupported: boolean;
+  cipher: string | null; // e.g. "AES-256-GCM"
+}
+
+export type TracerouteMethod = 'tcp' | 'icmp';
+
+export interface TracerouteHop {
+  ttl: number;
+  address: string | null; // null if the hop did not answer
+  hostname: string | null; // Reverse DNS name
+  rttMs: number | null;
+  reached: boolean; // Answer came from the destination
+}
+
+// Result of a traceroute (traceroute command)
+export interface TracerouteReport {
+  host: string;
+  address: string; // Resolved destination address
+  port: number;
+  method: TracerouteMethod;
+  maxHops: number;
+  hops: TracerouteHop[];
+  reached: boolean;
+  note: string | null; // Why the result may be incomplete
+  timestamp: string; // RFC 3339
 }
 
 // Error state for endpoint testing
@@ -64,6 +243,19 @@ export interface AppConfig {
   fslogixTestInterval: number; // Seconds between FSLogix connectivity tests (default: 60)
   fslogixAlertThreshold: number; // Consecutive failures before FSLogix alert (default: 3)
   fslogixAlertCooldown: number; // Minutes between repeated FSLogix alerts (default: 5)
+  fslogixShareCheck: boolean; // Also open and list each FSLogix share (default: false)
+  probeAllAddresses: boolean; // Probe every resolved IPv4/IPv6 address instead of only the first
+  probeTimeoutMs: number; // Timeout for each probe phase - DNS, connect, TLS, first byte (default: 5000)
+  httpProbeTimeoutMs: number; // Timeout for each phase of HTTP/HTTPS probes (default: 10000)
+  probeRetries: number; // Extra attempts before a probe is reported as failed (default: 0)
+  probeRetryBackoffMs: number; // Delay before the first retry, doubled for each further retry (default: 1000)
+  probeSamples: number; // Samples per probe run, 1 = single reading (default: 1)
+  probeSampleIntervalMs: number; // Pause between samples (default: 200)
+  latencyStatistic: LatencyStatistic; // Statistic used as the endpoint latency (default: 'median')
+  eventLogEnabled: boolean; // Write alerts to the Windows Event Log / syslog (default: false)
+  metricsEnabled: boolean; // Serve Prometheus metrics on /metrics (default: false)
+  metricsPort: number; // Port of the /metrics listener (default: 9469)
+  metricsBindAddress: string; // Local address of the /metrics listener (default: '127.0.0.1')
 }
 
 // Custom endpoint added by user (stored in settings.json)
@@ -72,10 +264,13 @@ export interface CustomEndpoint {
   name: string;
   url: string;
   port?: number;
-  protocol?: 'tcp' | 'http' | 'https';
+  protocol?: EndpointProtocol;
   category?: string;
   enabled: boolean;
   latencyCritical?: boolean; // If true, show latency in ms; if false, just show reachable/unreachable
+  timeoutMs?: number;
+  retries?: number;
+  retryBackoffMs?: number;
 }
 
 // JSON settings file structure (stored on disk)
@@ -101,6 +296,40 @@ export interface LatencyHistory {
   }>;
 }
 
+// Probe result from the backend history store (get_latency_history)
+export interface HistoryRecord {
+  endpointId: string; // Endpoint ID or FSLogix path ID
+  timestamp: number;
+  latency: number | null; // null if the probe failed
+  success: boolean;
+  error?: string | null;
+  errorCode?: string | null; // Stable ProbeError code, missing for older records
+}
+
+// Period during which endpoints or FSLogix paths were alerting (get_incidents)
+export interface IncidentTarget {
+  id: string;
+  name: string;
+}
+
+export interface Incident {
+  id: string;
+  source: 'endpoints' | 'fslogix';
+  started: number; // Unix ms of the first failed check
+  ended: number | null; // null while still open
+  affected: IncidentTarget[];
+  worstLatency: number | null;
+}
+
+// Result of delivering an event to one webhook (send_test_webhook)
+export interface WebhookDelivery {
+  name: string;
+  success: boolean;
+  status: number | null; // HTTP status of the last attempt
+  attempts: number;
+  error: string | null;
+}
+
 export type LatencyStatus = 'excellent' | 'good' | 'warning' | 'critical' | 'unknown';
 
 export interface EndpointStatus {
@@ -111,6 +340,7 @@ export interface EndpointStatus {
   history: LatencyHistory['data'];
   error: EndpointError | null; // Current error state if test failed
   isLoading: boolean; // Whether a test is currently running
+  timings: ProbeResult | null; // Timing breakdown of the last successful test
 }
 
 // FSLogix Storage Path from Windows Registry
@@ -121,6 +351,60 @@ export interface FSLogixPath {
   hostname: string;
   port: number;
   muted?: boolean; // If true, alerts are suppressed for this path
+  provider: StorageProvider; // 'azureBlob' is probed over HTTPS on port 443
+  cloudCache: boolean; // From CCDLocations instead of VHDLocations
+}
+
+export type StorageProvider = 'smb' | 'azureBlob';
+
+// Effective settings of one FSLogix container type (policy key merged over local key)
+export interface FSLogixContainerConfig {
+  type: 'profile' | 'odfc';
+  enabled: boolean;
+  vhdLocations: string[];
+  ccdLocations: string[]; // Cloud Cache provider entries, unparsed
+  sizeInMbs: number;
+  volumeType: string; // 'VHD' or 'VHDX'
+  flipFlopProfileDirectoryName: boolean;
+  isDynamic: boolean;
+  policyValues: string[]; // Values set through Group Policy
+}
+
+export type ProfileSessionStatus = 'succeeded' | 'failed' | 'incomplete';
+
+// ERROR line of an FSLogix profile session
+export interface ProfileLogError {
+  time: string; // Local time from the log, e.g. '2026-01-15T09:12:13.651'
+  code: number | null; // Win32 error code
+  message: string;
+}
+
+// One Begin/End Session block of the FSLogix profile log
+export interface ProfileSession {
+  operation: string; // 'Logon' or 'Logoff'
+  user: string | null; // DOMAIN\user
+  sid: string | null;
+  started: string;
+  ended: string | null; // null while running or when the service stopped mid-session
+  status: ProfileSessionStatus;
+  errors: ProfileLogError[];
+  warnings: number;
+  logFile: string;
+}
+
+// Recent profile attach/detach sessions, newest first
+export interface ProfileLogSummary {
+  directory: string;
+  sessions: ProfileSession[];
+  failedSessions: number;
+  warnings: string[]; // Log files that could not be read and were skipped
+}
+
+// FSLogix configuration read from the registry
+export interface FSLogixConfig {
+  profiles: FSLogixContainerConfig;
+  odfc: FSLogixContainerConfig;
+  warnings: string[]; // Misconfigurations, e.g. Enabled without locations
 }
 
 // FSLogix connectivity status
@@ -132,4 +416,6 @@ export interface FSLogixStatus {
   isLoading: boolean;
   lastUpdated: number | null;
   consecutiveFailures: number; // Track consecutive failures for alerting
+  resolution: StorageResolution | null; // Private/public classification from the last test
+  share: ShareCheck | null; // Share access from the last test, when share checks are enabled
 }